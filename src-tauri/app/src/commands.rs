@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 use font_kit::sources::fs::FsSource;
 use portable_pty::ExitStatus;
@@ -10,9 +11,14 @@ use tauri::{Emitter, Window};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_dialog::DialogExt;
 
-use crate::git::build_command_line;
+use crate::git::backend::{Backend, GitBackend};
+use crate::git::build_command_args;
+use crate::git::cancel;
+use crate::git::cat_file::ObjectReader;
 use crate::state::pty::{PtyId, PtyStateMutex};
+use crate::state::requests::RequestsStateMutex;
 use crate::state::stager::StagerStateMutex;
+use crate::state::watcher::WatcherStateMutex;
 use crate::{
     git,
     state::{config::ConfigStateMutex, env::EnvStateMutex, repositories::RepositoriesStateMutex},
@@ -30,7 +36,7 @@ use types::*;
 pub async fn load_persist_data(
     config_state: State<'_, ConfigStateMutex>,
     env_state: State<'_, EnvStateMutex>,
-) -> Result<(Config, Environment), String> {
+) -> Result<(Config, Environment), CommandError> {
     let config = config_state.0.lock().await;
     let env = env_state.0.lock().await;
     Ok((config.config.clone(), env.env.clone()))
@@ -46,7 +52,7 @@ pub async fn load_persist_data(
 pub async fn store_recent_opened(
     new_list: Vec<String>,
     env_state: State<'_, EnvStateMutex>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let mut env = env_state.0.lock().await;
     env.env.recent_opened = new_list.clone();
     Ok(())
@@ -63,7 +69,7 @@ pub async fn store_recent_opened(
 pub async fn store_state(
     new_state: HashMap<String, String>,
     env_state: State<'_, EnvStateMutex>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let mut env = env_state.0.lock().await;
     env.env.state = new_state.clone();
     Ok(())
@@ -83,11 +89,15 @@ pub async fn store_state(
 pub async fn save_config(
     new_config: Config,
     config_state: State<'_, ConfigStateMutex>,
-) -> Result<(), String> {
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<(), CommandError> {
+    let git_backend = new_config.git_backend;
     let mut config = config_state.0.lock().await;
     config
         .save(new_config)
-        .map_err(|e| format!("Failed to save config, {}", e))
+        .map_err(|e| format!("Failed to save config, {}", e))?;
+    repo_state.0.lock().await.set_backend(git_backend);
+    Ok(())
 }
 
 /// Shows a native folder selection dialog.
@@ -113,7 +123,10 @@ pub async fn show_folder_selector<T: Runtime>(
 /// Opens a Git repository and starts watching for changes.
 ///
 /// Initializes the repository in the application state and sets up file watching
-/// to detect changes in the working directory.
+/// to detect changes in the working directory and the repository.
+///
+/// While the repository is opened, `request_reload` event is emitted with `ReloadRequest`
+/// when refs, the index or (not ignored) files in the working tree are changed.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository to open
@@ -123,14 +136,21 @@ pub async fn show_folder_selector<T: Runtime>(
 #[tauri::command]
 pub async fn open_repository<R: Runtime>(
     repo_path: &Path,
+    config_state: State<'_, ConfigStateMutex>,
     repo_state: State<'_, RepositoriesStateMutex>,
     stager_state: State<'_, StagerStateMutex>,
+    watcher_state: State<'_, WatcherStateMutex>,
     app_handle: AppHandle<R>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
+    let git_backend = config_state.0.lock().await.config.git_backend;
     let mut repositories = repo_state.0.lock().await;
-    let repo = repositories.get_or_insert(repo_path);
+    let repo = repositories.get_or_insert(repo_path, git_backend);
     let mut stager = stager_state.0.lock().await;
     stager
+        .watch(app_handle.clone(), repo)
+        .map_err(|e| format!("{}", e))?;
+    let mut watcher = watcher_state.0.lock().await;
+    watcher
         .watch(app_handle, repo)
         .map_err(|e| format!("{}", e))?;
     Ok(())
@@ -138,7 +158,7 @@ pub async fn open_repository<R: Runtime>(
 
 /// Closes a Git repository and stops watching for changes.
 ///
-/// Removes the file watcher for the repository, cleaning up resources.
+/// Removes the file watchers for the repository, and stops background git processes.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository to close
@@ -151,19 +171,96 @@ pub async fn close_repository(
     repo_path: &Path,
     repo_state: State<'_, RepositoriesStateMutex>,
     stager_state: State<'_, StagerStateMutex>,
-) -> Result<(), String> {
+    watcher_state: State<'_, WatcherStateMutex>,
+) -> Result<(), CommandError> {
     let repositories = repo_state.0.lock().await;
     if let Some(repo) = repositories.get(repo_path) {
+        repo.object_reader.stop().await;
+        let mut watcher = watcher_state.0.lock().await;
+        watcher.unwatch(repo);
         let mut stager = stager_state.0.lock().await;
         stager.unwatch(repo).await.map_err(|e| format!("{}", e))?;
     }
     Ok(())
 }
 
+/// Registers a repository created by the app.
+///
+/// It is not added to the recent opened list here, since the list is owned by the frontend
+/// and it is added when the repository is opened.
+async fn register_new_repository(
+    path: &str,
+    config_state: &State<'_, ConfigStateMutex>,
+    repo_state: &State<'_, RepositoriesStateMutex>,
+) -> String {
+    let path = path.replace("\\", "/");
+    let git_backend = config_state.0.lock().await.config.git_backend;
+    repo_state
+        .0
+        .lock()
+        .await
+        .get_or_insert(Path::new(&path), git_backend);
+    path
+}
+
+/// Clones a repository.
+///
+/// Progress is emitted as `remote-progress:{id}` events while running.
+/// The cloned repository is registered, and should be opened by the caller
+/// with the returned path (which adds it to the recent opened list).
+///
+/// # Arguments
+/// * `id` - Identifier used for the name of progress events
+/// * `options` - Clone options. `url` is a URL or a local path, and `path` is the absolute
+///   path of the directory to be created.
+///
+/// # Returns
+/// Path of the cloned repository (with forward slashes).
+///
+/// # Errors
+/// Returns an error if `path` is not absolute, or it already exists and is not empty.
+#[tauri::command]
+pub async fn clone_repository<T: Runtime>(
+    id: usize,
+    options: CloneOptions,
+    config_state: State<'_, ConfigStateMutex>,
+    repo_state: State<'_, RepositoriesStateMutex>,
+    app_handle: AppHandle<T>,
+) -> Result<String, CommandError> {
+    let on_progress = emit_remote_progress(id, app_handle);
+    git::clone::clone(&options, on_progress).await?;
+    Ok(register_new_repository(&options.path, &config_state, &repo_state).await)
+}
+
+/// Creates an empty repository.
+///
+/// The created repository is registered, and should be opened by the caller
+/// with the returned path (except for a bare repository, which can not be opened in the app).
+///
+/// # Arguments
+/// * `options` - Init options. `path` is the absolute path of the repository, which is created
+///   if it does not exist.
+///
+/// # Returns
+/// Path of the created repository (with forward slashes).
+#[tauri::command]
+pub async fn init_repository(
+    options: InitOptions,
+    config_state: State<'_, ConfigStateMutex>,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<String, CommandError> {
+    git::init::init(&options).await?;
+    if options.bare.is_some_and(|v| v) {
+        return Ok(options.path.replace("\\", "/"));
+    }
+    Ok(register_new_repository(&options.path, &config_state, &repo_state).await)
+}
+
 /// Fetches the commit history and references for a repository.
 ///
-/// Retrieves the commit log along with all branches, tags, and reflog entries.
-/// The reflog entries are merged into the refs structure.
+/// Retrieves the commit log along with all branches, tags, reflog entries and stashes.
+/// The reflog entries and stashes are merged into the refs structure.
+/// Helper commits of stashes (index and untracked files) are not included.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
@@ -171,20 +268,62 @@ pub async fn close_repository(
 /// * `reflog_count` - Maximum number of reflog entries to fetch
 ///
 /// # Returns
-/// A tuple containing the list of commits and all references (including reflog).
+/// A tuple containing the list of commits and all references (including reflog and stashes).
 #[tauri::command]
 pub async fn fetch_history(
     repo_path: &Path,
     max_count: u32,
     reflog_count: u32,
-) -> Result<(Vec<Commit>, Refs), String> {
-    let reflog = git::refs::get_reflog(repo_path, reflog_count).await?;
-    let heads = reflog.iter().map(|(_, id)| id.as_str()).collect::<Vec<_>>();
-    let (commits, mut refs) = tokio::try_join!(
-        git::log::log(repo_path, max_count, true, &heads[..]),
-        git::refs::get_refs(&repo_path),
-    )
-    .map_err(|e| <git::GitError as Into<String>>::into(e))?;
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<(Vec<Commit>, Refs), CommandError> {
+    let backend = get_backend(&repo_state, repo_path).await;
+    let (refs, heads, stash_helpers) = get_history_refs(&backend, repo_path, reflog_count).await?;
+    let heads = heads.iter().map(|h| h.as_str()).collect::<Vec<_>>();
+    let mut commits = backend.log(max_count, &heads[..]).await?;
+    git::stash::hide_helper_commits(&mut commits, &stash_helpers);
+    Ok((commits, refs))
+}
+
+/// Gets the backend of the repository, or the CLI backend if the repository is not opened.
+async fn get_backend(repo_state: &State<'_, RepositoriesStateMutex>, repo_path: &Path) -> Backend {
+    match repo_state.0.lock().await.get(repo_path) {
+        Some(repo) => repo.backend.clone(),
+        None => Backend::new(GitBackendKind::Cli, repo_path, ObjectReader::new(repo_path)),
+    }
+}
+
+/// Gets refs including reflog entries, stashes and worktrees, ids of them which are used
+/// as log heads, and ids of helper commits of stashes which are hidden from the history.
+async fn get_history_refs(
+    backend: &Backend,
+    repo_path: &Path,
+    reflog_count: u32,
+) -> Result<(Refs, Vec<String>, HashSet<String>), git::GitError> {
+    let (reflog, stashes, worktrees, mut refs) = tokio::try_join!(
+        git::refs::get_reflog(repo_path, reflog_count),
+        git::stash::list(repo_path),
+        git::worktree::list(repo_path),
+        backend.refs(),
+    )?;
+    // mark branches which are checked out in other worktrees
+    for r in refs.refs.iter_mut() {
+        if let Ref::Branch {
+            fullname, worktree, ..
+        } = r
+        {
+            *worktree = worktrees
+                .iter()
+                .find(|w| !w.current && w.branch.as_ref() == Some(fullname))
+                .map(|w| w.path.clone());
+        }
+    }
+    refs.worktrees = worktrees;
+    let stash_helpers = git::stash::helper_commits(repo_path, &stashes).await?;
+    let heads = reflog
+        .iter()
+        .map(|(_, id)| id.clone())
+        .chain(stashes.iter().map(|s| s.id.clone()))
+        .collect::<Vec<_>>();
     refs.refs.extend(
         reflog
             .into_iter()
@@ -196,7 +335,241 @@ pub async fn fetch_history(
                 name,
             }),
     );
-    Ok((commits, refs))
+    refs.refs.extend(stashes.into_iter().map(|s| {
+        let name = format!("stash@{{{}}}", s.index);
+        Ref::Stash {
+            id: s.id,
+            index: s.index,
+            fullname: name.clone(),
+            name,
+        }
+    }));
+    Ok((refs, heads, stash_helpers))
+}
+
+/// Gets refs and resolves ids of the tips of the history.
+async fn get_history_tips(
+    backend: &Backend,
+    repo_path: &Path,
+    reflog_count: u32,
+) -> Result<(Refs, Vec<String>, HashSet<String>), git::GitError> {
+    let (refs, heads, stash_helpers) = get_history_refs(backend, repo_path, reflog_count).await?;
+    let heads = heads.iter().map(|h| h.as_str()).collect::<Vec<_>>();
+    let tips = git::log::resolve_tips(repo_path, &heads).await?;
+    Ok((refs, tips, stash_helpers))
+}
+
+/// Fetches a page of the commit history.
+///
+/// Without `cursor`, the first page is loaded together with refs (including reflog entries
+/// and stashes). The tips of the history are fixed at the first page, so following pages
+/// requested by `next_cursor` are not shifted even if refs are updated meanwhile.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `max_count` - Maximum number of commits in a page (0 means all commits)
+/// * `reflog_count` - Maximum number of reflog entries to fetch (used only for the first page)
+/// * `cursor` - `next_cursor` of the previous page, or None for the first page
+///
+/// # Returns
+/// Commits in the page, refs (only for the first page), the cursor of the next page
+/// (None if this is the last page), and a token for `fetch_history_update`.
+///
+/// # Errors
+/// Returns an error if `cursor` is invalid.
+#[tauri::command]
+pub async fn fetch_history_page(
+    repo_path: &Path,
+    max_count: u32,
+    reflog_count: u32,
+    cursor: Option<String>,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<HistoryPage, CommandError> {
+    let (cursor, refs, stash_helpers) = match cursor {
+        Some(cursor) => {
+            let stashes = git::stash::list(repo_path).await?;
+            let stash_helpers = git::stash::helper_commits(repo_path, &stashes).await?;
+            (git::log::LogCursor::decode(&cursor)?, None, stash_helpers)
+        }
+        None => {
+            let backend = get_backend(&repo_state, repo_path).await;
+            let (refs, tips, stash_helpers) =
+                get_history_tips(&backend, repo_path, reflog_count).await?;
+            (
+                git::log::LogCursor { skip: 0, tips },
+                Some(refs),
+                stash_helpers,
+            )
+        }
+    };
+    let (mut commits, next_cursor) = git::log::log_page(repo_path, &cursor, max_count).await?;
+    git::stash::hide_helper_commits(&mut commits, &stash_helpers);
+    let refresh_token = git::log::LogCursor {
+        skip: 0,
+        tips: cursor.tips,
+    };
+    Ok(HistoryPage {
+        commits,
+        refs,
+        next_cursor: next_cursor.map(|c| c.encode()),
+        refresh_token: refresh_token.encode(),
+    })
+}
+
+/// Streams the whole commit history.
+///
+/// Commits are emitted as `history-batch:{id}` events (payload: `Commit[]`) every
+/// `batch_size` commits while git is running, in the same order as `fetch_history`.
+///
+/// # Arguments
+/// * `id` - Identifier used for the name of batch events
+/// * `repo_path` - Path to the Git repository
+/// * `batch_size` - Number of commits in each batch
+/// * `reflog_count` - Maximum number of reflog entries to fetch
+///
+/// # Returns
+/// Refs and a token for `fetch_history_update`, after all batches are emitted.
+/// `commits` of the result is always empty.
+#[tauri::command]
+pub async fn stream_history<R: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    batch_size: u32,
+    reflog_count: u32,
+    repo_state: State<'_, RepositoriesStateMutex>,
+    app_handle: AppHandle<R>,
+) -> Result<HistoryPage, CommandError> {
+    let backend = get_backend(&repo_state, repo_path).await;
+    let (refs, tips, stash_helpers) = get_history_tips(&backend, repo_path, reflog_count).await?;
+    let event_name = format!("history-batch:{}", id);
+    git::log::stream_log(repo_path, &tips, batch_size.max(1), |mut commits| {
+        git::stash::hide_helper_commits(&mut commits, &stash_helpers);
+        if let Err(e) = app_handle.emit(event_name.as_str(), commits) {
+            warn!("Failed to emit history-batch event, {}", e);
+        }
+    })
+    .await?;
+    let refresh_token = git::log::LogCursor { skip: 0, tips };
+    Ok(HistoryPage {
+        commits: Vec::new(),
+        refs: Some(refs),
+        next_cursor: None,
+        refresh_token: refresh_token.encode(),
+    })
+}
+
+/// Fetches commits which have become reachable since the last load.
+///
+/// Only commits reachable from current refs but not from the tips of the last load
+/// are loaded. They are ordered so that they can be prepended to the loaded commits.
+/// Commits which have become unreachable are not reported.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `refresh_token` - `refresh_token` of the last load
+/// * `reflog_count` - Maximum number of reflog entries to fetch
+///
+/// # Returns
+/// New commits, current refs and a token for the next update.
+///
+/// # Errors
+/// Returns an error if `refresh_token` is invalid.
+#[tauri::command]
+pub async fn fetch_history_update(
+    repo_path: &Path,
+    refresh_token: String,
+    reflog_count: u32,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<HistoryPage, CommandError> {
+    let known = git::log::LogCursor::decode(&refresh_token)?;
+    let backend = get_backend(&repo_state, repo_path).await;
+    let (refs, tips, stash_helpers) = get_history_tips(&backend, repo_path, reflog_count).await?;
+    let mut commits = git::log::log_since(repo_path, &tips, &known.tips).await?;
+    git::stash::hide_helper_commits(&mut commits, &stash_helpers);
+    let refresh_token = git::log::LogCursor { skip: 0, tips };
+    Ok(HistoryPage {
+        commits,
+        refs: Some(refs),
+        next_cursor: None,
+        refresh_token: refresh_token.encode(),
+    })
+}
+
+/// Searches commits in the commit history.
+///
+/// The history is same as `fetch_history` (all refs, reflog entries and stashes),
+/// so that matched commits can be highlighted in the graph.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `filter` - Conditions of commits (author, committer, date range, message, pickaxe, paths, etc.)
+///   All conditions must be satisfied. The date range is compared with committer dates.
+/// * `reflog_count` - Maximum number of reflog entries used as heads
+/// * `request_id` - Id to cancel the request by `cancel_request`
+///
+/// # Returns
+/// Ids of matched commits, in the same order as `fetch_history`.
+///
+/// # Errors
+/// Returns `GitError::Cancelled` if the request is cancelled or timed out.
+#[tauri::command]
+pub async fn search_history(
+    repo_path: &Path,
+    filter: HistoryFilter,
+    reflog_count: u32,
+    request_id: Option<String>,
+    repo_state: State<'_, RepositoriesStateMutex>,
+    requests_state: State<'_, RequestsStateMutex>,
+) -> Result<Vec<String>, CommandError> {
+    let backend = get_backend(&repo_state, repo_path).await;
+    let (_, heads, stash_helpers) = get_history_refs(&backend, repo_path, reflog_count).await?;
+    let heads = heads.iter().map(|h| h.as_str()).collect::<Vec<_>>();
+    let search = git::log::search(repo_path, &filter, &heads);
+    let mut ids = run_request(&requests_state, request_id, search).await?;
+    ids.retain(|id| !stash_helpers.contains(id));
+    Ok(ids)
+}
+
+/// Timeout of cancellable requests. git commands running longer than this are killed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Runs `f` as a cancellable request if `request_id` is given.
+///
+/// git commands executed in `f` are killed when the request is cancelled by `cancel_request`,
+/// another request with the same id is started, or `REQUEST_TIMEOUT` is elapsed.
+async fn run_request<F: std::future::Future>(
+    requests_state: &State<'_, RequestsStateMutex>,
+    request_id: Option<String>,
+    f: F,
+) -> F::Output {
+    let Some(request_id) = request_id else {
+        return f.await;
+    };
+    let token = requests_state.0.lock().await.register(&request_id);
+    let ret = cancel::scope(token.clone(), Some(REQUEST_TIMEOUT), f).await;
+    requests_state
+        .0
+        .lock()
+        .await
+        .unregister(&request_id, &token);
+    ret
+}
+
+/// Cancels a running request which is started with `request_id`.
+///
+/// git commands of the request are killed, and the request fails with `GitError::Cancelled`.
+///
+/// # Arguments
+/// * `request_id` - Id which is passed to the request
+///
+/// # Returns
+/// `true` if the request was running.
+#[tauri::command]
+pub async fn cancel_request(
+    request_id: &str,
+    requests_state: State<'_, RequestsStateMutex>,
+) -> Result<bool, CommandError> {
+    Ok(requests_state.0.lock().await.cancel(request_id))
 }
 
 /// Gets the name of the currently checked out branch.
@@ -207,7 +580,7 @@ pub async fn fetch_history(
 /// # Returns
 /// The name of the current branch.
 #[tauri::command]
-pub async fn get_current_branch(repo_path: &Path) -> Result<String, String> {
+pub async fn get_current_branch(repo_path: &Path) -> Result<String, CommandError> {
     git::branch::get_current_branch(repo_path)
         .await
         .map_err(|e| e.into())
@@ -224,12 +597,104 @@ pub async fn get_current_branch(repo_path: &Path) -> Result<String, String> {
 /// # Returns
 /// A vector of tuples containing (reference name, commit ID) pairs.
 #[tauri::command]
-pub async fn get_reflog(repo_path: &Path, count: u32) -> Result<Vec<(String, String)>, String> {
+pub async fn get_reflog(
+    repo_path: &Path,
+    count: u32,
+) -> Result<Vec<(String, String)>, CommandError> {
     git::refs::get_reflog(repo_path, count)
         .await
         .map_err(|e| e.into())
 }
 
+/// Gets the list of stashes.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// A vector of stash entries, the newest first.
+#[tauri::command]
+pub async fn get_stash_list(repo_path: &Path) -> Result<Vec<Stash>, CommandError> {
+    Ok(git::stash::list(repo_path).await?)
+}
+
+/// Stashes local changes.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Stash options (message, include untracked files, keep index, pathspecs)
+///
+/// # Errors
+/// Returns an error if there are no local changes to save.
+#[tauri::command]
+pub async fn create_stash(
+    repo_path: &Path,
+    options: CreateStashOptions,
+) -> Result<(), CommandError> {
+    Ok(git::stash::create(repo_path, &options).await?)
+}
+
+/// Applies a stash to the working tree and keeps it in the stash list.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `index` - Index of the stash (`stash@{index}`)
+/// * `restore_index` - If true, also restores the staged changes
+///
+/// # Errors
+/// Returns an error if the stash cannot be applied cleanly.
+#[tauri::command]
+pub async fn apply_stash(
+    repo_path: &Path,
+    index: usize,
+    restore_index: bool,
+) -> Result<(), CommandError> {
+    Ok(git::stash::apply(repo_path, index, restore_index).await?)
+}
+
+/// Applies a stash to the working tree and removes it from the stash list.
+///
+/// The stash is kept if it cannot be applied cleanly.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `index` - Index of the stash (`stash@{index}`)
+/// * `restore_index` - If true, also restores the staged changes
+#[tauri::command]
+pub async fn pop_stash(
+    repo_path: &Path,
+    index: usize,
+    restore_index: bool,
+) -> Result<(), CommandError> {
+    Ok(git::stash::pop(repo_path, index, restore_index).await?)
+}
+
+/// Removes a stash from the stash list.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `index` - Index of the stash (`stash@{index}`)
+#[tauri::command]
+pub async fn drop_stash(repo_path: &Path, index: usize) -> Result<(), CommandError> {
+    Ok(git::stash::drop(repo_path, index).await?)
+}
+
+/// Gets the list of files changed by a stash, including untracked files.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `index` - Index of the stash (`stash@{index}`)
+///
+/// # Returns
+/// A vector of file entries with change statistics, like `get_commit_detail`.
+#[tauri::command]
+pub async fn get_stash_changes(
+    repo_path: &Path,
+    index: usize,
+) -> Result<Vec<FileEntry>, CommandError> {
+    Ok(git::stash::show(repo_path, index).await?)
+}
+
 /// Gets detailed information about a specific commit.
 ///
 /// # Arguments
@@ -239,16 +704,58 @@ pub async fn get_reflog(repo_path: &Path, count: u32) -> Result<Vec<(String, Str
 /// # Returns
 /// Detailed commit information including message, author, date, and changes.
 #[tauri::command]
-pub async fn get_commit_detail(repo_path: &Path, revspec: &str) -> Result<CommitDetail, String> {
+pub async fn get_commit_detail(
+    repo_path: &Path,
+    revspec: &str,
+) -> Result<CommitDetail, CommandError> {
     git::commit_detail::get_commit_detail(repo_path, revspec)
         .await
         .map_err(|e| e.into())
 }
 
+/// Verifies the GPG/SSH signature of a commit.
+///
+/// This is not included in `get_commit_detail`, since gpg or ssh-keygen is run for signed commits.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `revspec` - Git revision specification (commit hash, branch name, etc.)
+///
+/// # Returns
+/// The signature status with signer and key, or `None` if the commit is not signed.
+#[tauri::command]
+pub async fn get_commit_signature(
+    repo_path: &Path,
+    revspec: &str,
+) -> Result<Option<CommitSignature>, CommandError> {
+    git::commit_detail::get_signature(repo_path, revspec)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// Gets branches, remote branches and tags which contain a commit.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `commit_id` - Commit hash
+///
+/// # Returns
+/// Short names of the refs (e.g. `main`, `origin/main`, `v1.0`).
+#[tauri::command]
+pub async fn get_refs_containing(
+    repo_path: &Path,
+    commit_id: &str,
+) -> Result<Vec<String>, CommandError> {
+    git::refs::get_refs_containing(repo_path, commit_id)
+        .await
+        .map_err(|e| e.into())
+}
+
 /// Gets the status of the working tree.
 ///
 /// Returns information about staged and unstaged changes, including file statistics
-/// (number of added/deleted lines) for each modified file.
+/// (number of added/deleted lines) for each modified file, and the operation
+/// (merge, rebase, etc.) in progress.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
@@ -256,10 +763,15 @@ pub async fn get_commit_detail(repo_path: &Path, revspec: &str) -> Result<Commit
 /// # Returns
 /// Working tree status containing files and their change statistics.
 #[tauri::command]
-pub async fn get_workingtree_stat<'a>(repo_path: &'a Path) -> Result<WorkingTreeStat, String> {
-    let (mut files, parent_ids) = tokio::try_join!(
-        git::status::status(repo_path),
+pub async fn get_workingtree_stat<'a>(
+    repo_path: &'a Path,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<WorkingTreeStat, CommandError> {
+    let backend = get_backend(&repo_state, repo_path).await;
+    let (mut files, parent_ids, operation) = tokio::try_join!(
+        backend.status(),
         git::status::get_workingtree_parents(repo_path),
+        git::operation::get_operation_state(repo_path),
     )?;
     let staged_files_exists = files.iter().any(|f| f.kind == WorkingTreeFileKind::Staged);
     let unstaged_files_exists = files
@@ -281,300 +793,1128 @@ pub async fn get_workingtree_stat<'a>(repo_path: &'a Path) -> Result<WorkingTree
         get_numstat_if_needed(repo_path, false, unstaged_files_exists),
     )?;
 
-    for file in files.iter_mut() {
-        file.delta = match file.kind {
-            WorkingTreeFileKind::Staged => staged_numstat.remove(&file.path),
-            WorkingTreeFileKind::Unstaged => unstaged_numstat.remove(&file.path),
-            _ => None,
-        };
-    }
-    Ok(WorkingTreeStat { files, parent_ids })
+    for file in files.iter_mut() {
+        file.delta = match file.kind {
+            WorkingTreeFileKind::Staged => staged_numstat.remove(&file.path),
+            WorkingTreeFileKind::Unstaged => unstaged_numstat.remove(&file.path),
+            _ => None,
+        };
+    }
+    Ok(WorkingTreeStat {
+        files,
+        parent_ids,
+        operation,
+    })
+}
+
+/// Gets blame information for a file.
+///
+/// Returns line-by-line authorship information, commit history for the file,
+/// and the file content.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository
+/// * `revspec` - Git revision specification to blame at
+/// * `request_id` - Id to cancel the request by `cancel_request`
+///
+/// # Returns
+/// Blame data including entries and base64-encoded file content.
+///
+/// # Errors
+/// Returns `GitError::Cancelled` if the request is cancelled or timed out.
+#[tauri::command]
+pub async fn get_blame(
+    repo_path: &Path,
+    rel_path: &str,
+    revspec: &str,
+    request_id: Option<String>,
+    repo_state: State<'_, RepositoriesStateMutex>,
+    requests_state: State<'_, RequestsStateMutex>,
+) -> Result<Blame, CommandError> {
+    let backend = get_backend(&repo_state, repo_path).await;
+    let blame = async {
+        tokio::try_join!(
+            backend.blame(rel_path, revspec),
+            backend.read_content(rel_path, revspec, false)
+        )
+    };
+    let (blame_entries, content) = run_request(&requests_state, request_id, blame).await?;
+    let content_base64 = base64::encode(&content);
+    Ok(Blame {
+        blame_entries,
+        content_base64,
+    })
+}
+
+/// Gets the commit that last modified a file at or before a specified revision.
+///
+/// Finds the most recent commit that modified the specified file, searching from
+/// the given revision backwards through the commit history.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository
+/// * `revspec` - Git revision specification (commit hash, branch name, etc.)
+///
+/// # Returns
+/// The full commit object including ID, author, date, and message, or `None` if no
+/// commits are found that modified the file at or before the specified revision.
+#[tauri::command]
+pub async fn get_last_modify_commit(
+    repo_path: &Path,
+    rel_path: &str,
+    revspec: &str,
+) -> Result<Option<Commit>, CommandError> {
+    git::log::get_last_modify_commit(repo_path, rel_path, revspec)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// Gets the commit history for a specific file.
+///
+/// Returns the history of commits that modified the specified file, including
+/// file statistics (additions/deletions) for each commit. Follows file renames.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository
+/// * `max_count` - Maximum number of commits to retrieve (0 for unlimited)
+/// * `all` - If true, includes all branches, tags, and remotes
+/// * `heads` - List of specific refs to start from (empty for default)
+/// * `request_id` - Id to cancel the request by `cancel_request`
+///
+/// # Returns
+/// List of file log entries, each containing a commit and the file change information.
+///
+/// # Errors
+/// Returns `GitError::Cancelled` if the request is cancelled or timed out.
+#[tauri::command]
+pub async fn get_filelog(
+    repo_path: &Path,
+    rel_path: &str,
+    max_count: u32,
+    all: bool,
+    heads: Vec<&str>,
+    request_id: Option<String>,
+    requests_state: State<'_, RequestsStateMutex>,
+) -> Result<Vec<FileLogEntry>, CommandError> {
+    let filelog = git::log::filelog(repo_path, rel_path, max_count, all, &heads);
+    run_request(&requests_state, request_id, filelog)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// Gets file content at a specific revision, encoded as base64.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository
+/// * `revspec` - Git revision specification to retrieve content from
+///
+/// # Returns
+/// Base64-encoded file content.
+#[tauri::command]
+pub async fn get_content_base64(
+    repo_path: &Path,
+    rel_path: &str,
+    revspec: &str,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<String, CommandError> {
+    let backend = get_backend(&repo_state, repo_path).await;
+    let content = backend.read_content(rel_path, revspec, true).await?;
+    Ok(base64::encode(&content))
+}
+
+/// Searches files with `git grep`.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Pattern, target revision ("STAGED" for the index, "UNSTAGED" for the working tree),
+///   and options such as case, word, pathspecs, maximum number of matches and context lines
+/// * `request_id` - Id to cancel the request by `cancel_request`
+///
+/// # Returns
+/// Matched lines with file path, line number, column and context lines.
+/// `truncated` is true if more matches than `max_count` exist.
+///
+/// # Errors
+/// Returns `GitError::Cancelled` if the request is cancelled or timed out.
+/// Returns `GitError::ArgumentError` if the revision starts with `-`.
+#[tauri::command]
+pub async fn grep(
+    repo_path: &Path,
+    options: GrepOptions,
+    request_id: Option<String>,
+    requests_state: State<'_, RequestsStateMutex>,
+) -> Result<GrepResult, CommandError> {
+    let grep = git::grep::grep(repo_path, &options);
+    Ok(run_request(&requests_state, request_id, grep).await?)
+}
+
+/// Gets the directory tree at a specific revision.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `revspec` - Git revision specification
+///
+/// # Returns
+/// List of entries in the tree (files and directories).
+#[tauri::command]
+pub async fn get_tree(
+    repo_path: &Path,
+    revspec: &str,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<Vec<LstreeEntry>, CommandError> {
+    let backend = get_backend(&repo_state, repo_path).await;
+    Ok(backend.tree(revspec).await?)
+}
+
+/// Gets the file changes between two revisions.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `revspec1` - First Git revision specification
+/// * `revspec2` - Second Git revision specification
+///
+/// # Returns
+/// List of files that changed between the two revisions.
+#[tauri::command]
+pub async fn get_changes_between(
+    repo_path: &Path,
+    revspec1: &str,
+    revspec2: &str,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<Vec<FileEntry>, CommandError> {
+    let backend = get_backend(&repo_state, repo_path).await;
+    Ok(backend.diff(revspec1, revspec2).await?)
+}
+
+/// Gets the file changes introduced by a commit.
+///
+/// Compares the commit with its parent to show what was changed.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `revspec` - Git revision specification
+///
+/// # Returns
+/// List of files changed in the commit.
+///
+/// # Errors
+/// Returns an error if the parent commit is not found.
+#[tauri::command]
+pub async fn get_changes(
+    repo_path: &Path,
+    revspec: &str,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<Vec<FileEntry>, CommandError> {
+    if let Some(parent) =
+        git::rev_parse::rev_parse(repo_path, format!("{}~", revspec).as_str()).await?
+    {
+        let backend = get_backend(&repo_state, repo_path).await;
+        Ok(backend.diff(parent.as_str(), revspec).await?)
+    } else {
+        Err("Parent commit is not found".into())
+    }
+}
+
+/// Gets the unified diff for a file in the working tree, encoded as base64.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository, or `quoted_path` of the file
+/// * `cached` - If true, get diff of staged changes; if false, get unstaged changes
+///
+/// # Returns
+/// Base64-encoded unified diff.
+#[tauri::command]
+pub async fn get_workingtree_udiff_base64(
+    repo_path: &Path,
+    rel_path: &str,
+    cached: bool,
+) -> Result<String, CommandError> {
+    let binary_content = git::diff::get_workingtree_udiff(repo_path, rel_path, cached).await?;
+    Ok(base64::encode(binary_content))
+}
+
+/// Stages files for commit.
+///
+/// Adds the specified files to the staging area (index).
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_paths` - List of relative file paths to stage, or `quoted_path` of files
+#[tauri::command]
+pub async fn stage(repo_path: &Path, rel_paths: Vec<&str>) -> Result<(), CommandError> {
+    Ok(git::workingtree::stage(repo_path, &rel_paths).await?)
+}
+
+/// Unstages files from the staging area.
+///
+/// Removes the specified files from the staging area (index) while keeping
+/// the changes in the working directory.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_paths` - List of relative file paths to unstage, or `quoted_path` of files
+#[tauri::command]
+pub async fn unstage(repo_path: &Path, rel_paths: Vec<&str>) -> Result<(), CommandError> {
+    Ok(git::workingtree::unstage(repo_path, &rel_paths).await?)
+}
+
+/// Restores files to their state in the index.
+///
+/// Discards changes in the working directory for the specified files,
+/// reverting them to their last committed or staged state.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_paths` - List of relative file paths to restore, or `quoted_path` of files
+#[tauri::command]
+pub async fn restore(repo_path: &Path, rel_paths: Vec<&str>) -> Result<(), CommandError> {
+    Ok(git::workingtree::restore(repo_path, &rel_paths).await?)
+}
+
+/// Gets the diff of a file in the working tree, parsed into hunks and lines.
+///
+/// Hunk and line indices of the result are used to select changes for
+/// `stage_hunks`, `unstage_hunks` and `restore_hunks`.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository
+/// * `cached` - If true, get diff of staged changes; if false, get unstaged changes
+///
+/// # Returns
+/// Parsed diff of the file.
+#[tauri::command]
+pub async fn get_workingtree_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    cached: bool,
+) -> Result<FileDiff, CommandError> {
+    Ok(git::workingtree::get_hunks(repo_path, rel_path, cached).await?)
+}
+
+/// Stages selected hunks or lines of a file.
+///
+/// Builds a partial patch from the unstaged diff and applies it to the index.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository
+/// * `selections` - Hunks (and optionally lines in them) to stage
+#[tauri::command]
+pub async fn stage_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    selections: Vec<HunkSelection>,
+) -> Result<(), CommandError> {
+    Ok(git::workingtree::stage_hunks(repo_path, rel_path, &selections).await?)
+}
+
+/// Unstages selected hunks or lines of a file.
+///
+/// Builds a partial patch from the staged diff and applies it to the index in reverse.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository
+/// * `selections` - Hunks (and optionally lines in them) to unstage
+#[tauri::command]
+pub async fn unstage_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    selections: Vec<HunkSelection>,
+) -> Result<(), CommandError> {
+    Ok(git::workingtree::unstage_hunks(repo_path, rel_path, &selections).await?)
+}
+
+/// Discards selected hunks or lines of a file in the working directory.
+///
+/// Builds a partial patch from the unstaged diff and applies it to the working
+/// directory in reverse.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the file within the repository
+/// * `selections` - Hunks (and optionally lines in them) to discard
+#[tauri::command]
+pub async fn restore_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    selections: Vec<HunkSelection>,
+) -> Result<(), CommandError> {
+    Ok(git::workingtree::restore_hunks(repo_path, rel_path, &selections).await?)
+}
+
+/// Gets base, ours and theirs versions (index stages 1, 2 and 3) of an unmerged file.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the unmerged file
+///
+/// # Returns
+/// Base64-encoded contents of each version. A version is absent if the stage does not exist.
+///
+/// # Errors
+/// Returns an error if the file is not unmerged.
+#[tauri::command]
+pub async fn get_conflict_versions(
+    repo_path: &Path,
+    rel_path: &str,
+) -> Result<ConflictVersions, CommandError> {
+    let [base, ours, theirs] = git::conflict::get_versions(repo_path, rel_path).await?;
+    Ok(ConflictVersions {
+        base_base64: base.map(base64::encode),
+        ours_base64: ours.map(base64::encode),
+        theirs_base64: theirs.map(base64::encode),
+    })
+}
+
+/// Parses conflict markers in the working file of an unmerged file.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the unmerged file
+///
+/// # Returns
+/// The content split into common regions and conflict regions.
+///
+/// # Errors
+/// Returns an error if the file is not UTF-8 text. Use the external merge tool for such files.
+#[tauri::command]
+pub async fn get_conflict_regions(
+    repo_path: &Path,
+    rel_path: &str,
+) -> Result<Vec<ConflictRegion>, CommandError> {
+    Ok(git::conflict::get_regions(repo_path, rel_path).await?)
+}
+
+/// Writes the resolved content to an unmerged file, and marks it as resolved.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the unmerged file
+/// * `content` - Resolved content of the file
+///
+/// # Errors
+/// Returns an error if the working file is not UTF-8 text, which would be broken by `content`.
+#[tauri::command]
+pub async fn resolve_conflict(
+    repo_path: &Path,
+    rel_path: &str,
+    content: &str,
+) -> Result<(), CommandError> {
+    Ok(git::conflict::resolve(repo_path, rel_path, content).await?)
+}
+
+/// Marks unmerged files as resolved with the current working files.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_paths` - List of relative paths to mark as resolved
+#[tauri::command]
+pub async fn mark_resolved(repo_path: &Path, rel_paths: Vec<&str>) -> Result<(), CommandError> {
+    Ok(git::conflict::mark_resolved(repo_path, &rel_paths).await?)
+}
+
+/// Resolves an unmerged file by taking ours or theirs wholesale.
+///
+/// If the file is deleted on the chosen side, the file is removed.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the unmerged file
+/// * `side` - Side to take
+#[tauri::command]
+pub async fn take_conflict_side(
+    repo_path: &Path,
+    rel_path: &str,
+    side: ConflictSide,
+) -> Result<(), CommandError> {
+    Ok(git::conflict::take_side(repo_path, rel_path, side).await?)
+}
+
+/// Creates a Git commit with staged changes.
+///
+/// Can create a new commit, amend the last commit, or create a fixup/squash commit
+/// depending on the options.
+///
+/// Output of git (including pre-commit and commit-msg hooks) is emitted
+/// as `command-output:{id}` events line by line while running.
+///
+/// # Arguments
+/// * `id` - Identifier used for the name of output events
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Commit options including the commit message, signing method, author override, etc.
+///
+/// # Returns
+/// The id of the new commit.
+///
+/// # Errors
+/// Returns an error with `SigningFailed` hint if the commit cannot be signed
+/// (e.g. the signing key is not found, or the passphrase is required).
+/// Use `commit_with_pty` to enter the passphrase.
+///
+/// Returns an error of `HookFailed` kind with the name of the hook if a hook rejected the commit.
+/// Set `noVerify` of the options to bypass hooks.
+#[tauri::command]
+pub async fn commit<T: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    options: CommitOptions,
+    app_handle: AppHandle<T>,
+) -> Result<String, CommandError> {
+    let on_output = emit_command_output(id, app_handle);
+    Ok(git::commit::commit(repo_path, &options, on_output).await?)
+}
+
+/// Resolves how the commit will be signed.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `signing` - Signing method specified for the commit (optional)
+///
+/// # Returns
+/// `signing` if specified, otherwise the method determined by `commit.gpgSign` and `gpg.format`.
+#[tauri::command]
+pub async fn get_commit_signing(
+    repo_path: &Path,
+    signing: Option<CommitSigning>,
+) -> Result<CommitSigning, CommandError> {
+    Ok(git::commit::resolve_signing(repo_path, signing).await?)
+}
+
+/// Creates a Git commit in a PTY session.
+///
+/// Used for signed commits, so that gpg (pinentry) or ssh-keygen can prompt for the passphrase
+/// of the signing key. Output is emitted as `pty-data:{id}` events,
+/// and `pty-exit:{id}` is emitted when finished.
+///
+/// # Arguments
+/// * `id` - Unique identifier for this PTY session
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Commit options including the commit message, signing method, author override, etc.
+/// * `rows` - Number of rows in the terminal
+/// * `cols` - Number of columns in the terminal
+#[tauri::command]
+pub async fn commit_with_pty<T: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    options: CommitOptions,
+    rows: u16,
+    cols: u16,
+    pty_state: State<'_, PtyStateMutex>,
+    app_handle: AppHandle<T>,
+) -> Result<(), CommandError> {
+    let args = git::commit::build_command_args_for_pty(repo_path, &options)?;
+    open_pty_internal(id, &args, repo_path, rows, cols, pty_state, app_handle).await
+}
+
+/// Creates a new Git branch.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Branch creation options including name and starting point
+#[tauri::command]
+pub async fn create_branch(
+    repo_path: &Path,
+    options: CreateBranchOptions,
+) -> Result<(), CommandError> {
+    Ok(git::branch::create_branch(repo_path, &options).await?)
+}
+
+/// Deletes a Git branch.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Branch deletion options including name and force flag
+#[tauri::command]
+pub async fn delete_branch(
+    repo_path: &Path,
+    options: DeleteBranchOptions,
+) -> Result<(), CommandError> {
+    Ok(git::branch::delete_branch(repo_path, &options).await?)
+}
+
+/// Renames a branch.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Old and new branch names, and whether to overwrite an existing branch
+#[tauri::command]
+pub async fn rename_branch(
+    repo_path: &Path,
+    options: RenameBranchOptions,
+) -> Result<(), CommandError> {
+    Ok(git::branch::rename_branch(repo_path, &options).await?)
+}
+
+/// Sets or unsets the upstream branch of a branch.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `branch_name` - Name of the local branch
+/// * `upstream` - Upstream branch (e.g., "origin/main"), or `None` to unset the upstream
+#[tauri::command]
+pub async fn set_branch_upstream(
+    repo_path: &Path,
+    branch_name: &str,
+    upstream: Option<String>,
+) -> Result<(), CommandError> {
+    Ok(git::branch::set_upstream(repo_path, branch_name, upstream.as_deref()).await?)
+}
+
+/// Creates a tag.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Tag options. An annotated tag is created if `message` is given or `sign` is true.
+///   If `push_to` is given, the tag is pushed to the remote after it is created.
+#[tauri::command]
+pub async fn create_tag(repo_path: &Path, options: CreateTagOptions) -> Result<(), CommandError> {
+    Ok(git::tag::create_tag(repo_path, &options).await?)
+}
+
+/// Deletes a tag.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Tag options. If `remote` is given, the tag is also deleted from the remote.
+///   The local tag is deleted only if it exists then, so a tag only on the remote can be deleted.
+#[tauri::command]
+pub async fn delete_tag(repo_path: &Path, options: DeleteTagOptions) -> Result<(), CommandError> {
+    Ok(git::tag::delete_tag(repo_path, &options).await?)
+}
+
+/// Lists worktrees of the repository.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository (either the main worktree or a linked worktree)
+///
+/// # Returns
+/// Worktrees, the main worktree first.
+#[tauri::command]
+pub async fn list_worktrees(repo_path: &Path) -> Result<Vec<Worktree>, CommandError> {
+    Ok(git::worktree::list(repo_path).await?)
+}
+
+/// Adds a linked worktree.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Worktree options. `commitish` is checked out at `path`, or a new branch
+///   `new_branch` is created at it. If `lock_reason` is given, the worktree is locked.
+///
+/// # Errors
+/// Returns an error if `path` already exists, or the branch is checked out in another worktree
+/// (unless `force` is true).
+#[tauri::command]
+pub async fn add_worktree(
+    repo_path: &Path,
+    options: AddWorktreeOptions,
+) -> Result<(), CommandError> {
+    Ok(git::worktree::add(repo_path, &options).await?)
+}
+
+/// Removes a linked worktree.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `worktree_path` - Path to the worktree to be removed
+/// * `force` - Removes the worktree even if it has local changes or it is locked
+///
+/// # Errors
+/// Returns an error if the worktree is dirty or locked and `force` is false.
+#[tauri::command]
+pub async fn remove_worktree(
+    repo_path: &Path,
+    worktree_path: &str,
+    force: bool,
+) -> Result<(), CommandError> {
+    Ok(git::worktree::remove(repo_path, worktree_path, force).await?)
+}
+
+/// Prunes administrative files of worktrees whose directories have been deleted.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+#[tauri::command]
+pub async fn prune_worktrees(repo_path: &Path) -> Result<(), CommandError> {
+    Ok(git::worktree::prune(repo_path).await?)
+}
+
+/// Locks or unlocks a linked worktree, so that it is not pruned (e.g. on a removable drive).
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `worktree_path` - Path to the worktree
+/// * `lock` - Locks the worktree if true, otherwise unlocks it
+/// * `reason` - Reason of the lock (optional)
+#[tauri::command]
+pub async fn lock_worktree(
+    repo_path: &Path,
+    worktree_path: &str,
+    lock: bool,
+    reason: Option<String>,
+) -> Result<(), CommandError> {
+    if lock {
+        git::worktree::lock(repo_path, worktree_path, reason.as_deref()).await?;
+    } else {
+        git::worktree::unlock(repo_path, worktree_path).await?;
+    }
+    Ok(())
+}
+
+/// Lists submodules of the repository.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+///
+/// # Returns
+/// Submodules with the commit recorded in the index, the commit checked out and local changes.
+#[tauri::command]
+pub async fn list_submodules(repo_path: &Path) -> Result<Vec<Submodule>, CommandError> {
+    Ok(git::submodule::list(repo_path).await?)
+}
+
+/// Initializes submodules, copying their urls from `.gitmodules` to the repository config.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `paths` - Paths of submodules to be initialized (all submodules if empty)
+#[tauri::command]
+pub async fn init_submodules(repo_path: &Path, paths: Vec<String>) -> Result<(), CommandError> {
+    Ok(git::submodule::init(repo_path, &paths).await?)
+}
+
+/// Clones missing submodules and checks out the commits recorded in the superproject.
+///
+/// Progress is emitted as `remote-progress:{id}` events while running.
+///
+/// # Arguments
+/// * `id` - Identifier used for the name of progress events
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Update options. If `remote` is true, submodules are updated to the latest
+///   commit of their remote-tracking branch instead.
+#[tauri::command]
+pub async fn update_submodules<T: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    options: UpdateSubmoduleOptions,
+    app_handle: AppHandle<T>,
+) -> Result<(), CommandError> {
+    let on_progress = emit_remote_progress(id, app_handle);
+    Ok(git::submodule::update(repo_path, &options, on_progress).await?)
+}
+
+/// Synchronizes urls of submodules with the ones in `.gitmodules`.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `paths` - Paths of submodules to be synchronized (all submodules if empty)
+/// * `recursive` - Also synchronizes nested submodules
+#[tauri::command]
+pub async fn sync_submodules(
+    repo_path: &Path,
+    paths: Vec<String>,
+    recursive: bool,
+) -> Result<(), CommandError> {
+    Ok(git::submodule::sync(repo_path, &paths, recursive).await?)
+}
+
+/// Gets detail of an annotated tag.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `name` - Name of the tag
+///
+/// # Returns
+/// Tagger, date and message of the tag, or `None` for a lightweight tag.
+#[tauri::command]
+pub async fn get_tag_detail(
+    repo_path: &Path,
+    name: &str,
+) -> Result<Option<TagDetail>, CommandError> {
+    Ok(git::tag::get_tag_detail(repo_path, name).await?)
+}
+
+/// Switches to a different branch or commit.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Switch options including target branch/commit
+#[tauri::command]
+pub async fn switch(repo_path: &Path, options: SwitchOptions) -> Result<(), CommandError> {
+    Ok(git::switch::switch(repo_path, &options).await?)
+}
+
+/// Resets the current branch to a specific commit.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `options` - Reset options including target commit and reset mode (soft/mixed/hard)
+#[tauri::command]
+pub async fn reset(repo_path: &Path, options: ResetOptions) -> Result<(), CommandError> {
+    Ok(git::reset::reset(repo_path, &options).await?)
 }
 
-/// Gets blame information for a file.
+/// Merges a commit into the current branch.
 ///
-/// Returns line-by-line authorship information, commit history for the file,
-/// and the file content.
+/// Output of git (including pre-merge-commit and commit-msg hooks) is emitted
+/// as `command-output:{id}` events line by line while running.
 ///
 /// # Arguments
+/// * `id` - Identifier used for the name of output events
 /// * `repo_path` - Path to the Git repository
-/// * `rel_path` - Relative path to the file within the repository
-/// * `revspec` - Git revision specification to blame at
+/// * `options` - Merge options including the commit to merge
 ///
 /// # Returns
-/// Blame data including entries and base64-encoded file content.
+/// The operation state if the merge is still in progress (e.g. stopped by conflicts).
+///
+/// # Errors
+/// Returns an error of `HookFailed` kind with the name of the hook if a hook rejected the merge.
+/// The merge is aborted in that case, so that it can be retried with `noVerify`.
 #[tauri::command]
-pub async fn get_blame(repo_path: &Path, rel_path: &str, revspec: &str) -> Result<Blame, String> {
-    let (blame_entries, content) = tokio::try_join!(
-        git::blame::blame(repo_path, rel_path, revspec),
-        git::file::get_content(repo_path, rel_path, revspec, false)
-    )?;
-    let content_base64 = base64::encode(&content);
-    Ok(Blame {
-        blame_entries,
-        content_base64,
-    })
+pub async fn merge<T: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    options: MergeOptions,
+    app_handle: AppHandle<T>,
+) -> Result<Option<OperationState>, CommandError> {
+    let on_output = emit_command_output(id, app_handle);
+    Ok(git::merge::merge(repo_path, &options, on_output).await?)
 }
 
-/// Gets the commit that last modified a file at or before a specified revision.
-///
-/// Finds the most recent commit that modified the specified file, searching from
-/// the given revision backwards through the commit history.
+/// Rebases the current branch onto another commit.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `rel_path` - Relative path to the file within the repository
-/// * `revspec` - Git revision specification (commit hash, branch name, etc.)
+/// * `options` - Rebase options including the upstream commit
 ///
 /// # Returns
-/// The full commit object including ID, author, date, and message, or `None` if no
-/// commits are found that modified the file at or before the specified revision.
+/// The operation state if the rebase is still in progress (e.g. stopped by conflicts).
 #[tauri::command]
-pub async fn get_last_modify_commit(
+pub async fn rebase(
     repo_path: &Path,
-    rel_path: &str,
-    revspec: &str,
-) -> Result<Option<Commit>, String> {
-    git::log::get_last_modify_commit(repo_path, rel_path, revspec)
-        .await
-        .map_err(|e| e.into())
+    options: RebaseOptions,
+) -> Result<Option<OperationState>, CommandError> {
+    Ok(git::rebase::rebase(repo_path, &options).await?)
 }
 
-/// Gets the commit history for a specific file.
-///
-/// Returns the history of commits that modified the specified file, including
-/// file statistics (additions/deletions) for each commit. Follows file renames.
+/// Gets the todo list of an interactive rebase onto a base commit.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `rel_path` - Relative path to the file within the repository
-/// * `max_count` - Maximum number of commits to retrieve (0 for unlimited)
-/// * `all` - If true, includes all branches, tags, and remotes
-/// * `heads` - List of specific refs to start from (empty for default)
+/// * `base` - Base commit; commits in `base..HEAD` are listed
 ///
 /// # Returns
-/// List of file log entries, each containing a commit and the file change information.
+/// Todo entries (all `pick`), the oldest commit first. Merge commits are excluded.
 #[tauri::command]
-pub async fn get_filelog(
+pub async fn get_rebase_todo(
     repo_path: &Path,
-    rel_path: &str,
-    max_count: u32,
-    all: bool,
-    heads: Vec<&str>,
-) -> Result<Vec<FileLogEntry>, String> {
-    git::log::filelog(repo_path, rel_path, max_count, all, &heads)
-        .await
-        .map_err(|e| e.into())
+    base: &str,
+) -> Result<Vec<RebaseTodoEntry>, CommandError> {
+    Ok(git::rebase::get_todo(repo_path, base).await?)
 }
 
-/// Gets file content at a specific revision, encoded as base64.
+/// Runs an interactive rebase with an edited todo list.
+///
+/// The rebase runs without launching any editor. `reword` entries are
+/// reworded to their `message`, which is ignored for other entries.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `rel_path` - Relative path to the file within the repository
-/// * `revspec` - Git revision specification to retrieve content from
+/// * `options` - Base commit and todo entries
 ///
 /// # Returns
-/// Base64-encoded file content.
+/// The operation state if the rebase is paused by conflicts or `edit` entries.
+///
+/// # Errors
+/// Returns `ArgumentError` if a `reword` entry has no `message`, or a `squash`/`fixup` entry
+/// has no previous commit.
 #[tauri::command]
-pub async fn get_content_base64(
+pub async fn interactive_rebase(
     repo_path: &Path,
-    rel_path: &str,
-    revspec: &str,
-) -> Result<String, String> {
-    let content = git::file::get_content(repo_path, rel_path, revspec, true).await?;
-    Ok(base64::encode(&content))
+    options: InteractiveRebaseOptions,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<Option<OperationState>, CommandError> {
+    let repo = {
+        let repositories = repo_state.0.lock().await;
+        repositories
+            .get(repo_path)
+            .ok_or_else(|| "Repository is not opened".to_owned())?
+            .clone()
+    };
+    Ok(git::rebase::interactive_rebase(&repo, &options).await?)
 }
 
-/// Gets the directory tree at a specific revision.
+/// Applies the changes introduced by commits onto the current branch.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `revspec` - Git revision specification
+/// * `options` - Cherry-pick options including the commits to pick
 ///
 /// # Returns
-/// List of entries in the tree (files and directories).
+/// The operation state if the cherry-pick is still in progress (e.g. stopped by conflicts).
 #[tauri::command]
-pub async fn get_tree(repo_path: &Path, revspec: &str) -> Result<Vec<LstreeEntry>, String> {
-    Ok(git::lstree::lstree(repo_path, revspec).await?)
+pub async fn cherry_pick(
+    repo_path: &Path,
+    options: CherryPickOptions,
+) -> Result<Option<OperationState>, CommandError> {
+    Ok(git::cherry_pick::cherry_pick(repo_path, &options).await?)
 }
 
-/// Gets the file changes between two revisions.
+/// Creates commits which revert the changes introduced by commits.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `revspec1` - First Git revision specification
-/// * `revspec2` - Second Git revision specification
+/// * `options` - Revert options including the commits to revert
 ///
 /// # Returns
-/// List of files that changed between the two revisions.
+/// The operation state if the revert is still in progress (e.g. stopped by conflicts).
 #[tauri::command]
-pub async fn get_changes_between(
+pub async fn revert(
     repo_path: &Path,
-    revspec1: &str,
-    revspec2: &str,
-) -> Result<Vec<FileEntry>, String> {
-    Ok(git::diff::get_changes_between(repo_path, revspec1, revspec2).await?)
+    options: RevertOptions,
+) -> Result<Option<OperationState>, CommandError> {
+    Ok(git::revert::revert(repo_path, &options).await?)
 }
 
-/// Gets the file changes introduced by a commit.
-///
-/// Compares the commit with its parent to show what was changed.
+/// Gets the operation (merge, rebase, cherry-pick, revert or bisect) in progress.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `revspec` - Git revision specification
 ///
 /// # Returns
-/// List of files changed in the commit.
-///
-/// # Errors
-/// Returns an error if the parent commit is not found.
+/// The operation state, or `None` if no operation is in progress.
 #[tauri::command]
-pub async fn get_changes(repo_path: &Path, revspec: &str) -> Result<Vec<FileEntry>, String> {
-    if let Some(parent) =
-        git::rev_parse::rev_parse(repo_path, format!("{}~", revspec).as_str()).await?
-    {
-        Ok(git::diff::get_changes_between(repo_path, parent.as_str(), revspec).await?)
-    } else {
-        Err("Parent commit is not found".to_owned())
-    }
+pub async fn get_operation_state(repo_path: &Path) -> Result<Option<OperationState>, CommandError> {
+    Ok(git::operation::get_operation_state(repo_path).await?)
 }
 
-/// Gets the unified diff for a file in the working tree, encoded as base64.
+/// Continues, skips or aborts the operation in progress.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `rel_path` - Relative path to the file within the repository
-/// * `cached` - If true, get diff of staged changes; if false, get unstaged changes
+/// * `action` - Action to run, which must be one of `actions` of the current operation state
 ///
 /// # Returns
-/// Base64-encoded unified diff.
+/// The operation state after the action, or `None` if the operation has finished.
+///
+/// # Errors
+/// Returns an error if no operation is in progress, or the action is not available.
 #[tauri::command]
-pub async fn get_workingtree_udiff_base64(
+pub async fn run_operation_action(
     repo_path: &Path,
-    rel_path: &str,
-    cached: bool,
-) -> Result<String, String> {
-    let binary_content = git::diff::get_workingtree_udiff(repo_path, rel_path, cached).await?;
-    Ok(base64::encode(binary_content))
+    action: OperationAction,
+) -> Result<Option<OperationState>, CommandError> {
+    Ok(git::operation::run_action(repo_path, action).await?)
 }
 
-/// Stages files for commit.
-///
-/// Adds the specified files to the staging area (index).
+/// Starts bisect, and marks the specified bad and good commits.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `rel_paths` - List of relative file paths to stage
+/// * `bad` - Commit in which the problem exists (optional)
+/// * `good` - Commits in which the problem does not exist
+///
+/// # Returns
+/// The commit checked out to be tested next and the number of remaining revisions,
+/// once both bad and good commits are known.
 #[tauri::command]
-pub async fn stage(repo_path: &Path, rel_paths: Vec<&str>) -> Result<(), String> {
-    Ok(git::workingtree::stage(repo_path, &rel_paths).await?)
+pub async fn start_bisect(
+    repo_path: &Path,
+    bad: Option<String>,
+    good: Vec<String>,
+) -> Result<BisectStep, CommandError> {
+    Ok(git::bisect::start(repo_path, bad.as_deref(), &good).await?)
 }
 
-/// Unstages files from the staging area.
-///
-/// Removes the specified files from the staging area (index) while keeping
-/// the changes in the working directory.
+/// Marks commits as good, bad or skipped while bisecting.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `rel_paths` - List of relative file paths to unstage
+/// * `verdict` - Good, bad or skip
+/// * `revs` - Commits to be marked (the current commit if empty)
+///
+/// # Returns
+/// The commit to be tested next, or the first bad commit if it has been found.
+/// If only skipped commits are left, they are returned as `possibleFirstBad`.
 #[tauri::command]
-pub async fn unstage(repo_path: &Path, rel_paths: Vec<&str>) -> Result<(), String> {
-    Ok(git::workingtree::unstage(repo_path, &rel_paths).await?)
+pub async fn mark_bisect(
+    repo_path: &Path,
+    verdict: BisectVerdict,
+    revs: Vec<String>,
+) -> Result<BisectStep, CommandError> {
+    Ok(git::bisect::mark(repo_path, verdict, &revs).await?)
 }
 
-/// Restores files to their state in the index.
+/// Runs `git bisect run` with a script in a PTY session.
 ///
-/// Discards changes in the working directory for the specified files,
-/// reverting them to their last committed or staged state.
+/// Output is emitted as `pty-data:{id}` events, and `pty-exit:{id}` is emitted when finished.
 ///
 /// # Arguments
+/// * `id` - Unique identifier for this PTY session
 /// * `repo_path` - Path to the Git repository
-/// * `rel_paths` - List of relative file paths to restore
+/// * `command_line` - Script and its arguments, which exits with 0 if the commit is good,
+///   125 if it cannot be tested, and other codes (1-127) if it is bad
+/// * `rows` - Number of rows in the terminal
+/// * `cols` - Number of columns in the terminal
 #[tauri::command]
-pub async fn restore(repo_path: &Path, rel_paths: Vec<&str>) -> Result<(), String> {
-    Ok(git::workingtree::restore(repo_path, &rel_paths).await?)
+pub async fn run_bisect<T: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    command_line: &str,
+    rows: u16,
+    cols: u16,
+    pty_state: State<'_, PtyStateMutex>,
+    app_handle: AppHandle<T>,
+) -> Result<(), CommandError> {
+    let script = crate::platform::split_commandline(command_line)
+        .map_err(|e| format!("Failed to parse command line: {}", e))?;
+    if script.is_empty() {
+        return Err("Command line is empty".into());
+    }
+    let mut args = vec!["run"];
+    args.extend(script.iter().map(|a| a.as_str()));
+    let git_args = build_command_args(Some(repo_path), "bisect", &args, &[]);
+    open_pty_internal(id, &git_args, repo_path, rows, cols, pty_state, app_handle).await
 }
 
-/// Creates a Git commit with staged changes.
-///
-/// Can create a new commit or amend the last commit depending on the options.
+/// Finishes bisect, and checks out the original HEAD (or the specified commit).
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `options` - Commit options including the commit message and amend flag
+/// * `commit` - Commit to be checked out instead of the original HEAD (optional)
 #[tauri::command]
-pub async fn commit(repo_path: &Path, options: CommitOptions) -> Result<(), String> {
-    match options {
-        CommitOptions::Normal { message } => {
-            git::commit::commit(repo_path, &message).await?;
-            Ok(())
-        }
-        CommitOptions::Amend {
-            message: Some(message),
-        } => {
-            git::commit::commit_amend(repo_path, Some(&message)).await?;
-            Ok(())
-        }
-        CommitOptions::Amend { message: None } => {
-            git::commit::commit_amend(repo_path, None).await?;
-            Ok(())
-        }
-    }
+pub async fn reset_bisect(repo_path: &Path, commit: Option<String>) -> Result<(), CommandError> {
+    Ok(git::bisect::reset(repo_path, commit.as_deref()).await?)
 }
 
-/// Creates a new Git branch.
+/// Gets verdicts of the bisect in progress.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `options` - Branch creation options including name and starting point
+///
+/// # Returns
+/// Marked commits in order, and the first bad commit if it has been found.
+///
+/// # Errors
+/// Returns an error if bisect is not in progress.
 #[tauri::command]
-pub async fn create_branch(repo_path: &Path, options: CreateBranchOptions) -> Result<(), String> {
-    Ok(git::branch::create_branch(repo_path, &options).await?)
+pub async fn get_bisect_log(repo_path: &Path) -> Result<BisectLog, CommandError> {
+    Ok(git::bisect::log(repo_path).await?)
 }
 
-/// Deletes a Git branch.
+/// Gets the list of configured remote repositories.
 ///
 /// # Arguments
 /// * `repo_path` - Path to the Git repository
-/// * `options` - Branch deletion options including name and force flag
+///
+/// # Returns
+/// List of remote names (e.g., "origin", "upstream").
 #[tauri::command]
-pub async fn delete_branch(repo_path: &Path, options: DeleteBranchOptions) -> Result<(), String> {
-    Ok(git::branch::delete_branch(repo_path, &options).await?)
+pub async fn get_remote_list(repo_path: &Path) -> Result<Vec<String>, CommandError> {
+    Ok(git::remote::get_remote_list(repo_path).await?)
 }
 
-/// Switches to a different branch or commit.
+fn emit_remote_progress<T: Runtime>(
+    id: usize,
+    app_handle: AppHandle<T>,
+) -> impl FnMut(RemoteProgress) {
+    move |progress: RemoteProgress| {
+        if let Err(e) = app_handle.emit(format!("remote-progress:{}", id).as_str(), progress) {
+            warn!("Failed to emit remote-progress event, {}", e);
+        }
+    }
+}
+
+fn emit_command_output<T: Runtime>(id: usize, app_handle: AppHandle<T>) -> impl FnMut(&str) {
+    move |line: &str| {
+        if let Err(e) = app_handle.emit(format!("command-output:{}", id).as_str(), line) {
+            warn!("Failed to emit command-output event, {}", e);
+        }
+    }
+}
+
+/// Fetches from remote repositories.
+///
+/// Progress is emitted as `remote-progress:{id}` events while running.
 ///
 /// # Arguments
+/// * `id` - Identifier used for the name of progress events
 /// * `repo_path` - Path to the Git repository
-/// * `options` - Switch options including target branch/commit
+/// * `options` - Fetch options including remote, refspec, prune and tags
+///
+/// # Returns
+/// List of updated refs.
+///
+/// # Errors
+/// Returns an error including rejected refs if some refs could not be updated.
 #[tauri::command]
-pub async fn switch(repo_path: &Path, options: SwitchOptions) -> Result<(), String> {
-    Ok(git::switch::switch(repo_path, &options).await?)
+pub async fn fetch<T: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    options: FetchOptions,
+    app_handle: AppHandle<T>,
+) -> Result<Vec<RefUpdate>, CommandError> {
+    let on_progress = emit_remote_progress(id, app_handle);
+    Ok(git::fetch::fetch(repo_path, &options, on_progress).await?)
 }
 
-/// Resets the current branch to a specific commit.
+/// Fetches from a remote repository and integrates it into the current branch.
+///
+/// Progress is emitted as `remote-progress:{id}` events while running.
 ///
 /// # Arguments
+/// * `id` - Identifier used for the name of progress events
 /// * `repo_path` - Path to the Git repository
-/// * `options` - Reset options including target commit and reset mode (soft/mixed/hard)
+/// * `options` - Pull options including remote, refspec and rebase flag
+///
+/// # Returns
+/// List of refs updated by the fetch.
 #[tauri::command]
-pub async fn reset(repo_path: &Path, options: ResetOptions) -> Result<(), String> {
-    Ok(git::reset::reset(repo_path, &options).await?)
+pub async fn pull<T: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    options: PullOptions,
+    app_handle: AppHandle<T>,
+) -> Result<Vec<RefUpdate>, CommandError> {
+    let on_progress = emit_remote_progress(id, app_handle);
+    Ok(git::pull::pull(repo_path, &options, on_progress).await?)
 }
 
-/// Gets the list of configured remote repositories.
+/// Pushes local refs to a remote repository.
+///
+/// Progress is emitted as `remote-progress:{id}` events, and other output of git
+/// (including pre-push hook) is emitted as `command-output:{id}` events while running.
 ///
 /// # Arguments
+/// * `id` - Identifier used for the name of progress and output events
 /// * `repo_path` - Path to the Git repository
+/// * `options` - Push options including remote, refspec and force-with-lease flag
 ///
 /// # Returns
-/// List of remote names (e.g., "origin", "upstream").
+/// List of updated refs.
+///
+/// # Errors
+/// Returns an error including rejected refs if the remote refused some refs.
+///
+/// Returns an error of `HookFailed` kind if pre-push hook rejected the push.
+/// Set `noVerify` of the options to bypass the hook.
 #[tauri::command]
-pub async fn get_remote_list(repo_path: &Path) -> Result<Vec<String>, String> {
-    Ok(git::remote::get_remote_list(repo_path).await?)
+pub async fn push<T: Runtime>(
+    id: usize,
+    repo_path: &Path,
+    options: PushOptions,
+    app_handle: AppHandle<T>,
+) -> Result<Vec<RefUpdate>, CommandError> {
+    let on_progress = emit_remote_progress(id, app_handle.clone());
+    let on_output = emit_command_output(id, app_handle);
+    Ok(git::push::push(repo_path, &options, on_progress, on_output).await?)
 }
 
 /// Opens an external diff tool to compare two file versions.
@@ -598,7 +1938,7 @@ pub async fn show_external_diff(
     config_state: State<'_, ConfigStateMutex>,
     repo_state: State<'_, RepositoriesStateMutex>,
     stager_state: State<'_, StagerStateMutex>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let command_line = {
         let config = config_state.0.lock().await;
         if let Some(ref command_line) = config.config.external_diff_tool {
@@ -637,16 +1977,61 @@ pub async fn show_external_diff(
     Ok(())
 }
 
+/// Launches the configured external merge tool for an unmerged file.
+///
+/// `${base}`, `${local}`, `${remote}` and `${merged}` in the command line are replaced
+/// with the file paths. The tool is launched without waiting for it to finish,
+/// so the file must be marked as resolved afterwards.
+///
+/// # Arguments
+/// * `repo_path` - Path to the Git repository
+/// * `rel_path` - Relative path to the unmerged file
+///
+/// # Errors
+/// Returns an error if no external merge tool is configured, or the repository is not opened.
+#[tauri::command]
+pub async fn show_external_merge(
+    repo_path: &Path,
+    rel_path: &str,
+    config_state: State<'_, ConfigStateMutex>,
+    repo_state: State<'_, RepositoriesStateMutex>,
+) -> Result<(), CommandError> {
+    let command_line = {
+        let config = config_state.0.lock().await;
+        if let Some(ref command_line) = config.config.external_merge_tool {
+            command_line.clone()
+        } else {
+            return Err("External merge tool is not configured".into());
+        }
+    };
+    let repo = {
+        let repositories = repo_state.0.lock().await;
+        repositories
+            .get(repo_path)
+            .ok_or_else(|| "Repository is not opened".to_owned())?
+            .clone()
+    };
+    let files = git::external_diff::prepare_merge_files(&repo, rel_path)
+        .await
+        .map_err(|e| format!("{}", e))?;
+    git::external_diff::show_external_merge(&repo, &command_line, &files, &repo.path.join(rel_path))
+        .await
+        .map_err(|e| CommandError::other(e.to_string()))
+}
+
 /// Copies text to the system clipboard.
 ///
 /// # Arguments
 /// * `text` - The text to copy to the clipboard
 #[tauri::command]
-pub async fn yank_text<T: Runtime>(text: &str, app_handle: AppHandle<T>) -> Result<(), String> {
+pub async fn yank_text<T: Runtime>(
+    text: &str,
+    app_handle: AppHandle<T>,
+) -> Result<(), CommandError> {
     app_handle
         .clipboard()
         .write_text(text)
-        .map_err(|e| format!("{}", e))
+        .map_err(|e| CommandError::other(e.to_string()))
 }
 
 /// Opens a pseudo-terminal (PTY) and executes a command.
@@ -669,22 +2054,24 @@ pub async fn open_pty<T: Runtime>(
     cols: u16,
     pty_state: State<'_, PtyStateMutex>,
     app_handle: AppHandle<T>,
-) -> Result<(), String> {
-    open_pty_internal(id, command_line, cwd, rows, cols, pty_state, app_handle).await
+) -> Result<(), CommandError> {
+    let args = crate::platform::split_commandline(command_line)
+        .map_err(|e| format!("Failed to parse command line: {}", e))?;
+    open_pty_internal(id, &args, cwd, rows, cols, pty_state, app_handle).await
 }
 
 async fn open_pty_internal<'a, T: Runtime, P: Into<Cow<'a, Path>>>(
     id: usize,
-    command_line: &str,
+    args: &[String],
     cwd: P,
     rows: u16,
     cols: u16,
     pty_state: State<'_, PtyStateMutex>,
     app_handle: AppHandle<T>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let handle_clone = AppHandle::clone(&app_handle);
     let on_data = move |id: PtyId, data: &[u8]| {
-        let data = String::from_utf8(data.to_vec()).unwrap();
+        let data = String::from_utf8_lossy(data).into_owned();
         if let Err(e) = handle_clone.emit(format!("pty-data:{}", id.0).as_str(), data) {
             warn!("Failed to emit pty-data event, {}", e);
         }
@@ -698,17 +2085,9 @@ async fn open_pty_internal<'a, T: Runtime, P: Into<Cow<'a, Path>>>(
         }
     };
     let mut pty = pty_state.0.lock().await;
-    pty.open(
-        PtyId(id),
-        command_line,
-        &cwd.into(),
-        rows,
-        cols,
-        on_data,
-        on_exit,
-    )
-    .await
-    .map_err(|e| format!("{}", e))?;
+    pty.open(PtyId(id), args, &cwd.into(), rows, cols, on_data, on_exit)
+        .await
+        .map_err(|e| format!("{}", e))?;
     return Ok(());
 }
 
@@ -724,11 +2103,11 @@ pub async fn write_pty(
     id: usize,
     data: String,
     pty_state: State<'_, PtyStateMutex>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let pty = pty_state.0.lock().await;
     pty.write(PtyId(id), data)
         .await
-        .map_err(|e| format!("{}", e))
+        .map_err(|e| CommandError::other(e.to_string()))
 }
 
 /// Closes a PTY session and terminates the running process.
@@ -736,9 +2115,11 @@ pub async fn write_pty(
 /// # Arguments
 /// * `id` - PTY session identifier
 #[tauri::command]
-pub async fn close_pty(id: usize, pty_state: State<'_, PtyStateMutex>) -> Result<(), String> {
+pub async fn close_pty(id: usize, pty_state: State<'_, PtyStateMutex>) -> Result<(), CommandError> {
     let pty = pty_state.0.lock().await;
-    pty.kill(PtyId(id)).await.map_err(|e| format!("{}", e))
+    pty.kill(PtyId(id))
+        .await
+        .map_err(|e| CommandError::other(e.to_string()))
 }
 
 /// Resizes a PTY session.
@@ -755,16 +2136,16 @@ pub async fn resize_pty(
     rows: u16,
     cols: u16,
     pty_state: State<'_, PtyStateMutex>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let pty = pty_state.0.lock().await;
     pty.resize(PtyId(id), rows, cols)
         .await
-        .map_err(|e| format!("{}", e))
+        .map_err(|e| CommandError::other(e.to_string()))
 }
 
 /// Executes a Git command in a PTY session.
 ///
-/// Constructs a Git command line and runs it in a new PTY session,
+/// Builds arguments of a Git command and runs it in a new PTY session,
 /// allowing for interactive Git operations with terminal output.
 ///
 /// # Arguments
@@ -784,8 +2165,8 @@ pub async fn exec_git_with_pty<T: Runtime>(
     cols: u16,
     pty_state: State<'_, PtyStateMutex>,
     app_handle: AppHandle<T>,
-) -> Result<(), String> {
-    let command_line = build_command_line(repo_path, command, &args[..]);
+) -> Result<(), CommandError> {
+    let command_args = build_command_args(repo_path, command, &args[..], &[]);
     let repo_path = if let Some(p) = repo_path {
         Cow::from(p)
     } else {
@@ -793,7 +2174,7 @@ pub async fn exec_git_with_pty<T: Runtime>(
     };
     open_pty_internal(
         id,
-        &command_line,
+        &command_args,
         repo_path,
         rows,
         cols,
@@ -813,12 +2194,11 @@ pub async fn exec_git_with_pty<T: Runtime>(
 /// # Returns
 /// Git user information containing name and email.
 #[tauri::command]
-pub async fn get_user_info(repo_path: &Path) -> Result<GitUser, String> {
+pub async fn get_user_info(repo_path: &Path) -> Result<GitUser, CommandError> {
     let (name, email) = tokio::try_join!(
         git::config::get_config_value(repo_path, "user.name", false),
         git::config::get_config_value(repo_path, "user.email", false),
-    )
-    .map_err(|e| format!("{}", e))?;
+    )?;
     Ok(GitUser { name, email })
 }
 
@@ -830,7 +2210,7 @@ pub async fn get_user_info(repo_path: &Path) -> Result<GitUser, String> {
 /// # Returns
 /// The path to the repository root, or `None` if not inside a Git repository.
 #[tauri::command]
-pub async fn find_repository_root() -> Result<Option<String>, String> {
+pub async fn find_repository_root() -> Result<Option<String>, CommandError> {
     Ok(git::find_repository_root().await?)
 }
 
@@ -842,7 +2222,7 @@ pub async fn find_repository_root() -> Result<Option<String>, String> {
 /// # Returns
 /// List of fonts with their names and properties (including monospace flag).
 #[tauri::command]
-pub async fn get_system_fonts() -> Result<Vec<Font>, String> {
+pub async fn get_system_fonts() -> Result<Vec<Font>, CommandError> {
     let source = FsSource::new();
 
     let handles = source.all_fonts().map_err(|e| format!("{}", e))?;
@@ -871,8 +2251,13 @@ pub async fn get_system_fonts() -> Result<Vec<Font>, String> {
 /// # Arguments
 /// * `title` - The new window title
 #[tauri::command]
-pub async fn set_window_title<T: Runtime>(title: &str, window: Window<T>) -> Result<(), String> {
-    window.set_title(title).map_err(|e| format!("{}", e))
+pub async fn set_window_title<T: Runtime>(
+    title: &str,
+    window: Window<T>,
+) -> Result<(), CommandError> {
+    window
+        .set_title(title)
+        .map_err(|e| CommandError::other(e.to_string()))
 }
 
 /// Opens the developer tools window.
@@ -880,7 +2265,7 @@ pub async fn set_window_title<T: Runtime>(title: &str, window: Window<T>) -> Res
 /// This command is only available in development builds (debug_assertions).
 /// In production builds, this function will return an error.
 #[tauri::command]
-pub async fn open_devtools<T: Runtime>(_window: Window<T>) -> Result<(), String> {
+pub async fn open_devtools<T: Runtime>(_window: Window<T>) -> Result<(), CommandError> {
     #[cfg(debug_assertions)]
     {
         use tauri::Manager;
@@ -892,7 +2277,7 @@ pub async fn open_devtools<T: Runtime>(_window: Window<T>) -> Result<(), String>
     }
     #[cfg(not(debug_assertions))]
     {
-        Err("DevTools are only available in development mode".to_string())
+        Err("DevTools are only available in development mode".into())
     }
 }
 
@@ -907,7 +2292,7 @@ pub async fn open_devtools<T: Runtime>(_window: Window<T>) -> Result<(), String>
 /// # Returns
 /// Returns Ok(()) on success, or an error message if the level string is invalid.
 #[tauri::command]
-pub fn set_log_level(level: &str) -> Result<(), String> {
+pub fn set_log_level(level: &str) -> Result<(), CommandError> {
     let level_filter = match level.to_lowercase().as_str() {
         "off" => log::LevelFilter::Off,
         "error" => log::LevelFilter::Error,
@@ -919,7 +2304,8 @@ pub fn set_log_level(level: &str) -> Result<(), String> {
             return Err(format!(
                 "Invalid log level: '{}'. Valid levels are: off, error, warn, info, debug, trace",
                 level
-            ))
+            )
+            .into())
         }
     };
 
@@ -941,11 +2327,11 @@ pub fn set_log_level(level: &str) -> Result<(), String> {
 pub async fn load_repo_config(
     repo_path: String,
     repo_config_state: State<'_, crate::state::repo_config::RepoConfigStateMutex>,
-) -> Result<RepositoryConfig, String> {
+) -> Result<RepositoryConfig, CommandError> {
     let mut state = repo_config_state.0.lock().await;
     state
         .load(&repo_path)
-        .map_err(|e| format!("Failed to load repository config: {}", e))
+        .map_err(|e| CommandError::other(format!("Failed to load repository config: {}", e)))
 }
 
 /// Saves repository-specific configuration to `.git/inazuma.json`.
@@ -960,11 +2346,11 @@ pub async fn save_repo_config(
     repo_path: String,
     new_config: RepositoryConfig,
     repo_config_state: State<'_, crate::state::repo_config::RepoConfigStateMutex>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let mut state = repo_config_state.0.lock().await;
     // Set repo_path first
     state.repo_path = Some(std::path::PathBuf::from(&repo_path));
     state
         .save(new_config)
-        .map_err(|e| format!("Failed to save repository config: {}", e))
+        .map_err(|e| CommandError::other(format!("Failed to save repository config: {}", e)))
 }
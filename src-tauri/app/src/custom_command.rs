@@ -4,6 +4,7 @@ use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use tauri::Emitter;
 use tauri::{AppHandle, Runtime, State};
+use types::CommandError;
 
 /// Executes a custom command in a PTY (pseudo-terminal).
 ///
@@ -27,7 +28,7 @@ pub async fn exec_custom_command_with_pty<T: Runtime>(
     cols: u16,
     pty_state: State<'_, PtyStateMutex>,
     app_handle: AppHandle<T>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Use repo_path as cwd, or current directory if not provided
     let cwd: Cow<Path> = if let Some(p) = repo_path {
         Cow::from(PathBuf::from(p))
@@ -35,10 +36,13 @@ pub async fn exec_custom_command_with_pty<T: Runtime>(
         Cow::from(PathBuf::from("."))
     };
 
+    let args = crate::platform::split_commandline(&command_line)
+        .map_err(|e| format!("Failed to parse command line: {}", e))?;
+
     // Open PTY
     let handle_clone = AppHandle::clone(&app_handle);
     let on_data = move |id: crate::state::pty::PtyId, data: &[u8]| {
-        let data = String::from_utf8(data.to_vec()).unwrap();
+        let data = String::from_utf8_lossy(data).into_owned();
         if let Err(e) = handle_clone.emit(format!("pty-data:{}", id.0).as_str(), data) {
             warn!("Failed to emit pty-data event, {}", e);
         }
@@ -54,7 +58,7 @@ pub async fn exec_custom_command_with_pty<T: Runtime>(
     let mut pty = pty_state.0.lock().await;
     pty.open(
         crate::state::pty::PtyId(id),
-        &command_line,
+        &args,
         &cwd,
         rows,
         cols,
@@ -62,7 +66,7 @@ pub async fn exec_custom_command_with_pty<T: Runtime>(
         on_exit,
     )
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| CommandError::other(e.to_string()))
 }
 
 /// Executes a custom command in the background (detached from the parent process).
@@ -82,7 +86,7 @@ pub async fn exec_custom_command_with_pty<T: Runtime>(
 pub async fn exec_custom_command_detached(
     repo_path: Option<String>,
     command_line: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Use repo_path as cwd, or current directory if not provided
     let cwd = repo_path.unwrap_or_else(|| ".".to_string());
 
@@ -91,7 +95,7 @@ pub async fn exec_custom_command_detached(
         .map_err(|e| format!("Failed to parse command line: {}", e))?;
 
     if args.is_empty() {
-        return Err("Command line is empty".to_string());
+        return Err("Command line is empty".into());
     }
 
     // Create command
@@ -1,27 +1,54 @@
+use std::borrow::Cow;
+use std::ffi::OsString;
 use std::process::Stdio;
 use std::{path::Path, process::Output};
 use thiserror::Error;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
+use types::{CommandError, CommandErrorKind, ErrorHint, RefUpdate};
 
+pub mod backend;
+pub mod bisect;
 pub mod blame;
 pub mod branch;
+pub mod cancel;
+pub mod cat_file;
+pub mod check_ignore;
+pub mod cherry_pick;
+pub mod clone;
 pub mod commit;
 pub mod commit_detail;
 pub mod config;
+pub mod conflict;
 pub mod diff;
 pub mod external_diff;
+pub mod fetch;
 pub mod file;
+pub mod grep;
+pub mod hook;
+pub mod init;
 pub mod log;
 pub mod lstree;
+pub mod merge;
 pub mod merge_heads;
+pub mod operation;
+pub mod patch;
+pub mod progress;
+pub mod pull;
+pub mod push;
+pub mod rebase;
 pub mod refs;
 pub mod remote;
 pub mod reset;
 pub mod rev_parse;
+pub mod revert;
+pub mod stash;
 pub mod status;
+pub mod submodule;
 pub mod switch;
+pub mod tag;
 pub mod workingtree;
+pub mod worktree;
 
 #[derive(Error, Debug)]
 pub enum GitError {
@@ -34,9 +61,35 @@ pub enum GitError {
     #[error("GitError({command},unexpected output) {text}")]
     UnexpectedOutput { command: String, text: String },
     #[error("GitError(exec failed) {}", .0.to_string())]
-    ExecFailed(#[from] std::io::Error),
+    ExecFailed(std::io::Error),
     #[error("GitError({command}, argument error) {message}")]
     ArgumentError { command: String, message: String },
+    #[error("GitError({command},gitoxide) {message}")]
+    Gitoxide { command: String, message: String },
+    #[error("GitError({command},cancelled) {reason}")]
+    Cancelled { command: String, reason: String },
+    #[error("GitError({command},rejected) {}", format_rejected_refs(.refs))]
+    RefsRejected {
+        command: String,
+        refs: Vec<RefUpdate>,
+    },
+    #[error("GitError({command},{hook} hook failed,rc={exit_code}) {stderr}")]
+    HookFailed {
+        command: String,
+        hook: String,
+        exit_code: i32,
+        stderr: String,
+    },
+}
+
+fn format_rejected_refs(refs: &[RefUpdate]) -> String {
+    refs.iter()
+        .map(|r| match r.reason {
+            Some(ref reason) => format!("{} ({})", r.to, reason),
+            None => r.to.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl GitError {
@@ -44,49 +97,239 @@ impl GitError {
         if output.status.success() {
             Ok(())
         } else {
-            let stderr = std::str::from_utf8(&output.stderr)
-                .unwrap()
-                .trim_end()
-                .to_string();
+            let stderr = decode_output(&output.stderr).trim_end().to_string();
 
             Err(GitError::ExitCodeNonZero {
                 command: command.to_string(),
-                exit_code: output.status.code().unwrap(),
+                // None if the process is killed by a signal
+                exit_code: output.status.code().unwrap_or(-1),
                 stderr,
             })
         }
     }
 }
 
-impl From<GitError> for String {
+impl From<std::io::Error> for GitError {
+    fn from(e: std::io::Error) -> Self {
+        match e
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<cancel::Cancelled>())
+        {
+            Some(cancelled) => GitError::Cancelled {
+                command: cancelled.command.clone(),
+                reason: cancelled.reason.clone(),
+            },
+            None => GitError::ExecFailed(e),
+        }
+    }
+}
+
+impl From<GitError> for CommandError {
     fn from(e: GitError) -> Self {
-        format!("{}", e)
+        let message = format!("{}", e);
+        let mut ret = CommandError::other(message);
+        match e {
+            GitError::ExitCodeNonZero {
+                command,
+                exit_code,
+                stderr,
+            } => {
+                ret.kind = CommandErrorKind::ExitCodeNonZero;
+                ret.command = Some(command);
+                ret.exit_code = Some(exit_code);
+                ret.hint = error_hint(&stderr);
+                ret.stderr = Some(stderr);
+            }
+            GitError::UnexpectedOutput { command, .. } => {
+                ret.kind = CommandErrorKind::UnexpectedOutput;
+                ret.command = Some(command);
+            }
+            GitError::ExecFailed(_) => {
+                ret.kind = CommandErrorKind::ExecFailed;
+            }
+            GitError::ArgumentError { command, .. } => {
+                ret.kind = CommandErrorKind::ArgumentError;
+                ret.command = Some(command);
+            }
+            GitError::Gitoxide { command, message } => {
+                ret.kind = CommandErrorKind::Gitoxide;
+                ret.command = Some(command);
+                ret.hint = error_hint(&message);
+            }
+            GitError::Cancelled { command, .. } => {
+                ret.kind = CommandErrorKind::Cancelled;
+                ret.command = Some(command);
+            }
+            GitError::RefsRejected { command, refs } => {
+                ret.kind = CommandErrorKind::RefsRejected;
+                ret.command = Some(command);
+                ret.rejected_refs = Some(refs);
+            }
+            GitError::HookFailed {
+                command,
+                hook,
+                exit_code,
+                stderr,
+            } => {
+                // hint is not detected, since stderr is mostly the output of the hook
+                ret.kind = CommandErrorKind::HookFailed;
+                ret.command = Some(command);
+                ret.exit_code = Some(exit_code);
+                ret.stderr = Some(stderr);
+                ret.hook = Some(hook);
+            }
+        }
+        ret
     }
 }
 
-pub fn build_command_line(repo_path: Option<&Path>, command: &str, args: &[&str]) -> String {
-    let mut command_line = if let Some(repo_path) = repo_path {
-        let git_dir = repo_path.join(".git");
-        format!(
-            "git -C \"{}\" --git-dir \"{}\" {}",
-            repo_path.to_str().unwrap(),
-            git_dir.to_str().unwrap(),
-            command
-        )
+/// Detects well known failures from the error output of git,
+/// so that the frontend can suggest what to do.
+fn error_hint(stderr: &str) -> Option<ErrorHint> {
+    let lower = stderr.to_lowercase();
+    if lower.contains("not a git repository") {
+        Some(ErrorHint::NotARepository)
+    } else if lower.contains("index.lock") {
+        Some(ErrorHint::IndexLocked)
+    } else if stderr.contains("CONFLICT")
+        || lower.contains("fix conflicts")
+        || lower.contains("needs merge")
+        || lower.contains("unmerged files")
+        || lower.contains("resolve your current index first")
+    {
+        Some(ErrorHint::MergeConflict)
+    } else if lower.contains("authentication failed")
+        || lower.contains("could not read username")
+        || lower.contains("could not read password")
+        || lower.contains("terminal prompts disabled")
+        || lower.contains("permission denied (publickey")
+        || lower.contains("invalid username or password")
+    {
+        Some(ErrorHint::AuthenticationRequired)
+    } else if lower.contains("gpg failed to sign")
+        || lower.contains("couldn't load public key")
+        || lower.contains("incorrect passphrase")
+        || lower.contains("ssh-keygen -y sign is needed")
+        || lower.contains("gpg.ssh.defaultkeycommand needs to be configured")
+    {
+        Some(ErrorHint::SigningFailed)
     } else {
-        format!("git {}", command)
-    };
-    for &a in args.iter() {
-        command_line.push_str(" ");
-        if a.contains(" ") {
-            command_line.push_str("\"");
-            command_line.push_str(a);
-            command_line.push_str("\"");
-        } else {
-            command_line.push_str(a);
+        None
+    }
+}
+
+/// Decodes output of git commands.
+///
+/// Invalid UTF-8 sequences (e.g. file names or commit messages without `encoding` header
+/// in legacy encodings) are replaced with U+FFFD instead of failing.
+pub fn decode_output(bytes: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(bytes)
+}
+
+/// Quotes `raw` like `core.quotePath` if it is not valid UTF-8, so that the path can be passed
+/// back to git (`--pathspec-from-file` unquotes it, and `path_arg` converts it to the raw bytes).
+///
+/// Returns None for valid UTF-8, which is used as is.
+pub fn quote_invalid_path(raw: &[u8]) -> Option<String> {
+    if std::str::from_utf8(raw).is_ok() {
+        return None;
+    }
+    let mut quoted = String::from("\"");
+    for &b in raw {
+        match b {
+            b'"' => quoted.push_str("\\\""),
+            b'\\' => quoted.push_str("\\\\"),
+            b'\t' => quoted.push_str("\\t"),
+            b'\n' => quoted.push_str("\\n"),
+            0x20..=0x7e => quoted.push(b as char),
+            _ => quoted.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    quoted.push('"');
+    Some(quoted)
+}
+
+/// Converts a path quoted by `quote_invalid_path` back to the raw bytes.
+fn unquote_path(quoted: &str) -> Option<Vec<u8>> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    let bytes = inner.as_bytes();
+    let mut raw: Vec<u8> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            raw.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        match bytes.get(i + 1)? {
+            b'"' => raw.push(b'"'),
+            b'\\' => raw.push(b'\\'),
+            b't' => raw.push(b'\t'),
+            b'n' => raw.push(b'\n'),
+            b'0'..=b'3' => {
+                let octal = inner.get(i + 1..i + 4)?;
+                raw.push(u8::from_str_radix(octal, 8).ok()?);
+                i += 4;
+                continue;
+            }
+            _ => return None,
+        }
+        i += 2;
+    }
+    Some(raw)
+}
+
+/// Converts a path given by the frontend to an argument of git.
+///
+/// Paths quoted by `quote_invalid_path` are converted back to the raw bytes,
+/// which can be passed only on unix (paths of git for Windows are always UTF-8).
+fn path_arg(rel_path: &str) -> OsString {
+    match unquote_path(rel_path).filter(|raw| std::str::from_utf8(raw).is_err()) {
+        #[cfg(unix)]
+        Some(raw) => {
+            use std::os::unix::ffi::OsStringExt;
+            OsString::from_vec(raw)
         }
+        _ => OsString::from(rel_path),
     }
-    command_line
+}
+
+/// Parses unix time in seconds (`%at`, `%ct`, etc.) as milliseconds.
+pub fn parse_date(command: &str, value: &str) -> Result<u64, GitError> {
+    let seconds = value
+        .parse::<u64>()
+        .map_err(|_| GitError::UnexpectedOutput {
+            command: command.to_string(),
+            text: format!("invalid date: {}", value),
+        })?;
+    Ok(seconds * 1000)
+}
+
+/// Builds arguments (including `git`) to run git command in a PTY.
+///
+/// They are passed to the process as is, so that arguments containing spaces or quotes
+/// (e.g. an author or a message file path) are not split again.
+pub fn build_command_args(
+    repo_path: Option<&Path>,
+    command: &str,
+    args: &[&str],
+    configs: &[&str],
+) -> Vec<String> {
+    let mut command_args = vec![String::from("git")];
+    if let Some(repo_path) = repo_path {
+        let git_dir = worktree::resolve_git_dir(repo_path);
+        command_args.push(String::from("-C"));
+        command_args.push(repo_path.to_string_lossy().into_owned());
+        command_args.push(String::from("--git-dir"));
+        command_args.push(git_dir.to_string_lossy().into_owned());
+    }
+    for &c in configs.iter() {
+        command_args.push(String::from("-c"));
+        command_args.push(c.to_string());
+    }
+    command_args.push(command.to_string());
+    command_args.extend(args.iter().map(|a| a.to_string()));
+    command_args
 }
 
 pub async fn exec(
@@ -95,32 +338,59 @@ pub async fn exec(
     args: &[&str],
     configs: &[&str],
 ) -> std::io::Result<Output> {
-    exec_internal(repo_path, command, args, configs, None).await
+    exec_internal(repo_path, command, args, configs, &[], None).await
 }
 
-pub async fn exec_with_stdin(
+/// Executes git command with additional environment variables.
+///
+/// Some environment variables take precedence over configs given by `-c`
+/// (e.g. `GIT_EDITOR` over `core.editor`), and may be inherited by the app.
+pub async fn exec_with_envs(
     repo_path: &Path,
     command: &str,
     args: &[&str],
     configs: &[&str],
-    stdin_content: &[u8],
+    envs: &[(&str, &str)],
 ) -> std::io::Result<Output> {
-    exec_internal(repo_path, command, args, configs, Some(stdin_content)).await
+    exec_internal(repo_path, command, args, configs, envs, None).await
 }
 
-async fn exec_internal(
+pub async fn exec_with_stdin(
     repo_path: &Path,
     command: &str,
     args: &[&str],
     configs: &[&str],
-    stdin_content: Option<&[u8]>,
+    stdin_content: &[u8],
 ) -> std::io::Result<Output> {
+    exec_internal(repo_path, command, args, configs, &[], Some(stdin_content)).await
+}
+
+fn build_command(repo_path: &Path, command: &str, args: &[&str], configs: &[&str]) -> Command {
+    let git_dir = worktree::resolve_git_dir(repo_path);
+    build_command_internal(repo_path, Some(&git_dir), command, args, configs)
+}
+
+fn build_command_internal(
+    cwd: &Path,
+    git_dir: Option<&Path>,
+    command: &str,
+    args: &[&str],
+    configs: &[&str],
+) -> Command {
     let mut cmd = Command::new("git");
+    // GIT_OPTIONAL_LOCKS=0 prevents commands like status from rewriting the index,
+    // which would be detected by the watcher and cause reload loop.
     cmd.env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_OPTIONAL_LOCKS", "0")
         .arg("-C")
-        .arg(repo_path)
-        .arg("--git-dir")
-        .arg(repo_path.join(".git"));
+        .arg(cwd);
+    if let Some(git_dir) = git_dir {
+        cmd.arg("--git-dir").arg(git_dir);
+    }
+    // commit messages with `encoding` header are re-encoded to UTF-8,
+    // and non-ASCII paths are not quoted.
+    cmd.args(["-c", "i18n.logOutputEncoding=UTF-8"])
+        .args(["-c", "core.quotePath=false"]);
     configs.iter().for_each(|c| {
         cmd.args(&["-c", c]);
     });
@@ -129,18 +399,209 @@ async fn exec_internal(
     cmd.stdin(Stdio::piped());
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
+    // the process is killed if it is cancelled (see `cancel::wait`)
+    cmd.kill_on_drop(true);
     #[cfg(target_os = "windows")]
     cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    cmd
+}
+
+async fn exec_internal(
+    repo_path: &Path,
+    command: &str,
+    args: &[&str],
+    configs: &[&str],
+    envs: &[(&str, &str)],
+    stdin_content: Option<&[u8]>,
+) -> std::io::Result<Output> {
+    let mut cmd = build_command(repo_path, command, args, configs);
+    cmd.envs(envs.iter().copied());
     debug!("{}, git {}, {:?}", repo_path.display(), command, args);
+    wait_with_stdin(cmd, command, stdin_content).await
+}
+
+/// Executes git command with `rel_path` as the pathspec after `--`.
+///
+/// `rel_path` may be a path quoted by `quote_invalid_path`, which is passed as the raw bytes.
+pub async fn exec_with_path(
+    repo_path: &Path,
+    command: &str,
+    args: &[&str],
+    rel_path: &str,
+) -> std::io::Result<Output> {
+    let mut cmd = build_command(repo_path, command, args, &[]);
+    cmd.arg("--").arg(path_arg(rel_path));
+    debug!(
+        "{}, git {}, {:?} -- {}",
+        repo_path.display(),
+        command,
+        args,
+        rel_path
+    );
+    wait_with_stdin(cmd, command, None).await
+}
+
+async fn wait_with_stdin(
+    mut cmd: Command,
+    command: &str,
+    stdin_content: Option<&[u8]>,
+) -> std::io::Result<Output> {
     let mut proc = cmd.spawn()?;
-    if let Some(stdin_content) = stdin_content {
-        if let Some(stdin) = &mut proc.stdin {
+    // stdin is written while reading stdout, since git may stop reading stdin
+    // until its output is read (e.g. `check-ignore --stdin`) when the pipe is full
+    let stdin = proc.stdin.take();
+    let write_stdin = async move {
+        if let (Some(mut stdin), Some(stdin_content)) = (stdin, stdin_content) {
             stdin.write_all(stdin_content).await?;
             stdin.shutdown().await?;
         }
+        Ok::<_, std::io::Error>(())
+    };
+    let wait = async {
+        let (written, output) = tokio::join!(write_stdin, proc.wait_with_output());
+        let output = output?;
+        match written {
+            // the process has exited without reading all of stdin, so its status tells the reason
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e),
+            _ => Ok(output),
+        }
+    };
+    cancel::wait(command, wait).await
+}
+
+/// Executes git command, and passes each line of stderr to `on_stderr_line` while running.
+///
+/// stderr is split by both CR and LF, so that progress lines (which are terminated by CR)
+/// can be handled one by one.
+/// Lines for which `on_stderr_line` returns true are treated as consumed,
+/// and are not included in the `stderr` of the result.
+pub async fn exec_with_stderr_handler<F: FnMut(&str) -> bool>(
+    repo_path: &Path,
+    command: &str,
+    args: &[&str],
+    configs: &[&str],
+    on_stderr_line: F,
+) -> std::io::Result<Output> {
+    let cmd = build_command(repo_path, command, args, configs);
+    debug!("{}, git {}, {:?}", repo_path.display(), command, args);
+    wait_with_stderr_handler(cmd, command, on_stderr_line).await
+}
+
+/// Executes git command which creates a repository (e.g. clone, init) in `cwd`,
+/// and passes each line of stderr to `on_stderr_line` like `exec_with_stderr_handler`.
+///
+/// `--git-dir` is not specified, since `git init` would create the repository there.
+pub async fn exec_without_repository<F: FnMut(&str) -> bool>(
+    cwd: &Path,
+    command: &str,
+    args: &[&str],
+    on_stderr_line: F,
+) -> std::io::Result<Output> {
+    let cmd = build_command_internal(cwd, None, command, args, &[]);
+    debug!("{}, git {}, {:?}", cwd.display(), command, args);
+    wait_with_stderr_handler(cmd, command, on_stderr_line).await
+}
+
+async fn wait_with_stderr_handler<F: FnMut(&str) -> bool>(
+    mut cmd: Command,
+    command: &str,
+    mut on_stderr_line: F,
+) -> std::io::Result<Output> {
+    cmd.stdin(Stdio::null());
+    let mut proc = cmd.spawn()?;
+    let mut stdout = proc.stdout.take().unwrap();
+    let mut stderr = proc.stderr.take().unwrap();
+    let read_stdout = async {
+        let mut buf: Vec<u8> = Vec::new();
+        stdout.read_to_end(&mut buf).await?;
+        Ok::<_, std::io::Error>(buf)
+    };
+    let read_stderr = async {
+        let mut rest: Vec<u8> = Vec::new();
+        let mut line: Vec<u8> = Vec::new();
+        let mut buf = [0u8; 4096];
+        let mut handle_line = |line: &mut Vec<u8>| {
+            if !line.is_empty() {
+                let text = String::from_utf8_lossy(line);
+                if !on_stderr_line(&text) {
+                    rest.extend_from_slice(line);
+                    rest.push(b'\n');
+                }
+                line.clear();
+            }
+        };
+        loop {
+            let len = stderr.read(&mut buf).await?;
+            if len == 0 {
+                break;
+            }
+            for &b in buf[0..len].iter() {
+                if b == b'\r' || b == b'\n' {
+                    handle_line(&mut line);
+                } else {
+                    line.push(b);
+                }
+            }
+        }
+        handle_line(&mut line);
+        Ok::<_, std::io::Error>(rest)
+    };
+    let wait = async {
+        let (stdout, stderr) = tokio::try_join!(read_stdout, read_stderr)?;
+        let status = proc.wait().await?;
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    };
+    cancel::wait(command, wait).await
+}
+
+/// Executes git command, and passes each line of stdout to `on_stdout_line` while running.
+///
+/// `stdin_content` is written to stdin before reading stdout.
+/// Since stdout is consumed by `on_stdout_line`, the `stdout` of the result is always empty.
+pub async fn exec_with_stdout_handler<F: FnMut(&str)>(
+    repo_path: &Path,
+    command: &str,
+    args: &[&str],
+    configs: &[&str],
+    stdin_content: &[u8],
+    mut on_stdout_line: F,
+) -> std::io::Result<Output> {
+    let mut cmd = build_command(repo_path, command, args, configs);
+    debug!("{}, git {}, {:?}", repo_path.display(), command, args);
+    let mut proc = cmd.spawn()?;
+    // stdin has to be dropped to be closed, since the process is not consumed by wait_with_output
+    if let Some(mut stdin) = proc.stdin.take() {
+        stdin.write_all(stdin_content).await?;
+        stdin.shutdown().await?;
     }
-    let output = proc.wait_with_output().await?;
-    Ok(output)
+    let stdout = proc.stdout.take().unwrap();
+    let mut stderr = proc.stderr.take().unwrap();
+    let read_stdout = async {
+        let mut lines = BufReader::new(stdout).split(b'\n');
+        while let Some(line) = lines.next_segment().await? {
+            on_stdout_line(&String::from_utf8_lossy(&line));
+        }
+        Ok::<_, std::io::Error>(())
+    };
+    let read_stderr = async {
+        let mut buf: Vec<u8> = Vec::new();
+        stderr.read_to_end(&mut buf).await?;
+        Ok::<_, std::io::Error>(buf)
+    };
+    let wait = async {
+        let (_, stderr) = tokio::try_join!(read_stdout, read_stderr)?;
+        let status = proc.wait().await?;
+        Ok(Output {
+            status,
+            stdout: Vec::new(),
+            stderr,
+        })
+    };
+    cancel::wait(command, wait).await
 }
 
 pub async fn find_repository_root() -> Result<Option<String>, GitError> {
@@ -155,8 +616,7 @@ pub async fn find_repository_root() -> Result<Option<String>, GitError> {
         .await
         .or_else(|e| Err(GitError::ExecFailed(e)))?;
     if ret.status.success() {
-        let path = std::str::from_utf8(&ret.stdout)
-            .unwrap()
+        let path = decode_output(&ret.stdout)
             .trim_end_matches('\n')
             .to_string();
         Ok(Some(path))
@@ -164,3 +624,125 @@ pub async fn find_repository_root() -> Result<Option<String>, GitError> {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_hint() {
+        assert_eq!(
+            Some(ErrorHint::NotARepository),
+            error_hint("fatal: not a git repository (or any of the parent directories): .git")
+        );
+        assert_eq!(
+            Some(ErrorHint::IndexLocked),
+            error_hint(
+                "fatal: Unable to create '/repo/.git/index.lock': File exists.\n\n\
+                 Another git process seems to be running in this repository"
+            )
+        );
+        assert_eq!(
+            Some(ErrorHint::MergeConflict),
+            error_hint("error: you need to resolve your current index first")
+        );
+        assert_eq!(
+            Some(ErrorHint::AuthenticationRequired),
+            error_hint(
+                "fatal: could not read Username for 'https://github.com': \
+                 terminal prompts disabled"
+            )
+        );
+        assert_eq!(
+            Some(ErrorHint::AuthenticationRequired),
+            error_hint("git@github.com: Permission denied (publickey).")
+        );
+        assert_eq!(
+            Some(ErrorHint::SigningFailed),
+            error_hint("error: gpg failed to sign the data\nfatal: failed to write commit object")
+        );
+        assert_eq!(
+            Some(ErrorHint::SigningFailed),
+            error_hint(
+                "error: Load key \"/home/user/.ssh/id_ed25519\": \
+                 incorrect passphrase supplied to decrypt private key?\n\n\
+                 fatal: failed to write commit object"
+            )
+        );
+        assert_eq!(
+            Some(ErrorHint::SigningFailed),
+            error_hint(
+                "fatal: either user.signingkey or gpg.ssh.defaultKeyCommand needs to be configured"
+            )
+        );
+        // output of hooks or aliases which merely mention ssh-keygen
+        assert_eq!(None, error_hint("error: ssh-keygen: command not found"));
+        assert_eq!(None, error_hint("fatal: bad revision 'foo'"));
+    }
+
+    #[test]
+    fn test_command_error_from_git_error() {
+        let e: CommandError = GitError::ExitCodeNonZero {
+            command: "merge".to_string(),
+            exit_code: 1,
+            stderr: "Automatic merge failed; fix conflicts and then commit the result.".to_string(),
+        }
+        .into();
+        assert_eq!(CommandErrorKind::ExitCodeNonZero, e.kind);
+        assert_eq!(Some("merge"), e.command.as_deref());
+        assert_eq!(Some(1), e.exit_code);
+        assert_eq!(Some(ErrorHint::MergeConflict), e.hint);
+
+        let e: CommandError = GitError::HookFailed {
+            command: "commit".to_string(),
+            hook: "pre-commit".to_string(),
+            exit_code: 1,
+            stderr: "src/main.ts: CONFLICT marker found".to_string(),
+        }
+        .into();
+        assert_eq!(CommandErrorKind::HookFailed, e.kind);
+        assert_eq!(Some("pre-commit"), e.hook.as_deref());
+        assert_eq!(None, e.hint);
+    }
+
+    #[test]
+    fn test_quote_invalid_path() {
+        assert_eq!(
+            None,
+            quote_invalid_path("src/\u{3042}\u{FFFD}.txt".as_bytes())
+        );
+        let raw = b"src/\x82\xa0 \"a\"\\.txt";
+        let quoted = quote_invalid_path(raw).unwrap();
+        assert_eq!(r#""src/\202\240 \"a\"\\.txt""#, quoted);
+        assert_eq!(Some(raw.to_vec()), unquote_path(&quoted));
+        assert_eq!(None, unquote_path("src/a.txt"));
+        assert_eq!(None, unquote_path(r#""\9""#));
+        assert_eq!(OsString::from(r#""a""#), path_arg(r#""a""#));
+    }
+
+    #[test]
+    fn test_build_command_args() {
+        let args = build_command_args(
+            None,
+            "commit",
+            &[
+                "--author=Bob \"B\" O'Neil <b@example.com>",
+                "-F",
+                "/tmp/a b/MSG",
+            ],
+            &["core.editor=true"],
+        );
+        assert_eq!(
+            vec![
+                "git",
+                "-c",
+                "core.editor=true",
+                "commit",
+                "--author=Bob \"B\" O'Neil <b@example.com>",
+                "-F",
+                "/tmp/a b/MSG"
+            ],
+            args
+        );
+    }
+}
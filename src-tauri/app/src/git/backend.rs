@@ -0,0 +1,143 @@
+use super::cat_file::ObjectReader;
+use super::GitError;
+use std::future::Future;
+use std::path::Path;
+use types::*;
+
+mod cli;
+mod gitoxide;
+#[cfg(test)]
+mod parity;
+
+pub use cli::CliBackend;
+pub use gitoxide::GitoxideBackend;
+
+/// Read operations of a repository, which are used by read-heavy views
+/// (history, working tree, blame, tree and file contents).
+///
+/// Results must be the same regardless of the implementation.
+pub trait GitBackend {
+    /// Gets commits reachable from branches, tags, remote branches, HEAD and `heads`
+    /// in topological order. (`max_count` == 0 means all commits)
+    fn log(
+        &self,
+        max_count: u32,
+        heads: &[&str],
+    ) -> impl Future<Output = Result<Vec<Commit>, GitError>> + Send;
+
+    /// Gets branches, tags and remote branches, sorted by creation date (newest first).
+    fn refs(&self) -> impl Future<Output = Result<Refs, GitError>> + Send;
+
+    /// Gets changed files in the index and the working tree, without delta.
+    fn status(&self) -> impl Future<Output = Result<Vec<WorkingTreeFileEntry>, GitError>> + Send;
+
+    /// Gets changed files between two revisions, with renames detected.
+    fn diff(
+        &self,
+        revspec1: &str,
+        revspec2: &str,
+    ) -> impl Future<Output = Result<Vec<FileEntry>, GitError>> + Send;
+
+    fn blame(
+        &self,
+        rel_path: &str,
+        revspec: &str,
+    ) -> impl Future<Output = Result<Vec<BlameEntry>, GitError>> + Send;
+
+    /// Gets entries in the tree of `revspec` recursively.
+    fn tree(
+        &self,
+        revspec: &str,
+    ) -> impl Future<Output = Result<Vec<LstreeEntry>, GitError>> + Send;
+
+    /// Reads the content of a file at `revspec` ("STAGED" means the index).
+    ///
+    /// If `apply_filters` is true, the content is converted as checked out
+    /// (smudge filters, end-of-line conversion, etc).
+    fn read_content(
+        &self,
+        rel_path: &str,
+        revspec: &str,
+        apply_filters: bool,
+    ) -> impl Future<Output = Result<Vec<u8>, GitError>> + Send;
+}
+
+/// The backend of a repository, which is selected by `Config::git_backend`.
+#[derive(Clone)]
+pub enum Backend {
+    Cli(CliBackend),
+    Gitoxide(GitoxideBackend),
+}
+
+impl Backend {
+    pub fn new(kind: GitBackendKind, repo_path: &Path, object_reader: ObjectReader) -> Self {
+        let cli = CliBackend::new(repo_path, object_reader);
+        match kind {
+            GitBackendKind::Cli => Backend::Cli(cli),
+            GitBackendKind::Gitoxide => Backend::Gitoxide(GitoxideBackend::new(repo_path, cli)),
+        }
+    }
+
+    pub fn kind(&self) -> GitBackendKind {
+        match self {
+            Backend::Cli(_) => GitBackendKind::Cli,
+            Backend::Gitoxide(_) => GitBackendKind::Gitoxide,
+        }
+    }
+}
+
+impl GitBackend for Backend {
+    async fn log(&self, max_count: u32, heads: &[&str]) -> Result<Vec<Commit>, GitError> {
+        match self {
+            Backend::Cli(b) => b.log(max_count, heads).await,
+            Backend::Gitoxide(b) => b.log(max_count, heads).await,
+        }
+    }
+
+    async fn refs(&self) -> Result<Refs, GitError> {
+        match self {
+            Backend::Cli(b) => b.refs().await,
+            Backend::Gitoxide(b) => b.refs().await,
+        }
+    }
+
+    async fn status(&self) -> Result<Vec<WorkingTreeFileEntry>, GitError> {
+        match self {
+            Backend::Cli(b) => b.status().await,
+            Backend::Gitoxide(b) => b.status().await,
+        }
+    }
+
+    async fn diff(&self, revspec1: &str, revspec2: &str) -> Result<Vec<FileEntry>, GitError> {
+        match self {
+            Backend::Cli(b) => b.diff(revspec1, revspec2).await,
+            Backend::Gitoxide(b) => b.diff(revspec1, revspec2).await,
+        }
+    }
+
+    async fn blame(&self, rel_path: &str, revspec: &str) -> Result<Vec<BlameEntry>, GitError> {
+        match self {
+            Backend::Cli(b) => b.blame(rel_path, revspec).await,
+            Backend::Gitoxide(b) => b.blame(rel_path, revspec).await,
+        }
+    }
+
+    async fn tree(&self, revspec: &str) -> Result<Vec<LstreeEntry>, GitError> {
+        match self {
+            Backend::Cli(b) => b.tree(revspec).await,
+            Backend::Gitoxide(b) => b.tree(revspec).await,
+        }
+    }
+
+    async fn read_content(
+        &self,
+        rel_path: &str,
+        revspec: &str,
+        apply_filters: bool,
+    ) -> Result<Vec<u8>, GitError> {
+        match self {
+            Backend::Cli(b) => b.read_content(rel_path, revspec, apply_filters).await,
+            Backend::Gitoxide(b) => b.read_content(rel_path, revspec, apply_filters).await,
+        }
+    }
+}
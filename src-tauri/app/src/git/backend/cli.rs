@@ -0,0 +1,63 @@
+use super::super::cat_file::ObjectReader;
+use super::super::{blame, diff, file, log, lstree, refs, status, GitError};
+use super::GitBackend;
+use std::path::{Path, PathBuf};
+use types::*;
+
+/// Backend which runs `git` commands and parses their output.
+#[derive(Clone)]
+pub struct CliBackend {
+    repo_path: PathBuf,
+    object_reader: ObjectReader,
+}
+
+impl CliBackend {
+    pub fn new(repo_path: &Path, object_reader: ObjectReader) -> Self {
+        CliBackend {
+            repo_path: repo_path.to_path_buf(),
+            object_reader,
+        }
+    }
+}
+
+impl GitBackend for CliBackend {
+    async fn log(&self, max_count: u32, heads: &[&str]) -> Result<Vec<Commit>, GitError> {
+        log::log(&self.repo_path, max_count, true, heads).await
+    }
+
+    async fn refs(&self) -> Result<Refs, GitError> {
+        refs::get_refs(&self.repo_path).await
+    }
+
+    async fn status(&self) -> Result<Vec<WorkingTreeFileEntry>, GitError> {
+        status::status(&self.repo_path).await
+    }
+
+    async fn diff(&self, revspec1: &str, revspec2: &str) -> Result<Vec<FileEntry>, GitError> {
+        diff::get_changes_between(&self.repo_path, revspec1, revspec2).await
+    }
+
+    async fn blame(&self, rel_path: &str, revspec: &str) -> Result<Vec<BlameEntry>, GitError> {
+        blame::blame(&self.repo_path, rel_path, revspec).await
+    }
+
+    async fn tree(&self, revspec: &str) -> Result<Vec<LstreeEntry>, GitError> {
+        lstree::lstree(&self.repo_path, revspec).await
+    }
+
+    async fn read_content(
+        &self,
+        rel_path: &str,
+        revspec: &str,
+        apply_filters: bool,
+    ) -> Result<Vec<u8>, GitError> {
+        file::read_content(
+            &self.object_reader,
+            &self.repo_path,
+            rel_path,
+            revspec,
+            apply_filters,
+        )
+        .await
+    }
+}
@@ -0,0 +1,747 @@
+use super::super::{bisect, file, quote_invalid_path, GitError};
+use super::{CliBackend, GitBackend};
+use gix::bstr::{BStr, ByteSlice};
+use gix::diff::blob::{intern::InternedInput, sink::Counter, sources, Algorithm};
+use gix::object::tree::diff::ChangeDetached;
+use gix::objs::tree::EntryKind;
+use gix::status::plumbing::index_as_worktree::{Change, Conflict, EntryStatus};
+use gix::status::{index_worktree, tree_index::TrackRenames, UntrackedFiles};
+use gix::traverse::commit::topo;
+use gix::ObjectId;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::error::Error;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tokio::task::spawn_blocking;
+use types::*;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// git treats a file as binary if NUL is found in this range.
+const BINARY_CHECK_SIZE: usize = 8000;
+
+/// Backend which reads repositories in-process with gitoxide.
+///
+/// Blame is delegated to the CLI backend, since gitoxide's blame is not enabled.
+/// Similarity scores of inexact renames are computed by gitoxide, so they may differ from git's.
+#[derive(Clone)]
+pub struct GitoxideBackend {
+    repo_path: PathBuf,
+    cli: CliBackend,
+}
+
+impl GitoxideBackend {
+    pub fn new(repo_path: &Path, cli: CliBackend) -> Self {
+        GitoxideBackend {
+            repo_path: repo_path.to_path_buf(),
+            cli,
+        }
+    }
+
+    /// Runs `f` in a blocking thread.
+    ///
+    /// The repository is opened for each request, since gitoxide caches the configuration
+    /// (e.g. upstreams of branches) at opening.
+    async fn run<T, F>(&self, command: &'static str, f: F) -> Result<T, GitError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&gix::Repository) -> Result<T, BoxError> + Send + 'static,
+    {
+        let repo_path = self.repo_path.clone();
+        debug!("{}, gitoxide, {}", repo_path.display(), command);
+        let to_error = |message: String| GitError::Gitoxide {
+            command: command.to_string(),
+            message,
+        };
+        spawn_blocking(move || {
+            let repo = gix::open(&repo_path)?;
+            f(&repo)
+        })
+        .await
+        .map_err(|e| to_error(e.to_string()))?
+        .map_err(|e| to_error(e.to_string()))
+    }
+}
+
+impl GitBackend for GitoxideBackend {
+    async fn log(&self, max_count: u32, heads: &[&str]) -> Result<Vec<Commit>, GitError> {
+        let heads = heads.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+        self.run("log", move |repo| log(repo, max_count, &heads))
+            .await
+    }
+
+    async fn refs(&self) -> Result<Refs, GitError> {
+        self.run("refs", refs).await
+    }
+
+    async fn status(&self) -> Result<Vec<WorkingTreeFileEntry>, GitError> {
+        self.run("status", status).await
+    }
+
+    async fn diff(&self, revspec1: &str, revspec2: &str) -> Result<Vec<FileEntry>, GitError> {
+        let revspec1 = revspec1.to_string();
+        let revspec2 = revspec2.to_string();
+        self.run("diff", move |repo| diff(repo, &revspec1, &revspec2))
+            .await
+    }
+
+    async fn blame(&self, rel_path: &str, revspec: &str) -> Result<Vec<BlameEntry>, GitError> {
+        self.cli.blame(rel_path, revspec).await
+    }
+
+    async fn tree(&self, revspec: &str) -> Result<Vec<LstreeEntry>, GitError> {
+        let revspec = revspec.to_string();
+        self.run("tree", move |repo| tree(repo, &revspec)).await
+    }
+
+    async fn read_content(
+        &self,
+        rel_path: &str,
+        revspec: &str,
+        apply_filters: bool,
+    ) -> Result<Vec<u8>, GitError> {
+        let rel_path = rel_path.to_string();
+        let revspec = revspec.to_string();
+        self.run("read_content", move |repo| {
+            read_content(repo, &rel_path, &revspec, apply_filters)
+        })
+        .await
+    }
+}
+
+fn log(repo: &gix::Repository, max_count: u32, heads: &[String]) -> Result<Vec<Commit>, BoxError> {
+    // same order as `git log {heads} --branches --tags --remotes HEAD`
+    let mut tips: Vec<ObjectId> = Vec::new();
+    for head in heads {
+        tips.push(repo.rev_parse_single(head.as_str())?.detach());
+    }
+    let platform = repo.references()?;
+    for refs in [
+        platform.local_branches()?,
+        platform.tags()?,
+        platform.remote_branches()?,
+    ] {
+        for r in refs {
+            let id = r?.into_fully_peeled_id()?;
+            // refs which don't point to commits are ignored like git
+            if let Ok(commit) = id.object()?.peel_to_commit() {
+                tips.push(commit.id);
+            }
+        }
+    }
+    if let Ok(head) = repo.head_id() {
+        tips.push(head.detach());
+    }
+    let mut seen = BTreeSet::new();
+    tips.retain(|id| seen.insert(*id));
+
+    let walk = topo::Builder::from_iters(&repo.objects, tips, None::<Vec<ObjectId>>)
+        .sorting(topo::Sorting::TopoOrder)
+        .with_commit_graph(repo.commit_graph_if_enabled()?)
+        .build()?;
+    let limit = if max_count > 0 {
+        max_count as usize
+    } else {
+        usize::MAX
+    };
+    let mut commits: Vec<Commit> = Vec::new();
+    for info in walk.take(limit) {
+        let info = info?;
+        let commit = repo.find_commit(info.id)?;
+        let commit = commit.decode()?;
+        let author = commit.author();
+        let committer = commit.committer();
+        let parents = commit
+            .parents()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        commits.push(
+            Commit::new(
+                &info.id.to_string(),
+                &parents,
+                &decode_commit_text(author.name, commit.encoding),
+                &author.email.to_str_lossy(),
+                author.time()?.seconds.max(0) as u64 * 1000,
+                &decode_commit_text(commit.message_summary().as_ref(), commit.encoding),
+            )
+            .with_committer(
+                &decode_commit_text(committer.name, commit.encoding),
+                &committer.email.to_str_lossy(),
+                committer.time()?.seconds.max(0) as u64 * 1000,
+            ),
+        );
+    }
+    Ok(commits)
+}
+
+/// Decodes a text in a commit by its `encoding` header, as git does with
+/// `i18n.logOutputEncoding=UTF-8`.
+fn decode_commit_text(text: &BStr, encoding: Option<&BStr>) -> String {
+    match encoding.and_then(|e| encoding_rs::Encoding::for_label(e)) {
+        Some(encoding) => encoding.decode_without_bom_handling(text).0.into_owned(),
+        None => text.to_str_lossy().into_owned(),
+    }
+}
+
+/// Follows symbolic refs, and returns the id which the direct ref points to.
+fn direct_id(reference: &gix::Reference) -> Option<ObjectId> {
+    let mut reference = reference.clone();
+    while let Some(next) = reference.follow() {
+        reference = next.ok()?;
+    }
+    reference.target().try_id().map(|id| id.to_owned())
+}
+
+/// Gets `%(upstream)` of `git for-each-ref`.
+fn upstream_of<'repo>(
+    repo: &'repo gix::Repository,
+    name: &gix::refs::FullNameRef,
+) -> Option<std::borrow::Cow<'repo, gix::refs::FullNameRef>> {
+    let direction = gix::remote::Direction::Fetch;
+    let remote_name = repo.branch_remote_name(name.shorten(), direction)?;
+    if remote_name.as_bstr() == "." {
+        // upstream is a local branch
+        repo.branch_remote_ref_name(name, direction)?.ok()
+    } else {
+        repo.branch_remote_tracking_ref_name(name, direction)?.ok()
+    }
+}
+
+/// Returns the numbers of commits which are reachable only from `id` and only from `upstream_id`,
+/// like `git rev-list --left-right --count id...upstream_id`.
+///
+/// Both sides are walked by commit date until all remaining commits are reachable from both.
+fn ahead_behind(
+    repo: &gix::Repository,
+    id: ObjectId,
+    upstream_id: ObjectId,
+) -> Result<(u32, u32), BoxError> {
+    const LEFT: u8 = 1;
+    const RIGHT: u8 = 2;
+    const BOTH: u8 = LEFT | RIGHT;
+    let commit_time = |id: ObjectId| -> Result<i64, BoxError> {
+        Ok(repo.find_commit(id)?.committer()?.seconds())
+    };
+    let mut flags: HashMap<ObjectId, u8> = HashMap::new();
+    *flags.entry(id).or_default() |= LEFT;
+    *flags.entry(upstream_id).or_default() |= RIGHT;
+    let mut queue: BinaryHeap<(i64, ObjectId)> = BinaryHeap::new();
+    // Each commit is queued at most once, since its flags are read when it is popped.
+    let mut queued: HashSet<ObjectId> = HashSet::new();
+    // The number of queued commits which are not reachable from both sides
+    let mut pending = 0usize;
+    for (id, flag) in flags.iter() {
+        queue.push((commit_time(*id)?, *id));
+        queued.insert(*id);
+        if *flag != BOTH {
+            pending += 1;
+        }
+    }
+    while pending > 0 {
+        let Some((_, id)) = queue.pop() else {
+            break;
+        };
+        queued.remove(&id);
+        let flag = flags[&id];
+        if flag != BOTH {
+            pending -= 1;
+        }
+        for parent_id in repo.find_commit(id)?.parent_ids() {
+            let parent_id = parent_id.detach();
+            let parent_flag = flags.entry(parent_id).or_default();
+            let old_flag = *parent_flag;
+            if old_flag | flag == old_flag {
+                continue;
+            }
+            *parent_flag |= flag;
+            if queued.contains(&parent_id) {
+                if *parent_flag == BOTH {
+                    pending -= 1;
+                }
+            } else {
+                queue.push((commit_time(parent_id)?, parent_id));
+                queued.insert(parent_id);
+                if *parent_flag != BOTH {
+                    pending += 1;
+                }
+            }
+        }
+    }
+    let ahead = flags.values().filter(|f| **f == LEFT).count();
+    let behind = flags.values().filter(|f| **f == RIGHT).count();
+    Ok((ahead as u32, behind as u32))
+}
+
+fn refs(repo: &gix::Repository) -> Result<Refs, BoxError> {
+    let head_name = repo.head_name()?;
+    // (creatordate, refname, ref)
+    let mut entries: Vec<(i64, String, Ref)> = Vec::new();
+    for r in repo.references()?.all()? {
+        let r = r?;
+        let fullname = r.name().as_bstr().to_str_lossy().into_owned();
+        let components: Vec<&str> = fullname.split('/').collect();
+        if components.len() < 3 || components[0] != "refs" {
+            continue;
+        }
+        let Some(id) = direct_id(&r) else {
+            continue;
+        };
+        let object = repo.find_object(id)?;
+        let (date, deref) = match object.kind {
+            gix::object::Kind::Commit => (object.into_commit().time()?.seconds, None),
+            gix::object::Kind::Tag => {
+                let tag = object.into_tag();
+                let tag = tag.decode()?;
+                let date = match tag.tagger {
+                    Some(tagger) => tagger.time()?.seconds,
+                    None => 0,
+                };
+                (date, Some(tag.target()))
+            }
+            _ => (0, None),
+        };
+        let name = components[2..].join("/");
+        let entry = match components[1] {
+            "heads" => {
+                let current = head_name.as_ref().is_some_and(|h| h.as_ref() == r.name());
+                let upstream = upstream_of(repo, r.name());
+                let upstream_id = match upstream {
+                    Some(ref upstream) => repo
+                        .try_find_reference(upstream.as_ref())?
+                        .and_then(|u| direct_id(&u)),
+                    None => None,
+                };
+                let (ahead, behind) = match upstream_id {
+                    Some(upstream_id) => ahead_behind(repo, id, upstream_id)?,
+                    None => (0, 0),
+                };
+                Ref::Branch {
+                    fullname: fullname.clone(),
+                    id: id.to_string(),
+                    name,
+                    current,
+                    upstream: upstream.map(|u| u.shorten().to_str_lossy().into_owned()),
+                    ahead,
+                    behind,
+                    worktree: None,
+                }
+            }
+            "tags" => Ref::Tag {
+                fullname: fullname.clone(),
+                id: deref.unwrap_or(id).to_string(),
+                tag_sha: id.to_string(),
+                name,
+            },
+            "remotes" => Ref::Remote {
+                fullname: fullname.clone(),
+                id: id.to_string(),
+                remote: components[2].to_string(),
+                name: components[3..].join("/"),
+            },
+            "bisect" => match bisect::bisect_ref(&fullname, &id.to_string()) {
+                Some(r) => r,
+                None => continue,
+            },
+            _ => continue,
+        };
+        entries.push((date, fullname, entry));
+    }
+    // same as `--sort -creatordate`, ties are sorted by refname
+    entries.sort_by(|(date1, name1, _), (date2, name2, _)| {
+        date2.cmp(date1).then_with(|| name1.cmp(name2))
+    });
+
+    let merge_heads = match std::fs::read_to_string(repo.git_dir().join("MERGE_HEAD")) {
+        Ok(content) => content.lines().map(|l| l.to_string()).collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(Refs {
+        head: repo.head_id().ok().map(|id| id.to_string()),
+        merge_heads,
+        refs: entries.into_iter().map(|(_, _, r)| r).collect(),
+        worktrees: Vec::new(),
+    })
+}
+
+/// Gets `XY` of unmerged entries of `git status --porcelain`.
+fn conflict_type(conflict: Conflict) -> &'static str {
+    match conflict {
+        Conflict::BothDeleted => "DD",
+        Conflict::AddedByUs => "AU",
+        Conflict::DeletedByThem => "UD",
+        Conflict::AddedByThem => "UA",
+        Conflict::DeletedByUs => "DU",
+        Conflict::BothAdded => "AA",
+        Conflict::BothModified => "UU",
+    }
+}
+
+fn is_type_changed(mode1: gix::index::entry::Mode, mode2: gix::index::entry::Mode) -> bool {
+    let normalize = |mode: gix::index::entry::Mode| {
+        if mode == gix::index::entry::Mode::FILE_EXECUTABLE {
+            gix::index::entry::Mode::FILE
+        } else {
+            mode
+        }
+    };
+    normalize(mode1) != normalize(mode2)
+}
+
+/// Gets `<sub>` flags of `git status --porcelain=v2` from the status of a submodule.
+fn submodule_change(status: &gix::submodule::Status) -> SubmoduleChange {
+    let changes = status.changes.as_deref().unwrap_or_default();
+    let is_untracked = |item: &gix::status::Item| match item {
+        gix::status::Item::IndexWorktree(index_worktree::Item::DirectoryContents {
+            entry, ..
+        }) => entry.status == gix::dir::entry::Status::Untracked,
+        _ => false,
+    };
+    let is_modified = |item: &gix::status::Item| {
+        !matches!(
+            item,
+            gix::status::Item::IndexWorktree(index_worktree::Item::DirectoryContents { .. })
+        )
+    };
+    SubmoduleChange {
+        commit_changed: status.checked_out_head_id != status.index_id,
+        modified: changes.iter().any(is_modified),
+        untracked: changes.iter().any(is_untracked),
+    }
+}
+
+/// Gets the same entries as `git status --porcelain=v2 --find-renames --untracked=all`
+fn status(repo: &gix::Repository) -> Result<Vec<WorkingTreeFileEntry>, BoxError> {
+    let items = repo
+        .status(gix::progress::Discard)?
+        .untracked_files(UntrackedFiles::Files)
+        .tree_index_track_renames(TrackRenames::Given(Default::default()))
+        .into_iter(None)?;
+    let mut staged: BTreeMap<String, WorkingTreeFileEntry> = BTreeMap::new();
+    let mut unstaged: BTreeMap<String, WorkingTreeFileEntry> = BTreeMap::new();
+    let mut unmerged: BTreeMap<String, WorkingTreeFileEntry> = BTreeMap::new();
+    let mut untracked: Vec<WorkingTreeFileEntry> = Vec::new();
+    for item in items {
+        match item? {
+            gix::status::Item::IndexWorktree(index_worktree::Item::Modification {
+                rela_path,
+                status,
+                ..
+            }) => {
+                let path = rela_path.to_str_lossy().into_owned();
+                let quoted_path = quote_invalid_path(&rela_path);
+                let status_code = match status {
+                    EntryStatus::Conflict { summary, .. } => {
+                        let entry = WorkingTreeFileEntry::unmerged(&path, conflict_type(summary))
+                            .with_quoted_path(quoted_path);
+                        unmerged.insert(path, entry);
+                        continue;
+                    }
+                    EntryStatus::Change(Change::Removed) => "D",
+                    EntryStatus::Change(Change::Type { .. }) => "T",
+                    EntryStatus::Change(Change::Modification { .. }) => "M",
+                    EntryStatus::Change(Change::SubmoduleModification(ref sm)) => {
+                        let mut entry = WorkingTreeFileEntry::ordinal(&path, "M", true)
+                            .with_quoted_path(quoted_path);
+                        entry.submodule = Some(submodule_change(sm));
+                        unstaged.insert(path, entry);
+                        continue;
+                    }
+                    EntryStatus::IntentToAdd => "A",
+                    EntryStatus::NeedsUpdate(_) => continue,
+                };
+                let entry = WorkingTreeFileEntry::ordinal(&path, status_code, true)
+                    .with_quoted_path(quoted_path);
+                unstaged.insert(path, entry);
+            }
+            gix::status::Item::IndexWorktree(index_worktree::Item::DirectoryContents {
+                entry,
+                ..
+            }) => {
+                if entry.status != gix::dir::entry::Status::Untracked {
+                    continue;
+                }
+                let mut rela_path = entry.rela_path.clone();
+                if entry.disk_kind.is_some_and(|kind| {
+                    kind != gix::dir::entry::Kind::File && kind != gix::dir::entry::Kind::Symlink
+                }) {
+                    rela_path.push(b'/');
+                }
+                let path = rela_path.to_str_lossy();
+                untracked.push(
+                    WorkingTreeFileEntry::untracked(&path)
+                        .with_quoted_path(quote_invalid_path(&rela_path)),
+                );
+            }
+            gix::status::Item::IndexWorktree(_) => {}
+            gix::status::Item::TreeIndex(change) => {
+                use gix::diff::index::ChangeRef;
+                let (path, entry) = match change {
+                    ChangeRef::Addition { location, .. } => {
+                        let path = location.to_str_lossy().into_owned();
+                        let entry = WorkingTreeFileEntry::ordinal(&path, "A", false)
+                            .with_quoted_path(quote_invalid_path(&location));
+                        (path, entry)
+                    }
+                    ChangeRef::Deletion { location, .. } => {
+                        let path = location.to_str_lossy().into_owned();
+                        let entry = WorkingTreeFileEntry::ordinal(&path, "D", false)
+                            .with_quoted_path(quote_invalid_path(&location));
+                        (path, entry)
+                    }
+                    ChangeRef::Modification {
+                        location,
+                        previous_entry_mode,
+                        entry_mode,
+                        ..
+                    } => {
+                        let path = location.to_str_lossy().into_owned();
+                        let status_code = if is_type_changed(previous_entry_mode, entry_mode) {
+                            "T"
+                        } else {
+                            "M"
+                        };
+                        let entry = WorkingTreeFileEntry::ordinal(&path, status_code, false)
+                            .with_quoted_path(quote_invalid_path(&location));
+                        (path, entry)
+                    }
+                    ChangeRef::Rewrite {
+                        source_location,
+                        location,
+                        copy,
+                        ..
+                    } => {
+                        let path = location.to_str_lossy().into_owned();
+                        let entry = WorkingTreeFileEntry::renamed_or_copied(
+                            &path,
+                            if copy { "C" } else { "R" },
+                            &source_location.to_str_lossy(),
+                            false,
+                        )
+                        .with_quoted_path(quote_invalid_path(&location));
+                        (path, entry)
+                    }
+                };
+                staged.insert(path, entry);
+            }
+        }
+    }
+    let paths = staged
+        .keys()
+        .chain(unstaged.keys())
+        .chain(unmerged.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let mut ret: Vec<WorkingTreeFileEntry> = Vec::new();
+    for path in paths {
+        if let Some(entry) = unmerged.remove(&path) {
+            ret.push(entry);
+            continue;
+        }
+        ret.extend(staged.remove(&path));
+        ret.extend(unstaged.remove(&path));
+    }
+    untracked.sort_by(|a, b| a.path.cmp(&b.path));
+    ret.extend(untracked);
+    Ok(ret)
+}
+
+/// Gets the content which is compared by `git diff`.
+fn diff_content(
+    repo: &gix::Repository,
+    id: ObjectId,
+    kind: EntryKind,
+) -> Result<Vec<u8>, BoxError> {
+    if kind == EntryKind::Commit {
+        // submodule
+        Ok(format!("Subproject commit {}\n", id).into_bytes())
+    } else {
+        Ok(repo.find_object(id)?.detach().data)
+    }
+}
+
+/// Counts inserted and deleted lines like `git diff --numstat`
+fn numstat(old: &[u8], new: &[u8]) -> FileDelta {
+    let is_binary = |content: &[u8]| content[..content.len().min(BINARY_CHECK_SIZE)].contains(&0);
+    if is_binary(old) || is_binary(new) {
+        return FileDelta::Binary;
+    }
+    let input = InternedInput::new(
+        sources::byte_lines_with_terminator(old),
+        sources::byte_lines_with_terminator(new),
+    );
+    let counter = gix::diff::blob::diff(Algorithm::Myers, &input, Counter::default());
+    FileDelta::Text {
+        insertions: counter.insertions,
+        deletions: counter.removals,
+    }
+}
+
+fn normalize_kind(kind: EntryKind) -> EntryKind {
+    if kind == EntryKind::BlobExecutable {
+        EntryKind::Blob
+    } else {
+        kind
+    }
+}
+
+/// Gets the same entries as `git diff {revspec1} {revspec2} --raw --numstat --find-renames`
+fn diff(
+    repo: &gix::Repository,
+    revspec1: &str,
+    revspec2: &str,
+) -> Result<Vec<FileEntry>, BoxError> {
+    let old_tree = repo.rev_parse_single(revspec1)?.object()?.peel_to_tree()?;
+    let new_tree = repo.rev_parse_single(revspec2)?.object()?.peel_to_tree()?;
+    let mut options = gix::diff::Options::default();
+    options
+        .track_path()
+        .track_rewrites(Some(gix::diff::Rewrites::default()));
+    let changes = repo.diff_tree_to_tree(&old_tree, &new_tree, options)?;
+    let mut files: Vec<FileEntry> = Vec::new();
+    for change in changes {
+        let file = match change {
+            ChangeDetached::Addition {
+                location,
+                entry_mode,
+                id,
+                ..
+            } => {
+                if entry_mode.is_tree() {
+                    continue;
+                }
+                let new = diff_content(repo, id, entry_mode.kind())?;
+                let path = location.to_str_lossy();
+                FileEntry::new(&path, "A", None, Some(numstat(&[], &new)))
+            }
+            ChangeDetached::Deletion {
+                location,
+                entry_mode,
+                id,
+                ..
+            } => {
+                if entry_mode.is_tree() {
+                    continue;
+                }
+                let old = diff_content(repo, id, entry_mode.kind())?;
+                let path = location.to_str_lossy();
+                FileEntry::new(&path, "D", None, Some(numstat(&old, &[])))
+            }
+            ChangeDetached::Modification {
+                location,
+                previous_entry_mode,
+                previous_id,
+                entry_mode,
+                id,
+            } => {
+                if previous_entry_mode.is_tree() && entry_mode.is_tree() {
+                    continue;
+                }
+                let status_code = if normalize_kind(previous_entry_mode.kind())
+                    == normalize_kind(entry_mode.kind())
+                {
+                    "M"
+                } else {
+                    "T"
+                };
+                let old = diff_content(repo, previous_id, previous_entry_mode.kind())?;
+                let new = diff_content(repo, id, entry_mode.kind())?;
+                let path = location.to_str_lossy();
+                FileEntry::new(&path, status_code, None, Some(numstat(&old, &new)))
+            }
+            ChangeDetached::Rewrite {
+                source_location,
+                source_entry_mode,
+                source_id,
+                diff,
+                entry_mode,
+                id,
+                location,
+                copy,
+                ..
+            } => {
+                let score = match diff {
+                    Some(diff) => (diff.similarity * 100.0) as u32,
+                    None => 100,
+                };
+                let status_code = format!("{}{:03}", if copy { "C" } else { "R" }, score);
+                let old = diff_content(repo, source_id, source_entry_mode.kind())?;
+                let new = diff_content(repo, id, entry_mode.kind())?;
+                let path = location.to_str_lossy();
+                let old_path = source_location.to_str_lossy();
+                FileEntry::new(
+                    &path,
+                    &status_code,
+                    Some(&old_path),
+                    Some(numstat(&old, &new)),
+                )
+            }
+        };
+        files.push(file);
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn tree_entries(
+    repo: &gix::Repository,
+    tree: gix::Tree,
+    prefix: &BStr,
+) -> Result<Vec<LstreeEntry>, BoxError> {
+    let mut ret: Vec<LstreeEntry> = Vec::new();
+    for entry in tree.decode()?.entries.iter() {
+        let mut raw_path = prefix.to_owned();
+        if !raw_path.is_empty() {
+            raw_path.push(b'/');
+        }
+        raw_path.extend_from_slice(entry.filename);
+        let path = raw_path.to_str_lossy().into_owned();
+        let quoted_path = quote_invalid_path(&raw_path);
+        if entry.mode.is_tree() {
+            let children = tree_entries(repo, repo.find_tree(entry.oid)?, raw_path.as_bstr())?;
+            ret.push(
+                LstreeEntry::new(LstreeData::Tree(path), Some(children))
+                    .with_quoted_path(quoted_path),
+            );
+        } else if entry.mode.is_blob_or_symlink() {
+            ret.push(LstreeEntry::new(LstreeData::Blob(path), None).with_quoted_path(quoted_path));
+        } else if entry.mode.is_commit() {
+            ret.push(
+                LstreeEntry::new(LstreeData::Submodule(path), None).with_quoted_path(quoted_path),
+            );
+        }
+    }
+    Ok(ret)
+}
+
+fn tree(repo: &gix::Repository, revspec: &str) -> Result<Vec<LstreeEntry>, BoxError> {
+    let tree = repo.rev_parse_single(revspec)?.object()?.peel_to_tree()?;
+    tree_entries(repo, tree, BStr::new(""))
+}
+
+fn read_content(
+    repo: &gix::Repository,
+    rel_path: &str,
+    revspec: &str,
+    apply_filters: bool,
+) -> Result<Vec<u8>, BoxError> {
+    let object_name = file::object_name(rel_path, revspec);
+    let id = repo.rev_parse_single(object_name.as_str())?;
+    let content = id.object()?.detach().data;
+    if !apply_filters {
+        return Ok(content);
+    }
+    let (mut pipeline, _) = repo.filter_pipeline(None)?;
+    let mut outcome = pipeline.convert_to_worktree(
+        &content,
+        BStr::new(rel_path),
+        gix::filter::plumbing::driver::apply::Delay::Forbid,
+    )?;
+    if outcome.is_delayed() {
+        return Err(format!("{} is delayed by the filter", rel_path).into());
+    }
+    let mut ret: Vec<u8> = Vec::new();
+    outcome.read_to_end(&mut ret)?;
+    Ok(ret)
+}
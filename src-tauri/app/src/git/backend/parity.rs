@@ -0,0 +1,432 @@
+//! Runs both backends against fixture repositories and checks that the results are the same.
+
+use super::super::cat_file::ObjectReader;
+use super::super::{diff, workingtree};
+use super::{Backend, GitBackend};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use types::*;
+
+static REPO_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// A repository in the temporary directory, which is removed with its bare remote when dropped.
+struct TempRepo(PathBuf);
+
+impl Deref for TempRepo {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempRepo {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+        let _ = std::fs::remove_dir_all(self.0.with_extension("remote"));
+    }
+}
+
+fn git(repo: &Path, args: &[&str]) -> String {
+    git_at(repo, 1700000000, args)
+}
+
+/// Runs git with the author and committer date `time`, so that the order of commits
+/// and refs is deterministic.
+fn git_at(repo: &Path, time: u64, args: &[&str]) -> String {
+    let date = format!("{} +0900", time);
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_AUTHOR_DATE", &date)
+        .env("GIT_COMMITTER_DATE", &date)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?}: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn commit(repo: &Path, message: &str, time: u64) {
+    git_at(
+        repo,
+        time,
+        &["commit", "-q", "--allow-empty", "-m", message],
+    );
+}
+
+fn write(repo: &Path, rel_path: &str, content: &[u8]) {
+    let path = repo.join(rel_path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn init(name: &str) -> TempRepo {
+    // tests using the same fixture run in parallel
+    let seq = REPO_SEQ.fetch_add(1, Ordering::Relaxed);
+    let dir = TempRepo(std::env::temp_dir().join(format!(
+        "backend-{}-{}-{}",
+        name,
+        std::process::id(),
+        seq
+    )));
+    let _ = std::fs::remove_dir_all(&*dir);
+    std::fs::create_dir_all(&*dir).unwrap();
+    git(&dir, &["init", "-q", "-b", "main"]);
+    git(&dir, &["config", "user.name", "Tester"]);
+    git(&dir, &["config", "user.email", "tester@example.com"]);
+    git(&dir, &["config", "core.autocrlf", "false"]);
+    dir
+}
+
+fn backends(repo_path: &Path) -> (Backend, Backend) {
+    (
+        Backend::new(GitBackendKind::Cli, repo_path, ObjectReader::new(repo_path)),
+        Backend::new(
+            GitBackendKind::Gitoxide,
+            repo_path,
+            ObjectReader::new(repo_path),
+        ),
+    )
+}
+
+/// main and feature branches with a merge, tags, a rename, a binary file,
+/// a file with eol conversion, and a remote which is ahead and behind.
+fn history_fixture() -> TempRepo {
+    let dir = init("history");
+    write(&dir, ".gitattributes", b"*.crlf text eol=crlf\n");
+    write(&dir, "a.txt", b"1\n2\n3\n");
+    write(&dir, "dir/b.txt", b"b\n");
+    write(&dir, "c.crlf", b"x\ny\n");
+    git(&dir, &["add", "."]);
+    commit(&dir, "init", 1700000100);
+    git(&dir, &["tag", "-a", "v1", "-m", "version 1"]);
+
+    git(&dir, &["checkout", "-q", "-b", "feature"]);
+    write(&dir, "a.txt", b"1\n2\n3\n4\n");
+    write(&dir, "bin.dat", b"\0\x01\x02");
+    git(&dir, &["add", "."]);
+    commit(&dir, "feature 1", 1700000200);
+
+    git(&dir, &["checkout", "-q", "main"]);
+    git(&dir, &["mv", "dir/b.txt", "dir/renamed.txt"]);
+    commit(&dir, "rename", 1700000300);
+    git(&dir, &["tag", "light"]);
+    git_at(
+        &dir,
+        1700000400,
+        &["merge", "-q", "--no-ff", "-m", "merge feature", "feature"],
+    );
+    commit(&dir, "after merge", 1700000500);
+
+    let remote = dir.with_extension("remote");
+    let _ = std::fs::remove_dir_all(&remote);
+    git(
+        &dir,
+        &["clone", "-q", "--bare", ".", remote.to_str().unwrap()],
+    );
+    git(&dir, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&dir, &["fetch", "-q", "origin"]);
+    git(
+        &dir,
+        &["branch", "-q", "--set-upstream-to=origin/main", "main"],
+    );
+    git(&dir, &["branch", "-q", "--set-upstream-to=main", "feature"]);
+    git(&dir, &["reset", "-q", "--hard", "HEAD~"]);
+    commit(&dir, "local only", 1700000600);
+    dir
+}
+
+#[tokio::test]
+async fn test_log_and_refs() {
+    let dir = history_fixture();
+    let (cli, gitoxide) = backends(&dir);
+    let head = git(&dir, &["rev-parse", "HEAD~"]);
+    for heads in [vec![], vec![head.trim()]] {
+        assert_eq!(
+            cli.log(0, &heads).await.unwrap(),
+            gitoxide.log(0, &heads).await.unwrap()
+        );
+        assert_eq!(
+            cli.log(3, &heads).await.unwrap(),
+            gitoxide.log(3, &heads).await.unwrap()
+        );
+    }
+    assert_eq!(cli.refs().await.unwrap(), gitoxide.refs().await.unwrap());
+
+    git(&dir, &["checkout", "-q", "--detach", "v1"]);
+    assert_eq!(cli.refs().await.unwrap(), gitoxide.refs().await.unwrap());
+
+    git(&dir, &["bisect", "start", "main", "v1"]);
+    git(&dir, &["bisect", "skip"]);
+    let refs = cli.refs().await.unwrap();
+    let verdicts = refs
+        .refs
+        .iter()
+        .filter_map(|r| match r {
+            Ref::Bisect { verdict, .. } => Some(*verdict),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(3, verdicts.len());
+    assert_eq!(refs, gitoxide.refs().await.unwrap());
+    git(&dir, &["bisect", "reset"]);
+}
+
+#[tokio::test]
+async fn test_diff_tree_and_content() {
+    let dir = history_fixture();
+    let (cli, gitoxide) = backends(&dir);
+    for (revspec1, revspec2) in [("v1", "main"), ("v1", "feature"), ("main~", "main")] {
+        assert_eq!(
+            cli.diff(revspec1, revspec2).await.unwrap(),
+            gitoxide.diff(revspec1, revspec2).await.unwrap()
+        );
+    }
+    for revspec in ["v1", "main", "feature"] {
+        assert_eq!(
+            cli.tree(revspec).await.unwrap(),
+            gitoxide.tree(revspec).await.unwrap()
+        );
+    }
+    assert_eq!(
+        cli.blame("a.txt", "main").await.unwrap(),
+        gitoxide.blame("a.txt", "main").await.unwrap()
+    );
+
+    write(&dir, "c.crlf", b"staged\n");
+    git(&dir, &["add", "c.crlf"]);
+    for (rel_path, revspec) in [
+        ("a.txt", "HEAD"),
+        ("dir/renamed.txt", "main"),
+        ("bin.dat", "feature"),
+        ("c.crlf", "HEAD"),
+        ("c.crlf", "STAGED"),
+    ] {
+        for apply_filters in [false, true] {
+            assert_eq!(
+                cli.read_content(rel_path, revspec, apply_filters)
+                    .await
+                    .unwrap(),
+                gitoxide
+                    .read_content(rel_path, revspec, apply_filters)
+                    .await
+                    .unwrap()
+            );
+        }
+    }
+    assert!(gitoxide
+        .read_content("missing", "HEAD", false)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_status() {
+    let dir = init("status");
+    write(&dir, "modified.txt", b"1\n");
+    write(&dir, "deleted.txt", b"1\n");
+    write(&dir, "renamed.txt", b"1\n2\n3\n4\n5\n");
+    write(&dir, "both.txt", b"1\n");
+    git(&dir, &["add", "."]);
+    commit(&dir, "init", 1700000100);
+
+    write(&dir, "modified.txt", b"2\n");
+    std::fs::remove_file(dir.join("deleted.txt")).unwrap();
+    std::fs::create_dir(dir.join("new")).unwrap();
+    git(&dir, &["mv", "renamed.txt", "new/renamed.txt"]);
+    write(&dir, "both.txt", b"2\n");
+    git(&dir, &["add", "both.txt"]);
+    write(&dir, "both.txt", b"3\n");
+    write(&dir, "added.txt", b"added\n");
+    git(&dir, &["add", "added.txt"]);
+    write(&dir, "untracked/file.txt", b"untracked\n");
+
+    let (cli, gitoxide) = backends(&dir);
+    assert_eq!(
+        cli.status().await.unwrap(),
+        gitoxide.status().await.unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_status_with_conflicts() {
+    let dir = init("conflict");
+    write(&dir, "a.txt", b"base\n");
+    write(&dir, "b.txt", b"base\n");
+    git(&dir, &["add", "."]);
+    commit(&dir, "init", 1700000100);
+    git(&dir, &["checkout", "-q", "-b", "other"]);
+    write(&dir, "a.txt", b"other\n");
+    git(&dir, &["rm", "-q", "b.txt"]);
+    write(&dir, "c.txt", b"other\n");
+    git(&dir, &["add", "."]);
+    commit(&dir, "other", 1700000200);
+    git(&dir, &["checkout", "-q", "main"]);
+    write(&dir, "a.txt", b"main\n");
+    write(&dir, "b.txt", b"main\n");
+    write(&dir, "c.txt", b"main\n");
+    git(&dir, &["add", "."]);
+    commit(&dir, "main", 1700000300);
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&*dir)
+        .args(["merge", "other"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let (cli, gitoxide) = backends(&dir);
+    assert_eq!(
+        cli.status().await.unwrap(),
+        gitoxide.status().await.unwrap()
+    );
+    assert_eq!(cli.refs().await.unwrap(), gitoxide.refs().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_submodules() {
+    let sub = init("submodule-sub");
+    write(&sub, "a.txt", b"1\n");
+    git(&sub, &["add", "."]);
+    commit(&sub, "sub init", 1700000100);
+    let dir = init("submodule");
+    write(&dir, "b.txt", b"1\n");
+    for path in ["lib/modified", "lib/untracked", "lib/moved", "lib/clean"] {
+        git(
+            &dir,
+            &[
+                "-c",
+                "protocol.file.allow=always",
+                "submodule",
+                "add",
+                "-q",
+                sub.to_str().unwrap(),
+                path,
+            ],
+        );
+    }
+    git(&dir, &["add", "."]);
+    commit(&dir, "init", 1700000200);
+
+    write(&dir, "lib/modified/a.txt", b"2\n");
+    write(&dir, "lib/untracked/new.txt", b"new\n");
+    let moved = dir.join("lib/moved");
+    git(&moved, &["config", "user.name", "Tester"]);
+    git(&moved, &["config", "user.email", "tester@example.com"]);
+    commit(&moved, "moved", 1700000300);
+
+    let (cli, gitoxide) = backends(&dir);
+    let status = cli.status().await.unwrap();
+    assert_eq!(3, status.len());
+    let change = |path: &str| {
+        status
+            .iter()
+            .find(|e| e.path == path)
+            .and_then(|e| e.submodule.clone())
+            .unwrap()
+    };
+    assert!(change("lib/modified").modified);
+    assert!(change("lib/untracked").untracked);
+    assert!(change("lib/moved").commit_changed);
+    assert!(!change("lib/moved").modified);
+    assert_eq!(status, gitoxide.status().await.unwrap());
+    assert_eq!(
+        cli.tree("HEAD").await.unwrap(),
+        gitoxide.tree("HEAD").await.unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_non_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = init("non-utf8");
+    // "日本語" in Shift_JIS, with `encoding` header
+    let message = dir.join("message");
+    std::fs::write(&message, b"\x93\xfa\x96\x7b\x8c\xea\n").unwrap();
+    git(
+        &dir,
+        &[
+            "-c",
+            "i18n.commitEncoding=Shift_JIS",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-F",
+            message.to_str().unwrap(),
+        ],
+    );
+    // Latin-1 without `encoding` header (`git commit` would convert it to UTF-8),
+    // and a file name which is not UTF-8
+    std::fs::remove_file(&message).unwrap();
+    std::fs::write(dir.join(OsStr::from_bytes(b"caf\xe9.txt")), b"1\n").unwrap();
+    git(&dir, &["add", "."]);
+    let tree = git(&dir, &["write-tree"]);
+    let parent = git(&dir, &["rev-parse", "HEAD"]);
+    let mut content = format!(
+        "tree {}\nparent {}\nauthor Tester <tester@example.com> 1700000100 +0900\n\
+         committer Tester <tester@example.com> 1700000100 +0900\n\n",
+        tree.trim(),
+        parent.trim()
+    )
+    .into_bytes();
+    content.extend_from_slice(b"caf\xe9\n");
+    let object = dir.join(".git/commit");
+    std::fs::write(&object, content).unwrap();
+    let id = git(
+        &dir,
+        &[
+            "hash-object",
+            "-t",
+            "commit",
+            "-w",
+            object.to_str().unwrap(),
+        ],
+    );
+    git(&dir, &["reset", "-q", "--soft", id.trim()]);
+    std::fs::write(dir.join(OsStr::from_bytes(b"caf\xe9.txt")), b"2\n").unwrap();
+    std::fs::write(dir.join(OsStr::from_bytes(b"\x93\xfa.txt")), b"1\n").unwrap();
+
+    let (cli, gitoxide) = backends(&dir);
+    let commits = cli.log(0, &[]).await.unwrap();
+    assert_eq!("caf\u{FFFD}", commits[0].summary);
+    assert_eq!("日本語", commits[1].summary);
+    assert_eq!(commits, gitoxide.log(0, &[]).await.unwrap());
+    let status = cli.status().await.unwrap();
+    let mut quoted_paths: Vec<&str> = status
+        .iter()
+        .filter_map(|e| e.quoted_path.as_deref())
+        .collect();
+    quoted_paths.sort();
+    assert_eq!(vec![r#""\223\372.txt""#, r#""caf\351.txt""#], quoted_paths);
+    assert_eq!(status, gitoxide.status().await.unwrap());
+    // quoted paths are passed to git as the raw bytes
+    let udiff = diff::get_workingtree_udiff(&dir, quoted_paths[1], false)
+        .await
+        .unwrap();
+    assert!(!udiff.is_empty());
+    workingtree::stage(&dir, &quoted_paths).await.unwrap();
+    assert_eq!(
+        "M\t\"caf\\351.txt\"\nA\t\"\\223\\372.txt\"\n",
+        git(&dir, &["diff", "--cached", "--name-status"])
+    );
+    assert_eq!(
+        cli.tree("HEAD").await.unwrap(),
+        gitoxide.tree("HEAD").await.unwrap()
+    );
+    assert_eq!(
+        cli.diff("HEAD~", "HEAD").await.unwrap(),
+        gitoxide.diff("HEAD~", "HEAD").await.unwrap()
+    );
+}
@@ -0,0 +1,284 @@
+use super::{decode_output, exec, GitError};
+use regex::Regex;
+use std::path::Path;
+use types::{BisectLog, BisectLogEntry, BisectStep, BisectVerdict, Ref};
+
+fn parse_verdict(term: &str) -> Option<BisectVerdict> {
+    // "new" and "old" are alternative terms of "bad" and "good"
+    match term {
+        "bad" | "new" => Some(BisectVerdict::Bad),
+        "good" | "old" => Some(BisectVerdict::Good),
+        "skip" => Some(BisectVerdict::Skip),
+        _ => None,
+    }
+}
+
+fn verdict_term(verdict: BisectVerdict) -> &'static str {
+    match verdict {
+        BisectVerdict::Good => "good",
+        BisectVerdict::Bad => "bad",
+        BisectVerdict::Skip => "skip",
+    }
+}
+
+/**
+ * Creates a `Ref` from a ref under `refs/bisect/`
+ *
+ * refs/bisect/bad
+ * refs/bisect/good-{id}
+ * refs/bisect/skip-{id}
+ */
+pub fn bisect_ref(fullname: &str, id: &str) -> Option<Ref> {
+    let name = fullname.strip_prefix("refs/bisect/")?;
+    let term = name.split_once('-').map_or(name, |(term, _)| term);
+    Some(Ref::Bisect {
+        id: id.to_string(),
+        fullname: fullname.to_string(),
+        name: term.to_string(),
+        verdict: parse_verdict(term)?,
+    })
+}
+
+/**
+ * parse output of `git bisect start|good|bad|skip`
+ *
+ * Bisecting: 2 revisions left to test after this (roughly 1 step)
+ * [{id}] {summary}
+ *
+ * or
+ *
+ * {id} is the first bad commit
+ *
+ * or
+ *
+ * There are only 'skip'ped commits left to test.
+ * The first bad commit could be any of:
+ * {id}
+ * {id}
+ * We cannot bisect more!
+ */
+fn parse_bisect_output(output: &str) -> BisectStep {
+    let bisecting = Regex::new(
+        r"^Bisecting: (\d+) revisions? left to test after this \(roughly (\d+) steps?\)",
+    )
+    .unwrap();
+    let candidate = Regex::new(r"^\[([0-9a-f]+)\]").unwrap();
+    let first_bad = Regex::new(r"^([0-9a-f]+) is the first bad commit").unwrap();
+    let id = Regex::new(r"^[0-9a-f]{40,64}$").unwrap();
+    let mut step = BisectStep {
+        current: None,
+        remaining: None,
+        steps: None,
+        first_bad: None,
+        possible_first_bad: Vec::new(),
+    };
+    let mut in_possible_list = false;
+    for line in output.lines() {
+        if let Some(c) = bisecting.captures(line) {
+            step.remaining = c[1].parse().ok();
+            step.steps = c[2].parse().ok();
+        } else if let Some(c) = candidate.captures(line) {
+            step.current = Some(c[1].to_string());
+        } else if let Some(c) = first_bad.captures(line) {
+            step.first_bad = Some(c[1].to_string());
+            // the detail of the commit follows
+            break;
+        } else if line.starts_with("The first bad commit could be any of:") {
+            in_possible_list = true;
+        } else if in_possible_list && id.is_match(line) {
+            step.possible_first_bad.push(line.to_string());
+        } else {
+            in_possible_list = false;
+        }
+    }
+    step
+}
+
+/**
+ * parse output of `git bisect log`
+ *
+ * git bisect start
+ * # status: waiting for both good and bad commits
+ * # bad: [{id}] {summary}
+ * git bisect bad {id}
+ * # good: [{id}] {summary}
+ * git bisect good {id}
+ * # first bad commit: [{id}] {summary}
+ *
+ * (or `# possible first bad commit: [{id}] {summary}` for each candidate
+ *  when only skipped commits are left)
+ */
+fn parse_bisect_log(output: &str) -> BisectLog {
+    let comment = Regex::new(r"^# ([a-z ]+): \[([0-9a-f]+)\] ?(.*)$").unwrap();
+    let mut log = BisectLog {
+        entries: Vec::new(),
+        first_bad: None,
+        possible_first_bad: Vec::new(),
+    };
+    for line in output.lines() {
+        let Some(c) = comment.captures(line) else {
+            continue;
+        };
+        let id = c[2].to_string();
+        match &c[1] {
+            "first bad commit" => log.first_bad = Some(id),
+            "possible first bad commit" => {
+                if !log.possible_first_bad.contains(&id) {
+                    log.possible_first_bad.push(id);
+                }
+            }
+            term => {
+                if let Some(verdict) = parse_verdict(term) {
+                    log.entries.push(BisectLogEntry {
+                        verdict,
+                        id,
+                        summary: c[3].to_string(),
+                    });
+                }
+            }
+        }
+    }
+    log
+}
+
+async fn exec_bisect(repo_path: &Path, args: &[&str]) -> Result<BisectStep, GitError> {
+    let output = exec(repo_path, "bisect", args, &[]).await?;
+    let step = parse_bisect_output(&decode_output(&output.stdout));
+    // exit code is 2 if only skipped commits are left
+    if step.possible_first_bad.is_empty() {
+        GitError::assert_process_output("bisect", &output)?;
+    }
+    Ok(step)
+}
+
+/// Starts bisect, and marks `bad` and `good` commits if specified.
+pub async fn start(
+    repo_path: &Path,
+    bad: Option<&str>,
+    good: &[String],
+) -> Result<BisectStep, GitError> {
+    let mut step = exec_bisect(repo_path, &["start"]).await?;
+    if let Some(bad) = bad {
+        step = exec_bisect(repo_path, &["bad", bad]).await?;
+    }
+    if !good.is_empty() {
+        let mut args = vec!["good"];
+        args.extend(good.iter().map(|g| g.as_str()));
+        step = exec_bisect(repo_path, &args).await?;
+    }
+    Ok(step)
+}
+
+/// Marks `revs` (or the current commit if empty) with `verdict`,
+/// and checks out the next commit to be tested.
+pub async fn mark(
+    repo_path: &Path,
+    verdict: BisectVerdict,
+    revs: &[String],
+) -> Result<BisectStep, GitError> {
+    let mut args = vec![verdict_term(verdict)];
+    args.extend(revs.iter().map(|r| r.as_str()));
+    exec_bisect(repo_path, &args).await
+}
+
+/// Finishes bisect, and checks out `commit` (or the commit before bisect if None).
+pub async fn reset(repo_path: &Path, commit: Option<&str>) -> Result<(), GitError> {
+    let mut args = vec!["reset"];
+    if let Some(commit) = commit {
+        args.push(commit);
+    }
+    let output = exec(repo_path, "bisect", &args, &[]).await?;
+    GitError::assert_process_output("bisect", &output)?;
+    Ok(())
+}
+
+pub async fn log(repo_path: &Path) -> Result<BisectLog, GitError> {
+    let output = exec(repo_path, "bisect", &["log"], &[]).await?;
+    GitError::assert_process_output("bisect", &output)?;
+    Ok(parse_bisect_log(&decode_output(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bisect_ref() {
+        let r = bisect_ref("refs/bisect/good-1111", "1111").unwrap();
+        assert_eq!(
+            Ref::Bisect {
+                id: "1111".to_owned(),
+                fullname: "refs/bisect/good-1111".to_owned(),
+                name: "good".to_owned(),
+                verdict: BisectVerdict::Good,
+            },
+            r
+        );
+        assert!(matches!(
+            bisect_ref("refs/bisect/bad", "2222"),
+            Some(Ref::Bisect {
+                verdict: BisectVerdict::Bad,
+                ..
+            })
+        ));
+        assert_eq!(None, bisect_ref("refs/bisect/unknown", "3333"));
+        assert_eq!(None, bisect_ref("refs/heads/main", "3333"));
+    }
+
+    #[test]
+    fn test_parse_bisect_output() {
+        let step = parse_bisect_output(
+            "Bisecting: 2 revisions left to test after this (roughly 1 step)\n\
+             [f7cdadb6d4fb5b5d11145cc1442b4c530c5ea300] c3\n",
+        );
+        assert_eq!(Some(2), step.remaining);
+        assert_eq!(Some(1), step.steps);
+        assert_eq!(
+            Some("f7cdadb6d4fb5b5d11145cc1442b4c530c5ea300"),
+            step.current.as_deref()
+        );
+        assert_eq!(None, step.first_bad);
+
+        let step = parse_bisect_output(
+            "f7cdadb6d4fb5b5d11145cc1442b4c530c5ea300 is the first bad commit\n\
+             commit f7cdadb6d4fb5b5d11145cc1442b4c530c5ea300\n",
+        );
+        assert_eq!(
+            Some("f7cdadb6d4fb5b5d11145cc1442b4c530c5ea300"),
+            step.first_bad.as_deref()
+        );
+
+        let step = parse_bisect_output(
+            "There are only 'skip'ped commits left to test.\n\
+             The first bad commit could be any of:\n\
+             e878652d77d360e125bf63e3807d249439e03e92\n\
+             f7cdadb6d4fb5b5d11145cc1442b4c530c5ea300\n\
+             We cannot bisect more!\n",
+        );
+        assert_eq!(2, step.possible_first_bad.len());
+    }
+
+    #[test]
+    fn test_parse_bisect_log() {
+        const OUTPUT: &str = "\
+            git bisect start\n\
+            # status: waiting for both good and bad commits\n\
+            # bad: [732bdf24b2307c0d26bfbf137df530fe2592da0d] c6\n\
+            git bisect bad 732bdf24b2307c0d26bfbf137df530fe2592da0d\n\
+            # good: [fd004a31f2cb57eb52664e67dae8b54460aac0c9] c1\n\
+            git bisect good fd004a31f2cb57eb52664e67dae8b54460aac0c9\n\
+            # skip: [f7cdadb6d4fb5b5d11145cc1442b4c530c5ea300] c3: fix\n\
+            git bisect skip f7cdadb6d4fb5b5d11145cc1442b4c530c5ea300\n\
+            # first bad commit: [e878652d77d360e125bf63e3807d249439e03e92] c4\n";
+        let log = parse_bisect_log(OUTPUT);
+        assert_eq!(3, log.entries.len());
+        assert_eq!(BisectVerdict::Bad, log.entries[0].verdict);
+        assert_eq!("c6", log.entries[0].summary);
+        assert_eq!(BisectVerdict::Skip, log.entries[2].verdict);
+        assert_eq!("c3: fix", log.entries[2].summary);
+        assert_eq!(
+            Some("e878652d77d360e125bf63e3807d249439e03e92"),
+            log.first_bad.as_deref()
+        );
+    }
+}
@@ -1,18 +1,22 @@
-use super::{exec, GitError};
+use super::{decode_output, exec, parse_date, GitError};
 use regex::Regex;
 use std::path::Path;
 use types::BlameEntry;
 
-pub fn parse_blame_output(output: &str) -> Vec<BlameEntry> {
+pub fn parse_blame_output(output: &str) -> Result<Vec<BlameEntry>, GitError> {
     let header_regex = Regex::new(r"^([a-f0-9]{40}) \d+ (\d+) (\d+)$").unwrap();
     let metadata_regex = Regex::new(r"^(author|author-time|summary) (.+)$").unwrap();
     let mut entries: Vec<BlameEntry> = Vec::new();
     let mut previous_id = "";
-    output.lines().for_each(|line| {
+    let unexpected = |line: &str| GitError::UnexpectedOutput {
+        command: String::from("blame"),
+        text: line.to_string(),
+    };
+    for line in output.lines() {
         if let Some(c) = header_regex.captures(line) {
             let id = c.get(1).unwrap().as_str();
-            let start_line: u32 = c[2].parse().unwrap();
-            let line_count: u32 = c[3].parse().unwrap();
+            let start_line: u32 = c[2].parse().map_err(|_| unexpected(line))?;
+            let line_count: u32 = c[3].parse().map_err(|_| unexpected(line))?;
             if previous_id != id {
                 previous_id = id;
                 entries.push(BlameEntry {
@@ -28,13 +32,13 @@ pub fn parse_blame_output(output: &str) -> Vec<BlameEntry> {
                 line_no.push(n);
             }
         } else if let Some(c) = metadata_regex.captures(line) {
-            let entry = entries.last_mut().unwrap();
+            let entry = entries.last_mut().ok_or_else(|| unexpected(line))?;
             match &c[1] {
                 "author" => {
                     entry.author = c[2].to_owned();
                 }
                 "author-time" => {
-                    entry.date = c[2].parse::<u64>().unwrap() * 1000;
+                    entry.date = parse_date("blame", &c[2])?;
                 }
                 "summary" => {
                     entry.summary = c[2].to_owned();
@@ -42,8 +46,8 @@ pub fn parse_blame_output(output: &str) -> Vec<BlameEntry> {
                 _ => {}
             }
         }
-    });
-    return entries;
+    }
+    Ok(entries)
 }
 
 pub async fn blame(
@@ -54,7 +58,6 @@ pub async fn blame(
     let args = &[sha, "--incremental", "--", rel_path];
     let output = exec(repo_path, "blame", args, &[]).await?;
     GitError::assert_process_output("blame", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    let entries = parse_blame_output(stdout);
-    return Ok(entries);
+    let stdout = decode_output(&output.stdout);
+    parse_blame_output(&stdout)
 }
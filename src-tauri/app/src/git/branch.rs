@@ -1,6 +1,9 @@
-use types::{CreateBranchOptions, DeleteBranchOptions, SwitchCreateOptions, SwitchOptions};
+use types::{
+    CreateBranchOptions, DeleteBranchOptions, RenameBranchOptions, SwitchCreateOptions,
+    SwitchOptions,
+};
 
-use super::{exec, switch, GitError};
+use super::{decode_output, exec, switch, GitError};
 use std::path::Path;
 
 pub async fn create_branch(
@@ -46,12 +49,48 @@ pub async fn delete_branch(
     Ok(())
 }
 
+pub async fn rename_branch(
+    repo_path: &Path,
+    options: &RenameBranchOptions,
+) -> Result<(), GitError> {
+    let args = vec![
+        if options.force.is_some_and(|v| v) {
+            "-M"
+        } else {
+            "-m"
+        },
+        &options.old_name,
+        &options.new_name,
+    ];
+    let output = exec(repo_path, "branch", &args, &[]).await?;
+    GitError::assert_process_output("branch", &output)?;
+    Ok(())
+}
+
+pub async fn set_upstream(
+    repo_path: &Path,
+    branch_name: &str,
+    upstream: Option<&str>,
+) -> Result<(), GitError> {
+    let set_upstream_option;
+    let mut args: Vec<&str> = Vec::new();
+    if let Some(upstream) = upstream {
+        set_upstream_option = format!("--set-upstream-to={}", upstream);
+        args.push(&set_upstream_option);
+    } else {
+        args.push("--unset-upstream");
+    }
+    args.push(branch_name);
+    let output = exec(repo_path, "branch", &args, &[]).await?;
+    GitError::assert_process_output("branch", &output)?;
+    Ok(())
+}
+
 pub async fn get_current_branch(repo_path: &Path) -> Result<String, GitError> {
     let args = ["--show-current"];
     let output = exec(repo_path, "branch", &args, &[]).await?;
     GitError::assert_process_output("branch", &output)?;
-    let branch_name = std::str::from_utf8(&output.stdout)
-        .unwrap()
+    let branch_name = decode_output(&output.stdout)
         .trim_end_matches('\n')
         .to_owned();
     Ok(branch_name)
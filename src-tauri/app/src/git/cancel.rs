@@ -0,0 +1,105 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+/// Token to cancel git commands which are executed in `scope`.
+#[derive(Clone, Debug)]
+pub struct CancelToken(Arc<watch::Sender<bool>>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        CancelToken(Arc::new(watch::Sender::new(false)))
+    }
+
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Waits until `cancel` is called.
+    pub async fn cancelled(&self) {
+        let mut rx = self.0.subscribe();
+        // never fails, since the sender is owned by self
+        let _ = rx.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error which is wrapped in `std::io::Error` when a git command is killed,
+/// and converted to `GitError::Cancelled`.
+#[derive(Error, Debug)]
+#[error("{reason}")]
+pub struct Cancelled {
+    pub command: String,
+    pub reason: String,
+}
+
+#[derive(Clone)]
+struct Cancellation {
+    token: CancelToken,
+    deadline: Option<Instant>,
+}
+
+tokio::task_local! {
+    static CURRENT: Cancellation;
+}
+
+/// Runs `f`, and kills git commands executed in it when `token` is cancelled
+/// or `timeout` is elapsed.
+pub async fn scope<F: Future>(token: CancelToken, timeout: Option<Duration>, f: F) -> F::Output {
+    let cancellation = Cancellation {
+        token,
+        deadline: timeout.map(|t| Instant::now() + t),
+    };
+    CURRENT.scope(cancellation, f).await
+}
+
+/// Awaits `f`, which waits for the git process, unless the current scope is cancelled.
+///
+/// If cancelled, `f` is dropped (the process must be spawned with `kill_on_drop`),
+/// and `Cancelled` is returned as `std::io::Error`.
+pub(super) async fn wait<T, F>(command: &str, f: F) -> std::io::Result<T>
+where
+    F: Future<Output = std::io::Result<T>>,
+{
+    let Ok(cancellation) = CURRENT.try_with(|c| c.clone()) else {
+        return f.await;
+    };
+    let cancelled = |reason: String| {
+        std::io::Error::other(Cancelled {
+            command: command.to_string(),
+            reason,
+        })
+    };
+    if cancellation.token.is_cancelled() {
+        return Err(cancelled("cancelled".to_string()));
+    }
+    let timeout = async {
+        match cancellation.deadline {
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        ret = f => ret,
+        _ = cancellation.token.cancelled() => Err(cancelled("cancelled".to_string())),
+        _ = timeout => Err(cancelled("timed out".to_string())),
+    }
+}
@@ -0,0 +1,235 @@
+use super::{build_command, GitError};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::Mutex;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectInfo {
+    pub id: String,
+    pub object_type: String,
+    pub size: usize,
+}
+
+struct BatchProcess {
+    // the process is killed when dropped
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+/**
+ * parse header line of `git cat-file --batch` / `--batch-check`
+ *
+ * {id} {type} {size}
+ * {object} missing
+ * {object} ambiguous
+ */
+fn parse_header(header: &str) -> Result<Option<ObjectInfo>, GitError> {
+    if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+        return Ok(None);
+    }
+    let tokens: Vec<&str> = header.split(' ').collect();
+    match tokens.as_slice() {
+        [id, object_type, size] => {
+            let size = size
+                .parse::<usize>()
+                .map_err(|_| GitError::UnexpectedOutput {
+                    command: String::from("cat-file"),
+                    text: header.to_string(),
+                })?;
+            Ok(Some(ObjectInfo {
+                id: id.to_string(),
+                object_type: object_type.to_string(),
+                size,
+            }))
+        }
+        _ => Err(GitError::UnexpectedOutput {
+            command: String::from("cat-file"),
+            text: header.to_string(),
+        }),
+    }
+}
+
+/// A long-lived `git cat-file --batch*` process, which is started on demand.
+///
+/// Requests are not multiplexed: they are serialized by a mutex and served one by one,
+/// since a response can't be associated with its request once requests are pipelined
+/// and one of them is cancelled. (reading objects is fast enough compared to spawning processes)
+/// The process is restarted if a request fails or is cancelled.
+struct BatchReader {
+    repo_path: PathBuf,
+    args: &'static [&'static str],
+    read_content: bool,
+    process: Mutex<Option<BatchProcess>>,
+}
+
+impl BatchReader {
+    fn new(repo_path: &Path, args: &'static [&'static str], read_content: bool) -> Self {
+        BatchReader {
+            repo_path: repo_path.to_path_buf(),
+            args,
+            read_content,
+            process: Mutex::new(None),
+        }
+    }
+
+    fn spawn(&self) -> Result<BatchProcess, GitError> {
+        let mut cmd = build_command(&self.repo_path, "cat-file", self.args, &[]);
+        cmd.stderr(Stdio::null());
+        cmd.kill_on_drop(true);
+        debug!(
+            "{}, git cat-file, {:?}",
+            self.repo_path.display(),
+            self.args
+        );
+        let mut child = cmd.spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Ok(BatchProcess {
+            _child: child,
+            stdin,
+            stdout,
+        })
+    }
+
+    async fn request_once(
+        &self,
+        process: &mut BatchProcess,
+        object: &str,
+    ) -> Result<Option<(ObjectInfo, Vec<u8>)>, GitError> {
+        process.stdin.write_all(object.as_bytes()).await?;
+        process.stdin.write_all(b"\n").await?;
+        process.stdin.flush().await?;
+        let mut header = String::new();
+        if process.stdout.read_line(&mut header).await? == 0 {
+            return Err(GitError::UnexpectedOutput {
+                command: String::from("cat-file"),
+                text: String::from("unexpected EOF"),
+            });
+        }
+        let info = match parse_header(header.trim_end_matches('\n'))? {
+            Some(info) => info,
+            None => return Ok(None),
+        };
+        let mut content: Vec<u8> = Vec::new();
+        if self.read_content {
+            // content is followed by LF
+            content.resize(info.size + 1, 0);
+            process.stdout.read_exact(&mut content).await?;
+            content.truncate(info.size);
+        }
+        Ok(Some((info, content)))
+    }
+
+    async fn request(&self, object: &str) -> Result<Option<(ObjectInfo, Vec<u8>)>, GitError> {
+        if object.contains('\n') {
+            return Err(GitError::ArgumentError {
+                command: String::from("cat-file"),
+                message: format!("{:?} cannot be requested in batch mode", object),
+            });
+        }
+        let mut slot = self.process.lock().await;
+        let mut last_error: Option<GitError> = None;
+        // retry once with a new process, since the process may be terminated unexpectedly
+        for _ in 0..2 {
+            // the process is taken out of the slot while requesting, and is put back only
+            // if succeeded. if the request fails or the future is dropped (cancelled),
+            // output may be left in the pipe, so the process is killed and not used any more.
+            let mut process = match slot.take() {
+                Some(process) => process,
+                None => self.spawn()?,
+            };
+            match self.request_once(&mut process, object).await {
+                Ok(ret) => {
+                    *slot = Some(process);
+                    return Ok(ret);
+                }
+                Err(e) => {
+                    warn!("cat-file: Failed to read {}, restarting. {}", object, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    async fn stop(&self) {
+        let mut process = self.process.lock().await;
+        *process = None;
+    }
+}
+
+/// Reads objects via persistent `git cat-file` processes of a repository.
+///
+/// Note that `cat-file` reads the index only once, so objects in the index (`:<path>`)
+/// should not be read by this.
+#[derive(Clone)]
+pub struct ObjectReader {
+    contents: Arc<BatchReader>,
+    filtered_contents: Arc<BatchReader>,
+    infos: Arc<BatchReader>,
+}
+
+impl ObjectReader {
+    pub fn new(repo_path: &Path) -> Self {
+        ObjectReader {
+            contents: Arc::new(BatchReader::new(repo_path, &["--batch"], true)),
+            filtered_contents: Arc::new(BatchReader::new(
+                repo_path,
+                &["--batch", "--filters"],
+                true,
+            )),
+            infos: Arc::new(BatchReader::new(repo_path, &["--batch-check"], false)),
+        }
+    }
+
+    /// Reads the content of an object. Returns None if the object does not exist.
+    ///
+    /// If `apply_filters` is true, `object` must be in the form `<revspec>:<path>`.
+    pub async fn read(
+        &self,
+        object: &str,
+        apply_filters: bool,
+    ) -> Result<Option<Vec<u8>>, GitError> {
+        let reader = if apply_filters {
+            &self.filtered_contents
+        } else {
+            &self.contents
+        };
+        Ok(reader.request(object).await?.map(|(_, content)| content))
+    }
+
+    /// Gets id, type and size of an object. Returns None if the object does not exist.
+    pub async fn get_info(&self, object: &str) -> Result<Option<ObjectInfo>, GitError> {
+        Ok(self.infos.request(object).await?.map(|(info, _)| info))
+    }
+
+    pub async fn stop(&self) {
+        self.contents.stop().await;
+        self.filtered_contents.stop().await;
+        self.infos.stop().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            Some(ObjectInfo {
+                id: "5cc9b4bc00000000000000000000000000000000".to_string(),
+                object_type: "blob".to_string(),
+                size: 123,
+            }),
+            parse_header("5cc9b4bc00000000000000000000000000000000 blob 123").unwrap()
+        );
+        assert_eq!(None, parse_header("HEAD:a b.txt missing").unwrap());
+        assert_eq!(None, parse_header("5cc9 ambiguous").unwrap());
+        assert!(parse_header("fatal: error").is_err());
+    }
+}
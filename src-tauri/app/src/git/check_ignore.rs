@@ -0,0 +1,38 @@
+use super::{decode_output, exec_with_stdin, GitError};
+use std::collections::HashSet;
+use std::path::Path;
+
+/**
+ * Returns paths which are not ignored by .gitignore, etc.
+ *
+ * `git check-ignore -z --stdin` outputs ignored paths separated by NUL.
+ * Paths of tracked files are never reported as ignored.
+ */
+pub async fn filter_ignored(
+    repo_path: &Path,
+    rel_paths: &[String],
+) -> Result<Vec<String>, GitError> {
+    if rel_paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let stdin = rel_paths.join("\0");
+    let output = exec_with_stdin(
+        repo_path,
+        "check-ignore",
+        &["-z", "--stdin"],
+        &[],
+        stdin.as_bytes(),
+    )
+    .await?;
+    // exit code 1 means that none of paths are ignored
+    if output.status.code() != Some(1) {
+        GitError::assert_process_output("check-ignore", &output)?;
+    }
+    let stdout = decode_output(&output.stdout);
+    let ignored = stdout.split('\0').collect::<HashSet<_>>();
+    Ok(rel_paths
+        .iter()
+        .filter(|p| !ignored.contains(&p.as_str()))
+        .cloned()
+        .collect())
+}
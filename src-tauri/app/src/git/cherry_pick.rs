@@ -0,0 +1,23 @@
+use super::{operation, GitError};
+use std::path::Path;
+use types::{CherryPickOptions, OperationState};
+
+pub async fn cherry_pick(
+    repo_path: &Path,
+    options: &CherryPickOptions,
+) -> Result<Option<OperationState>, GitError> {
+    let mainline = options.mainline.map(|v| v.to_string());
+    let mut args: Vec<&str> = Vec::new();
+    if let Some(ref mainline) = mainline {
+        args.push("--mainline");
+        args.push(mainline);
+    }
+    if options.no_commit.is_some_and(|v| v) {
+        args.push("--no-commit");
+    }
+    if options.record_origin.is_some_and(|v| v) {
+        args.push("-x");
+    }
+    args.extend(options.commit_ids.iter().map(|v| v.as_str()));
+    operation::exec_operation(repo_path, "cherry-pick", &args, &[]).await
+}
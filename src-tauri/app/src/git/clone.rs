@@ -0,0 +1,51 @@
+use super::progress::parse_progress_line;
+use super::{exec_without_repository, GitError};
+use std::path::Path;
+use types::{CloneOptions, RemoteProgress};
+
+/// Returns the parent directory of `path`, in which the repository is created.
+pub fn parent_dir<'a>(command: &str, path: &'a Path) -> Result<&'a Path, GitError> {
+    match path.parent() {
+        Some(parent) if path.is_absolute() => Ok(parent),
+        _ => Err(GitError::ArgumentError {
+            command: command.to_string(),
+            message: format!("path must be absolute: {}", path.display()),
+        }),
+    }
+}
+
+pub async fn clone<F: FnMut(RemoteProgress)>(
+    options: &CloneOptions,
+    mut on_progress: F,
+) -> Result<(), GitError> {
+    let path = Path::new(&options.path);
+    let cwd = parent_dir("clone", path)?;
+    std::fs::create_dir_all(cwd)?;
+    let depth = options.depth.map(|d| d.to_string());
+    let mut args: Vec<&str> = vec!["--progress"];
+    if let Some(ref branch) = options.branch {
+        args.push("--branch");
+        args.push(branch);
+    }
+    if let Some(ref depth) = depth {
+        args.push("--depth");
+        args.push(depth);
+    }
+    if options.recurse_submodules.is_some_and(|v| v) {
+        args.push("--recurse-submodules");
+    }
+    args.push("--");
+    args.push(&options.url);
+    args.push(&options.path);
+    let output = exec_without_repository(cwd, "clone", &args, |line| {
+        if let Some(progress) = parse_progress_line(line) {
+            on_progress(progress);
+            true
+        } else {
+            false
+        }
+    })
+    .await?;
+    GitError::assert_process_output("clone", &output)?;
+    Ok(())
+}
@@ -1,5 +1,6 @@
-use super::{exec, GitError};
+use super::{build_command_args, config, hook, rev_parse, worktree, GitError};
 use std::path::Path;
+use types::{CommitOptions, CommitSigning};
 
 fn assert_message_given(message: &str) -> Result<(), GitError> {
     if message.len() == 0 {
@@ -12,25 +13,215 @@ fn assert_message_given(message: &str) -> Result<(), GitError> {
     }
 }
 
-pub async fn commit(repo_path: &Path, message: &str) -> Result<(), GitError> {
-    assert_message_given(message)?;
-    let args = vec!["-m", message];
-    let output = exec(repo_path, "commit", &args, &[]).await?;
-    GitError::assert_process_output("commit", &output)?;
-    Ok(())
+/// Returns arguments and configs of `git commit` to sign (or not to sign) the commit.
+///
+/// If `signing` is None, git follows `commit.gpgSign` and `gpg.format`.
+fn signing_args(
+    signing: Option<CommitSigning>,
+) -> (&'static [&'static str], &'static [&'static str]) {
+    match signing {
+        Some(CommitSigning::Gpg) => (&["--gpg-sign"], &["gpg.format=openpgp"]),
+        Some(CommitSigning::Ssh) => (&["--gpg-sign"], &["gpg.format=ssh"]),
+        Some(CommitSigning::None) => (&["--no-gpg-sign"], &[]),
+        None => (&[], &[]),
+    }
+}
+
+/// Resolves how the commit will be signed.
+///
+/// If `signing` is None, it is determined by `commit.gpgSign` and `gpg.format`.
+/// (`gpg.format=x509` is treated as GPG, since it is also signed by `gpg.x509.program`)
+pub async fn resolve_signing(
+    repo_path: &Path,
+    signing: Option<CommitSigning>,
+) -> Result<CommitSigning, GitError> {
+    if let Some(signing) = signing {
+        return Ok(signing);
+    }
+    let gpg_sign = config::find_config_value(repo_path, "commit.gpgSign", Some("bool")).await?;
+    if gpg_sign.as_deref() != Some("true") {
+        return Ok(CommitSigning::None);
+    }
+    let format = config::find_config_value(repo_path, "gpg.format", None).await?;
+    Ok(match format.as_deref() {
+        Some("ssh") => CommitSigning::Ssh,
+        _ => CommitSigning::Gpg,
+    })
 }
 
-pub async fn commit_amend(repo_path: &Path, message: Option<&str>) -> Result<(), GitError> {
-    let args = if let Some(message) = message {
-        if message.len() == 0 {
-            vec!["--amend", "-C", "HEAD"]
-        } else {
-            vec!["--amend", "-m", message]
+/// Arguments (except the message) and configs of `git commit`, and the message to be passed.
+type CommitArgs<'a> = (Vec<String>, &'static [&'static str], Option<&'a str>);
+
+/// Returns `CommitArgs` for `options`.
+fn build_args(options: &CommitOptions) -> Result<CommitArgs<'_>, GitError> {
+    let mut args: Vec<String> = Vec::new();
+    let (message, flags) = match options {
+        CommitOptions::Normal { message, flags } => {
+            assert_message_given(message)?;
+            (Some(message.as_str()), flags)
+        }
+        CommitOptions::Amend {
+            message,
+            reset_author,
+            flags,
+        } => {
+            args.push("--amend".into());
+            if reset_author.is_some_and(|v| v) {
+                args.push("--reset-author".into());
+            }
+            let message = message.as_deref().filter(|m| !m.is_empty());
+            if message.is_none() {
+                args.push("-C".into());
+                args.push("HEAD".into());
+            }
+            (message, flags)
+        }
+        CommitOptions::Fixup {
+            target,
+            squash,
+            message,
+            flags,
+        } => {
+            let kind = if squash.is_some_and(|v| v) {
+                "squash"
+            } else {
+                "fixup"
+            };
+            args.push(format!("--{}={}", kind, target));
+            let message = message.as_deref().filter(|m| !m.is_empty());
+            if message.is_none() {
+                // --squash opens the editor without message
+                args.push("--no-edit".into());
+            }
+            (message, flags)
         }
-    } else {
-        vec!["--amend", "-C", "HEAD"]
     };
-    let output = exec(repo_path, "commit", &args, &[]).await?;
+    let (signing_args, configs) = signing_args(flags.signing);
+    args.extend(signing_args.iter().map(|a| a.to_string()));
+    if let Some(author) = &flags.author {
+        args.push(format!("--author={}", author));
+    }
+    if let Some(date) = &flags.author_date {
+        args.push(format!("--date={}", date));
+    }
+    if flags.signoff.is_some_and(|v| v) {
+        args.push("--signoff".into());
+    }
+    if flags.no_verify.is_some_and(|v| v) {
+        args.push("--no-verify".into());
+    }
+    if flags.allow_empty.is_some_and(|v| v) {
+        args.push("--allow-empty".into());
+    }
+    Ok((args, configs, message))
+}
+
+/// Creates a commit (or amends HEAD), and returns the id of the new commit.
+///
+/// Each line of stderr (including the output of pre-commit and commit-msg hooks)
+/// is passed to `on_output` while running.
+pub async fn commit<F: FnMut(&str)>(
+    repo_path: &Path,
+    options: &CommitOptions,
+    mut on_output: F,
+) -> Result<String, GitError> {
+    let (mut args, configs, message) = build_args(options)?;
+    if let Some(message) = message {
+        args.push("-m".into());
+        args.push(message.to_string());
+    }
+    let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+    let output = hook::exec_with_hooks(repo_path, "commit", &args, configs, |line| {
+        on_output(line);
+        false
+    })
+    .await?;
     GitError::assert_process_output("commit", &output)?;
-    Ok(())
+    rev_parse::rev_parse(repo_path, "HEAD")
+        .await?
+        .ok_or_else(|| GitError::UnexpectedOutput {
+            command: String::from("commit"),
+            text: String::from("HEAD is not found after commit"),
+        })
+}
+
+/// Builds arguments of `git commit` to be run in a PTY,
+/// where gpg (pinentry) or ssh-keygen can prompt for the passphrase of the signing key.
+///
+/// The message is passed by `.git/COMMIT_EDITMSG` instead of `-m`,
+/// since it may contain characters which cannot be passed through the command line.
+pub fn build_command_args_for_pty(
+    repo_path: &Path,
+    options: &CommitOptions,
+) -> Result<Vec<String>, GitError> {
+    let (mut args, configs, message) = build_args(options)?;
+    if let Some(message) = message {
+        let message_file = worktree::resolve_git_dir(repo_path).join("COMMIT_EDITMSG");
+        std::fs::write(&message_file, message)?;
+        args.push("-F".into());
+        args.push(message_file.to_string_lossy().into_owned());
+    }
+    let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+    Ok(build_command_args(
+        Some(repo_path),
+        "commit",
+        &args,
+        configs,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::CommitFlags;
+
+    #[test]
+    fn test_build_args() {
+        let options = CommitOptions::Amend {
+            message: None,
+            reset_author: Some(true),
+            flags: CommitFlags {
+                signing: Some(CommitSigning::Ssh),
+                signoff: Some(true),
+                ..Default::default()
+            },
+        };
+        let (args, configs, message) = build_args(&options).unwrap();
+        assert_eq!(
+            vec![
+                "--amend",
+                "--reset-author",
+                "-C",
+                "HEAD",
+                "--gpg-sign",
+                "--signoff"
+            ],
+            args
+        );
+        assert_eq!(&["gpg.format=ssh"], configs);
+        assert_eq!(None, message);
+
+        let options = CommitOptions::Fixup {
+            target: "1111".to_owned(),
+            squash: Some(true),
+            message: Some("more".to_owned()),
+            flags: CommitFlags {
+                author: Some("A <a@example.com>".to_owned()),
+                no_verify: Some(true),
+                ..Default::default()
+            },
+        };
+        let (args, _, message) = build_args(&options).unwrap();
+        assert_eq!(
+            vec!["--squash=1111", "--author=A <a@example.com>", "--no-verify"],
+            args
+        );
+        assert_eq!(Some("more"), message);
+
+        let options = CommitOptions::Normal {
+            message: String::new(),
+            flags: CommitFlags::default(),
+        };
+        assert!(build_args(&options).is_err());
+    }
 }
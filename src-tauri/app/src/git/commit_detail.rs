@@ -1,4 +1,4 @@
-use super::{exec, GitError};
+use super::{decode_output, exec, parse_date, GitError};
 use std::collections::HashMap;
 use std::path::Path;
 use types::*;
@@ -10,22 +10,26 @@ pub fn parse_raw_tokens<'a>(
     let mut ret: Vec<(String, FileEntry)> = Vec::new();
     while i < tokens.len() {
         let token = tokens[i];
+        let unexpected = || GitError::UnexpectedOutput {
+            command: String::from("numstat"),
+            text: format!("unexpected raw output: {}", token),
+        };
         if token.starts_with(":") {
             // RAW OUTPUT
-            let status_code = token.split(" ").nth(4).unwrap();
-            let status = &status_code[0..1];
+            let status_code = token.split(" ").nth(4).ok_or_else(unexpected)?;
+            let status = status_code.get(0..1).ok_or_else(unexpected)?;
             match status {
                 "M" | "A" | "D" | "T" | "U" => {
                     i += 1;
-                    let path: &str = tokens[i];
+                    let path: &str = tokens.get(i).copied().ok_or_else(unexpected)?;
                     let file = FileEntry::new(path, status_code, None, None);
                     ret.push((path.to_owned(), file));
                 }
                 "R" | "C" => {
                     i += 1;
-                    let old_path = tokens[i];
+                    let old_path = tokens.get(i).copied().ok_or_else(unexpected)?;
                     i += 1;
-                    let path = tokens[i];
+                    let path = tokens.get(i).copied().ok_or_else(unexpected)?;
                     let file = FileEntry::new(path, status_code, Some(&old_path), None);
                     ret.push((path.to_owned(), file));
                 }
@@ -62,7 +66,13 @@ pub fn parse_numstat_tokens(tokens: &[&str]) -> Result<Vec<(String, FileDelta)>,
             values[2]
         } else {
             i += 2;
-            tokens[i]
+            tokens
+                .get(i)
+                .copied()
+                .ok_or_else(|| GitError::UnexpectedOutput {
+                    command: String::from("numstat"),
+                    text: format!("missing path: {}", token),
+                })?
         };
         let delta = match (values[0].parse::<u32>(), values[1].parse::<u32>()) {
             (Ok(insertions), Ok(deletions)) => FileDelta::Text {
@@ -119,9 +129,16 @@ const LOG_FORMAT: &str = "\
     author:%an%n\
     mail:%ae%n\
     date:%at%n\
+    committer:%cn%n\
+    committer_mail:%ce%n\
+    commit_date:%ct%n\
+    trailers:%(trailers:only,unfold,separator=%x1f)%n\
     summary:%s%n\
     body:{{{%n\
     %w(0,1,1)%b%n\
+    %w(0)}}}%n\
+    notes:{{{%n\
+    %w(0,1,1)%N%n\
     %w(0)}}}%n";
 
 const ID: &str = "id";
@@ -129,15 +146,71 @@ const PARENTS: &str = "parents";
 const AUTHOR: &str = "author";
 const MAIL: &str = "mail";
 const DATE: &str = "date";
+const COMMITTER: &str = "committer";
+const COMMITTER_MAIL: &str = "committer_mail";
+const COMMIT_DATE: &str = "commit_date";
+const TRAILERS: &str = "trailers";
 const SUMMARY: &str = "summary";
 const BODY: &str = "body";
+const NOTES: &str = "notes";
 
 enum Region {
     Props,
     Body,
+    Notes,
     Files,
 }
 
+/**
+ * parse `%G?`
+ *
+ * G: good, B: bad, U: good with unknown validity, X: good but expired,
+ * Y: good made by an expired key, R: good made by a revoked key,
+ * E: cannot be checked (e.g. missing key), N: no signature
+ */
+fn parse_signature_status(value: &str) -> Result<Option<SignatureStatus>, GitError> {
+    Ok(Some(match value {
+        "G" => SignatureStatus::Good,
+        "B" => SignatureStatus::Bad,
+        "U" => SignatureStatus::UnknownValidity,
+        "X" => SignatureStatus::Expired,
+        "Y" => SignatureStatus::ExpiredKey,
+        "R" => SignatureStatus::RevokedKey,
+        "E" => SignatureStatus::CannotCheck,
+        "N" => return Ok(None),
+        _ => {
+            return Err(GitError::UnexpectedOutput {
+                command: String::from("show"),
+                text: format!("unknown signature status: {}", value),
+            })
+        }
+    }))
+}
+
+/**
+ * parse `%(trailers:only,unfold,separator=%x1f)`
+ *
+ * {key}: {value}<US>{key}: {value}
+ */
+fn parse_trailers(value: &str) -> Vec<Trailer> {
+    value
+        .split('\x1f')
+        .filter_map(|t| t.split_once(':'))
+        .map(|(key, value)| Trailer {
+            key: key.trim().to_string(),
+            value: value.trim().to_string(),
+        })
+        .collect()
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    if value.len() > 0 {
+        Some(value.to_string())
+    } else {
+        None
+    }
+}
+
 /**
  * parse output generated by `git show {rev} --format={LOG_FORMAT} --numstat --raw -z`
  *
@@ -146,10 +219,17 @@ enum Region {
  * author:{author}\n
  * mail:{author mail}\n
  * date:{author-date}\n
+ * committer:{committer}\n
+ * committer_mail:{committer mail}\n
+ * commit_date:{committer-date}\n
+ * trailers:{trailers separated by <US>}\n
  * summary:{summary}\n
  * body:{{{
  *  {body (indent by single blank, maybe multi line)}
  * }}}
+ * notes:{{{
+ *  {notes (indent by single blank, maybe multi line)}
+ * }}}
  *
  * M<NUL>dir/foo.txt<NUL>A<NUL>dir/bar.txt<NUL>R100<NUL>dir/baz-new.txt<NUL>dir/baz-old.txt...
  */
@@ -160,8 +240,13 @@ fn parse_commit_detail_output(output: &str) -> Result<CommitDetail, GitError> {
     let mut author = "";
     let mut mail_address = "";
     let mut date: u64 = 0;
+    let mut committer = "";
+    let mut committer_mail_address = "";
+    let mut commit_date: u64 = 0;
+    let mut trailers: Vec<Trailer> = Vec::new();
     let mut summary = "";
     let mut body = String::from("");
+    let mut notes = String::from("");
     let mut files: Vec<FileEntry> = Vec::new();
     for line in output.lines() {
         match region {
@@ -181,7 +266,19 @@ fn parse_commit_detail_output(output: &str) -> Result<CommitDetail, GitError> {
                         mail_address = value;
                     }
                     [DATE, value] => {
-                        date = value.parse::<u64>().unwrap() * 1000;
+                        date = parse_date("show", value)?;
+                    }
+                    [COMMITTER, value] => {
+                        committer = value;
+                    }
+                    [COMMITTER_MAIL, value] => {
+                        committer_mail_address = value;
+                    }
+                    [COMMIT_DATE, value] => {
+                        commit_date = parse_date("show", value)?;
+                    }
+                    [TRAILERS, value] => {
+                        trailers = parse_trailers(value);
                     }
                     [SUMMARY, value] => {
                         summary = value;
@@ -189,6 +286,9 @@ fn parse_commit_detail_output(output: &str) -> Result<CommitDetail, GitError> {
                     [BODY, _] => {
                         region = Region::Body;
                     }
+                    [NOTES, _] => {
+                        region = Region::Notes;
+                    }
                     _ => {
                         return Err(GitError::UnexpectedOutput {
                             command: String::from("show"),
@@ -197,18 +297,27 @@ fn parse_commit_detail_output(output: &str) -> Result<CommitDetail, GitError> {
                     }
                 }
             }
-            Region::Body => {
+            Region::Body | Region::Notes => {
+                let text = if matches!(region, Region::Body) {
+                    &mut body
+                } else {
+                    &mut notes
+                };
                 if line == "}}}" {
-                    body.pop(); // remove last \n
-                    region = Region::Files;
+                    text.pop(); // remove last \n
+                    region = if matches!(region, Region::Body) {
+                        Region::Props
+                    } else {
+                        Region::Files
+                    };
                 } else if line.len() == 0 {
                     return Err(GitError::UnexpectedOutput {
                         command: String::from("show"),
                         text: line.to_string(),
                     });
                 } else {
-                    body.push_str(&line[1..]);
-                    body.push_str("\n");
+                    text.push_str(&line[1..]);
+                    text.push_str("\n");
                 }
             }
             _ => {
@@ -222,8 +331,14 @@ fn parse_commit_detail_output(output: &str) -> Result<CommitDetail, GitError> {
         }
     }
     Ok(CommitDetail {
-        commit: Commit::new(id, parents, author, mail_address, date, summary),
+        commit: Commit::new(id, parents, author, mail_address, date, summary).with_committer(
+            committer,
+            committer_mail_address,
+            commit_date,
+        ),
         body: body.to_string(),
+        trailers,
+        notes: non_empty(notes.trim_end()),
         files,
     })
 }
@@ -240,8 +355,41 @@ pub async fn get_commit_detail(repo_path: &Path, revspec: &str) -> Result<Commit
     ];
     let output = exec(repo_path, "show", &args, &[]).await?;
     GitError::assert_process_output("show", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    parse_commit_detail_output(stdout)
+    let stdout = decode_output(&output.stdout);
+    parse_commit_detail_output(&stdout)
+}
+
+/**
+ * parse output generated by `git show -s --format=%G?%n%GS%n%GK`
+ *
+ * {signature status}\n
+ * {signer (empty if not signed)}\n
+ * {signing key (empty if not signed)}\n
+ */
+fn parse_signature_output(output: &str) -> Result<Option<CommitSignature>, GitError> {
+    let mut lines = output.lines();
+    let status = parse_signature_status(lines.next().unwrap_or(""))?;
+    let signer = lines.next().unwrap_or("");
+    let key = lines.next().unwrap_or("");
+    Ok(status.map(|status| CommitSignature {
+        status,
+        signer: non_empty(signer),
+        key: non_empty(key),
+    }))
+}
+
+/// Verifies the signature of the commit, which may take time since gpg or ssh-keygen is run.
+///
+/// Returns `None` if the commit is not signed.
+pub async fn get_signature(
+    repo_path: &Path,
+    revspec: &str,
+) -> Result<Option<CommitSignature>, GitError> {
+    let args = ["-s", "--format=%G?%n%GS%n%GK", revspec];
+    let output = exec(repo_path, "show", &args, &[]).await?;
+    GitError::assert_process_output("show", &output)?;
+    let stdout = decode_output(&output.stdout);
+    parse_signature_output(&stdout)
 }
 
 #[cfg(test)]
@@ -273,4 +421,96 @@ mod tests {
         let actual = parse_raw_numstat_rows(OUTPUT).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_parse_commit_detail_output() {
+        const OUTPUT: &str = "\
+            id:1111\n\
+            parents:2222\n\
+            author:Author\n\
+            mail:author@example.com\n\
+            date:1700000000\n\
+            committer:Committer\n\
+            committer_mail:committer@example.com\n\
+            commit_date:1700001000\n\
+            trailers:Signed-off-by: Author <author@example.com>\x1fFixes: #12\n\
+            summary:summary\n\
+            body:{{{\n \
+            body\n \n \
+            Signed-off-by: Author <author@example.com>\n \
+            Fixes: #12\n \n\
+            }}}\n\
+            notes:{{{\n \
+            note\n \n\
+            }}}\n\
+            \0\n:100644 100644 55ee501 85acac2 M\0a.ts\05\t8\ta.ts\0";
+        let detail = parse_commit_detail_output(OUTPUT).unwrap();
+        assert_eq!("Author", detail.commit.author);
+        assert_eq!(1700000000000, detail.commit.date);
+        assert_eq!("Committer", detail.commit.committer);
+        assert_eq!(
+            "committer@example.com",
+            detail.commit.committer_mail_address
+        );
+        assert_eq!(1700001000000, detail.commit.commit_date);
+        assert_eq!(
+            vec![
+                Trailer {
+                    key: "Signed-off-by".to_owned(),
+                    value: "Author <author@example.com>".to_owned(),
+                },
+                Trailer {
+                    key: "Fixes".to_owned(),
+                    value: "#12".to_owned(),
+                },
+            ],
+            detail.trailers
+        );
+        assert!(detail.body.starts_with("body\n\nSigned-off-by"));
+        assert_eq!(Some("note"), detail.notes.as_deref());
+        assert_eq!(1, detail.files.len());
+    }
+
+    #[test]
+    fn test_parse_commit_without_notes() {
+        const OUTPUT: &str = "\
+            id:1111\n\
+            trailers:\n\
+            summary:summary\n\
+            body:{{{\n \n}}}\n\
+            notes:{{{\n \n}}}\n";
+        let detail = parse_commit_detail_output(OUTPUT).unwrap();
+        assert_eq!(None, detail.notes);
+        assert!(detail.trailers.is_empty());
+        assert_eq!("", detail.body);
+    }
+
+    #[test]
+    fn test_parse_signature_output() {
+        assert_eq!(
+            Some(CommitSignature {
+                status: SignatureStatus::Good,
+                signer: Some("Committer <committer@example.com>".to_owned()),
+                key: Some("SHA256:abcd".to_owned()),
+            }),
+            parse_signature_output("G\nCommitter <committer@example.com>\nSHA256:abcd\n").unwrap()
+        );
+        assert_eq!(
+            Some(CommitSignature {
+                status: SignatureStatus::CannotCheck,
+                signer: None,
+                key: Some("0123456789ABCDEF".to_owned()),
+            }),
+            parse_signature_output("E\n\n0123456789ABCDEF\n").unwrap()
+        );
+        assert_eq!(None, parse_signature_output("N\n\n\n").unwrap());
+        assert!(parse_signature_output("?\n\n\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_truncated_raw_output() {
+        assert!(parse_raw_numstat_rows(":100644 100644 bcd1234 0123456 M\0").is_err());
+        assert!(parse_raw_numstat_rows(":100644 100644 bcd1234 0123456 R86\0file1\0").is_err());
+        assert!(parse_raw_numstat_rows(":100644 100644\0file1\0").is_err());
+    }
 }
@@ -1,4 +1,4 @@
-use super::{exec, GitError};
+use super::{decode_output, exec, GitError};
 use std::path::Path;
 
 pub async fn get_config_value(
@@ -13,8 +13,33 @@ pub async fn get_config_value(
     };
     let output = exec(repo_path, "config", &args, &[]).await?;
     GitError::assert_process_output("config", &output)?;
-    let value = std::str::from_utf8(&output.stdout)
-        .unwrap()
-        .trim_end_matches('\n');
-    Ok(value.to_owned())
+    let value = decode_output(&output.stdout)
+        .trim_end_matches('\n')
+        .to_owned();
+    Ok(value)
+}
+
+/// Gets the value of `name` (converted to `value_type` like "bool" if specified),
+/// or None if it is not set.
+pub async fn find_config_value(
+    repo_path: &Path,
+    name: &str,
+    value_type: Option<&str>,
+) -> Result<Option<String>, GitError> {
+    let type_arg = value_type.map(|t| format!("--type={}", t));
+    let mut args = vec!["--get"];
+    if let Some(type_arg) = type_arg.as_deref() {
+        args.push(type_arg);
+    }
+    args.push(name);
+    let output = exec(repo_path, "config", &args, &[]).await?;
+    // exit code is 1 if the key is not set
+    if output.status.code() == Some(1) {
+        return Ok(None);
+    }
+    GitError::assert_process_output("config", &output)?;
+    let value = decode_output(&output.stdout)
+        .trim_end_matches('\n')
+        .to_owned();
+    Ok(Some(value))
 }
@@ -0,0 +1,305 @@
+use super::{decode_output, exec, exec_with_stdin, file, GitError};
+use std::path::Path;
+use tokio::fs::{read, write};
+use types::{ConflictHunk, ConflictRegion, ConflictSide};
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/**
+ * parse output generated by `git ls-files --unmerged -z -- {path}`
+ *
+ * {mode} {id} {stage}<TAB>{path}<NUL>
+ */
+fn parse_unmerged_stages(output: &str) -> Result<Vec<u8>, GitError> {
+    output
+        .split('\0')
+        .filter(|v| v.len() > 0)
+        .map(|v| {
+            v.split_once('\t')
+                .and_then(|(info, _)| info.split(' ').nth(2))
+                .and_then(|stage| stage.parse::<u8>().ok())
+                .ok_or_else(|| GitError::UnexpectedOutput {
+                    command: String::from("ls-files"),
+                    text: v.to_owned(),
+                })
+        })
+        .collect()
+}
+
+/// Gets index stages (1: base, 2: ours, 3: theirs) which exist for an unmerged file.
+pub async fn get_stages(repo_path: &Path, rel_path: &str) -> Result<Vec<u8>, GitError> {
+    let args = vec!["--unmerged", "-z", "--", rel_path];
+    let output = exec(repo_path, "ls-files", &args, &[]).await?;
+    GitError::assert_process_output("ls-files", &output)?;
+    let stdout = decode_output(&output.stdout);
+    let stages = parse_unmerged_stages(&stdout)?;
+    if stages.is_empty() {
+        return Err(GitError::ArgumentError {
+            command: String::from("ls-files"),
+            message: format!("{} is not unmerged", rel_path),
+        });
+    }
+    Ok(stages)
+}
+
+/// Gets contents of base, ours and theirs (index stages 1, 2 and 3) of an unmerged file.
+///
+/// The content is `None` if the stage does not exist (e.g. the file is added by both,
+/// or deleted by one side).
+pub async fn get_versions(
+    repo_path: &Path,
+    rel_path: &str,
+) -> Result<[Option<Vec<u8>>; 3], GitError> {
+    let stages = get_stages(repo_path, rel_path).await?;
+    let mut ret: [Option<Vec<u8>>; 3] = [None, None, None];
+    for stage in stages {
+        let revspec = format!(":{}", stage);
+        let content = file::get_content(repo_path, rel_path, &revspec, false).await?;
+        ret[stage as usize - 1] = Some(content);
+    }
+    Ok(ret)
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    let line = line.trim_end_matches(['\r', '\n']);
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.len() == 0 || rest.starts_with(' '))
+}
+
+fn marker_label(line: &str) -> String {
+    line.trim_end_matches(['\r', '\n'])[OURS_MARKER.len()..]
+        .trim()
+        .to_owned()
+}
+
+enum ParseState {
+    Common,
+    Ours,
+    Base,
+    Theirs,
+}
+
+/**
+ * parse conflict markers in a working file (both "merge" and "diff3" styles)
+ *
+ * common
+ * <<<<<<< {ours label}
+ * ours
+ * ||||||| {base label}
+ * base
+ * =======
+ * theirs
+ * >>>>>>> {theirs label}
+ *
+ * Markers of an unterminated conflict are treated as common text.
+ */
+pub fn parse_conflict_regions(content: &str) -> Vec<ConflictRegion> {
+    let mut regions: Vec<ConflictRegion> = Vec::new();
+    let mut state = ParseState::Common;
+    let mut common = String::new();
+    let mut raw = String::new();
+    let mut hunk = ConflictHunk {
+        ours: String::new(),
+        base: None,
+        theirs: String::new(),
+        ours_label: String::new(),
+        base_label: None,
+        theirs_label: String::new(),
+    };
+    for line in content.split_inclusive('\n') {
+        match state {
+            ParseState::Common => {
+                if is_marker(line, OURS_MARKER) {
+                    hunk.ours_label = marker_label(line);
+                    raw.push_str(line);
+                    state = ParseState::Ours;
+                } else {
+                    common.push_str(line);
+                }
+                continue;
+            }
+            ParseState::Ours if is_marker(line, BASE_MARKER) => {
+                hunk.base = Some(String::new());
+                hunk.base_label = Some(marker_label(line));
+                state = ParseState::Base;
+            }
+            ParseState::Ours | ParseState::Base if is_marker(line, SEPARATOR) => {
+                state = ParseState::Theirs;
+            }
+            ParseState::Theirs if is_marker(line, THEIRS_MARKER) => {
+                hunk.theirs_label = marker_label(line);
+                if common.len() > 0 {
+                    regions.push(ConflictRegion::Common {
+                        text: std::mem::take(&mut common),
+                    });
+                }
+                let empty = ConflictHunk {
+                    ours: String::new(),
+                    base: None,
+                    theirs: String::new(),
+                    ours_label: String::new(),
+                    base_label: None,
+                    theirs_label: String::new(),
+                };
+                regions.push(ConflictRegion::Conflict(std::mem::replace(
+                    &mut hunk, empty,
+                )));
+                raw.clear();
+                state = ParseState::Common;
+                continue;
+            }
+            ParseState::Ours => hunk.ours.push_str(line),
+            ParseState::Base => hunk.base.as_mut().unwrap().push_str(line),
+            ParseState::Theirs => hunk.theirs.push_str(line),
+        }
+        raw.push_str(line);
+    }
+    common.push_str(&raw);
+    if common.len() > 0 {
+        regions.push(ConflictRegion::Common { text: common });
+    }
+    regions
+}
+
+/// Reads the working file of an unmerged file as text.
+///
+/// Files which are not UTF-8 (e.g. Shift_JIS, Latin-1) are refused, since they would be broken
+/// by writing back the content decoded lossily. They should be resolved by the external merge tool.
+async fn read_text(repo_path: &Path, rel_path: &str) -> Result<String, GitError> {
+    let content = read(repo_path.join(rel_path)).await?;
+    String::from_utf8(content).map_err(|_| GitError::ArgumentError {
+        command: String::from("conflict"),
+        message: format!(
+            "{} is not UTF-8 text, resolve it with the external merge tool",
+            rel_path
+        ),
+    })
+}
+
+/// Parses conflict markers in the working file of an unmerged file.
+pub async fn get_regions(
+    repo_path: &Path,
+    rel_path: &str,
+) -> Result<Vec<ConflictRegion>, GitError> {
+    let content = read_text(repo_path, rel_path).await?;
+    Ok(parse_conflict_regions(&content))
+}
+
+/// Marks files as resolved by adding the working files (or their removal) to the index.
+pub async fn mark_resolved(repo_path: &Path, rel_paths: &[&str]) -> Result<(), GitError> {
+    let args = vec!["--all", "--pathspec-from-file=-"];
+    let stdin_data = rel_paths.join("\n");
+    let output = exec_with_stdin(repo_path, "add", &args, &[], stdin_data.as_bytes()).await?;
+    GitError::assert_process_output("add", &output)?;
+    Ok(())
+}
+
+/// Writes the resolved content to the working file, and marks it as resolved.
+///
+/// Fails if the working file is not UTF-8 (see `read_text`).
+pub async fn resolve(repo_path: &Path, rel_path: &str, content: &str) -> Result<(), GitError> {
+    read_text(repo_path, rel_path).await?;
+    write(repo_path.join(rel_path), content).await?;
+    mark_resolved(repo_path, &[rel_path]).await
+}
+
+/// Resolves a file by taking ours or theirs wholesale.
+///
+/// If the file is deleted on the side, the file is removed.
+pub async fn take_side(
+    repo_path: &Path,
+    rel_path: &str,
+    side: ConflictSide,
+) -> Result<(), GitError> {
+    let stages = get_stages(repo_path, rel_path).await?;
+    let (stage, option) = match side {
+        ConflictSide::Ours => (2, "--ours"),
+        ConflictSide::Theirs => (3, "--theirs"),
+    };
+    if stages.contains(&stage) {
+        let args = vec![option, "--", rel_path];
+        let output = exec(repo_path, "checkout", &args, &[]).await?;
+        GitError::assert_process_output("checkout", &output)?;
+        mark_resolved(repo_path, &[rel_path]).await
+    } else {
+        let args = vec!["--quiet", "--", rel_path];
+        let output = exec(repo_path, "rm", &args, &[]).await?;
+        GitError::assert_process_output("rm", &output)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn common(text: &str) -> ConflictRegion {
+        ConflictRegion::Common {
+            text: text.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_parse_unmerged_stages() {
+        const OUTPUT: &str = "\
+            100644 adf90a114e89d529c23d76ad32dd015271e0324f 1\tsrc/a.txt\0\
+            100644 6eccbc19dea5f2243ce93e48176e578d31533d6c 3\tsrc/a.txt\0\
+            ";
+        assert_eq!(vec![1, 3], parse_unmerged_stages(OUTPUT).unwrap());
+    }
+
+    #[test]
+    fn test_parse_conflict_regions() {
+        const CONTENT: &str = "\
+            line1\n\
+            <<<<<<< HEAD\n\
+            ours\n\
+            =======\n\
+            theirs1\r\n\
+            theirs2\n\
+            >>>>>>> topic\n\
+            line2\n\
+            <<<<<<< HEAD\n\
+            ||||||| merged common ancestors\n\
+            base\n\
+            =======\n\
+            theirs\n\
+            >>>>>>> topic\n\
+            ";
+        let expected = vec![
+            common("line1\n"),
+            ConflictRegion::Conflict(ConflictHunk {
+                ours: "ours\n".to_owned(),
+                base: None,
+                theirs: "theirs1\r\ntheirs2\n".to_owned(),
+                ours_label: "HEAD".to_owned(),
+                base_label: None,
+                theirs_label: "topic".to_owned(),
+            }),
+            common("line2\n"),
+            ConflictRegion::Conflict(ConflictHunk {
+                ours: "".to_owned(),
+                base: Some("base\n".to_owned()),
+                theirs: "theirs\n".to_owned(),
+                ours_label: "HEAD".to_owned(),
+                base_label: Some("merged common ancestors".to_owned()),
+                theirs_label: "topic".to_owned(),
+            }),
+        ];
+        assert_eq!(expected, parse_conflict_regions(CONTENT));
+    }
+
+    #[test]
+    fn test_parse_unterminated_conflict() {
+        const CONTENT: &str = "\
+            line1\n\
+            <<<<<<< HEAD\n\
+            ours\n\
+            =======\n\
+            theirs";
+        assert_eq!(vec![common(CONTENT)], parse_conflict_regions(CONTENT));
+    }
+}
@@ -1,5 +1,5 @@
 use super::commit_detail::parse_raw_numstat_rows;
-use super::{exec, GitError};
+use super::{decode_output, exec, exec_with_path, GitError};
 use std::path::Path;
 use types::*;
 
@@ -18,8 +18,8 @@ pub async fn get_changes_between(
     ];
     let output = exec(repo_path, "diff", &args, &[]).await?;
     GitError::assert_process_output("diff", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    parse_raw_numstat_rows(stdout)
+    let stdout = decode_output(&output.stdout);
+    parse_raw_numstat_rows(&stdout)
 }
 
 pub async fn get_workingtree_udiff(
@@ -31,9 +31,27 @@ pub async fn get_workingtree_udiff(
     if cached {
         args.push("--cached");
     }
-    args.push("--");
-    args.push(rel_path);
-    let output = exec(repo_path, "diff", &args, &[]).await?;
+    let output = exec_with_path(repo_path, "diff", &args, rel_path).await?;
+    GitError::assert_process_output("diff", &output)?;
+    Ok(output.stdout)
+}
+
+/// Same as `get_workingtree_udiff`, but the output is guaranteed to be applicable by `git apply`.
+pub async fn get_workingtree_patch(
+    repo_path: &Path,
+    rel_path: &str,
+    cached: bool,
+) -> Result<Vec<u8>, GitError> {
+    let mut args = vec![
+        "--no-color",
+        "--no-ext-diff",
+        "--src-prefix=a/",
+        "--dst-prefix=b/",
+    ];
+    if cached {
+        args.push("--cached");
+    }
+    let output = exec_with_path(repo_path, "diff", &args, rel_path).await?;
     GitError::assert_process_output("diff", &output)?;
     Ok(output.stdout)
 }
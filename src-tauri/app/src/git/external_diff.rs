@@ -1,7 +1,10 @@
 use rand::{distr::Alphanumeric, rng, Rng};
-use tokio::{fs::create_dir_all, process::Command};
+use tokio::{
+    fs::{create_dir_all, write},
+    process::Command,
+};
 
-use super::{rev_parse, GitError};
+use super::{conflict, GitError};
 use crate::{platform::split_commandline, state::repositories::Repository};
 use regex::Regex;
 use std::{
@@ -39,10 +42,12 @@ pub async fn prepare_diff_file(
         let revspec = if regex.is_match(&file.revspec) {
             file.revspec.clone()
         } else {
-            rev_parse::rev_parse(&repo.path, &file.revspec)
+            repo.object_reader
+                .get_info(&file.revspec)
                 .await?
+                .map(|info| info.id)
                 .ok_or(GitError::ArgumentError {
-                    command: "rev-parse".into(),
+                    command: "cat-file".into(),
                     message: format!("{} is not a valid revspec", &file.revspec),
                 })?
         };
@@ -53,7 +58,7 @@ pub async fn prepare_diff_file(
         }
         abs_path
     };
-    save_to(&repo.path, &file.path, &file.revspec, &abs_path, true).await?;
+    save_to(repo, &file.path, &file.revspec, &abs_path, true).await?;
     Ok(abs_path)
 }
 
@@ -94,3 +99,62 @@ pub async fn show_external_diff(
     cmd.spawn()?;
     Ok(())
 }
+
+/// Saves base, local (ours) and remote (theirs) versions of an unmerged file
+/// to the temp directory, for an external merge tool.
+///
+/// An empty file is saved for a stage which does not exist.
+pub async fn prepare_merge_files(
+    repo: &Repository,
+    rel_path: &str,
+) -> Result<[PathBuf; 3], Box<dyn Error + Send + Sync>> {
+    let stages = conflict::get_stages(&repo.path, rel_path).await?;
+    let filename = Path::new(rel_path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap();
+    let dir = repo.temp_dir.join("MERGE").join(random_name(6));
+    let mut ret: Vec<PathBuf> = Vec::new();
+    for (stage, name) in [(1, "BASE"), (2, "LOCAL"), (3, "REMOTE")] {
+        let abs_path = dir.join(name).join(filename);
+        create_dir_all(abs_path.parent().unwrap()).await?;
+        if stages.contains(&stage) {
+            let revspec = format!(":{}", stage);
+            save_to(repo, rel_path, &revspec, &abs_path, true).await?;
+        } else {
+            write(&abs_path, b"").await?;
+        }
+        ret.push(abs_path);
+    }
+    Ok(ret.try_into().unwrap())
+}
+
+pub async fn show_external_merge(
+    repo: &Repository,
+    command_line: &str,
+    [base, local, remote]: &[PathBuf; 3],
+    merged: &Path,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if command_line.len() == 0 {
+        return Ok(());
+    }
+    let mut args = split_commandline(command_line)?;
+    let program = args.remove(0);
+    replace_or_push(&mut args, "${base}", base.to_str().unwrap());
+    replace_or_push(&mut args, "${local}", local.to_str().unwrap());
+    replace_or_push(&mut args, "${remote}", remote.to_str().unwrap());
+    replace_or_push(&mut args, "${merged}", merged.to_str().unwrap());
+    debug!(
+        "{}, external merge: {}, {:?}",
+        repo.path.display(),
+        program,
+        args
+    );
+    let mut cmd = Command::new(&program);
+    cmd.args(&args);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(0x00000008); // DETACHED_PROCESS
+
+    cmd.spawn()?;
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use super::progress::{parse_fetch_ref_update_line, parse_progress_line};
+use super::{exec_with_stderr_handler, GitError};
+use std::path::Path;
+use types::{FetchOptions, RefUpdate, RefUpdateStatus, RemoteProgress};
+
+pub fn push_tags_option(args: &mut Vec<&str>, tags: Option<bool>) {
+    match tags {
+        Some(true) => args.push("--tags"),
+        Some(false) => args.push("--no-tags"),
+        None => {}
+    }
+}
+
+/// Executes remote command (fetch, pull) with `--progress`,
+/// and collects ref updates reported in stderr.
+pub async fn exec_with_progress<F: FnMut(RemoteProgress)>(
+    repo_path: &Path,
+    command: &str,
+    args: &[&str],
+    mut on_progress: F,
+) -> Result<Vec<RefUpdate>, GitError> {
+    let mut updates: Vec<RefUpdate> = Vec::new();
+    let output = exec_with_stderr_handler(repo_path, command, args, &[], |line| {
+        if let Some(progress) = parse_progress_line(line) {
+            on_progress(progress);
+            true
+        } else if let Some(update) = parse_fetch_ref_update_line(line) {
+            updates.push(update);
+            false
+        } else {
+            false
+        }
+    })
+    .await?;
+    let rejected: Vec<RefUpdate> = updates
+        .iter()
+        .filter(|u| u.status == RefUpdateStatus::Rejected)
+        .cloned()
+        .collect();
+    if !rejected.is_empty() {
+        return Err(GitError::RefsRejected {
+            command: command.to_owned(),
+            refs: rejected,
+        });
+    }
+    GitError::assert_process_output(command, &output)?;
+    Ok(updates)
+}
+
+pub async fn fetch<F: FnMut(RemoteProgress)>(
+    repo_path: &Path,
+    options: &FetchOptions,
+    on_progress: F,
+) -> Result<Vec<RefUpdate>, GitError> {
+    let mut args: Vec<&str> = vec!["--progress"];
+    if options.prune.is_some_and(|v| v) {
+        args.push("--prune");
+    }
+    push_tags_option(&mut args, options.tags);
+    if options.all.is_some_and(|v| v) {
+        args.push("--all");
+    } else if let Some(ref remote) = options.remote {
+        args.push(remote);
+        if let Some(ref refspec) = options.refspec {
+            args.push(refspec);
+        }
+    }
+    exec_with_progress(repo_path, "fetch", &args, on_progress).await
+}
@@ -1,18 +1,25 @@
+use super::backend::GitBackend;
+use super::cat_file::ObjectReader;
 use super::{exec, GitError};
+use crate::state::repositories::Repository;
 use std::path::Path;
 use tokio::fs::write;
 
+pub fn object_name(rel_path: &str, revspec: &str) -> String {
+    if revspec == "STAGED" {
+        format!(":{}", rel_path)
+    } else {
+        format!("{}:{}", revspec, rel_path)
+    }
+}
+
 pub async fn get_content(
     repo_path: &Path,
     rel_path: &str,
     revspec: &str,
     apply_filters: bool,
 ) -> Result<Vec<u8>, GitError> {
-    let target = if revspec == "STAGED" {
-        format!(":{}", rel_path)
-    } else {
-        format!("{}:{}", revspec, rel_path)
-    };
+    let target = object_name(rel_path, revspec);
     let args = if apply_filters {
         vec!["--filters", target.as_str()]
     } else {
@@ -24,14 +31,39 @@ pub async fn get_content(
     Ok(output.stdout)
 }
 
-pub async fn save_to(
+/// Same as `get_content`, but reads via the persistent `cat-file` process `object_reader`.
+///
+/// Falls back to `get_content` for contents in the index (which the persistent process
+/// can't follow changes of), and if reading fails (to report the error of git).
+pub async fn read_content(
+    object_reader: &ObjectReader,
     repo_path: &Path,
     rel_path: &str,
     revspec: &str,
+    apply_filters: bool,
+) -> Result<Vec<u8>, GitError> {
+    if revspec != "STAGED" && !revspec.starts_with(':') {
+        let target = object_name(rel_path, revspec);
+        match object_reader.read(&target, apply_filters).await {
+            Ok(Some(content)) => return Ok(content),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to read {} via cat-file --batch, {}", target, e),
+        }
+    }
+    get_content(repo_path, rel_path, revspec, apply_filters).await
+}
+
+pub async fn save_to(
+    repo: &Repository,
+    rel_path: &str,
+    revspec: &str,
     dest_path: &Path,
     apply_filters: bool,
 ) -> Result<(), GitError> {
-    let content = get_content(repo_path, rel_path, revspec, apply_filters).await?;
+    let content = repo
+        .backend
+        .read_content(rel_path, revspec, apply_filters)
+        .await?;
     write(dest_path, content).await?;
     Ok(())
 }
@@ -0,0 +1,201 @@
+use super::{exec, GitError};
+use std::path::Path;
+use types::*;
+
+/**
+ * parse output of `git grep -z -n --column [-C <context>]`
+ *
+ * match line:   {path}\0{line}\0{column}\0{text}
+ * context line: {path}\0{line}\0{text}
+ * separator:    --
+ *
+ * {path} is prefixed with "{revspec}:" if revspec is specified.
+ */
+fn parse_grep_output(
+    output: &str,
+    path_prefix: &str,
+    context: u32,
+    max_count: usize,
+) -> Result<GrepResult, GitError> {
+    let mut matches: Vec<GrepMatch> = Vec::new();
+    // context lines after the last match in the current group
+    let mut recent: Vec<GrepLine> = Vec::new();
+    // index of the first match in the current group
+    let mut group_start = 0;
+    let mut current_path = "";
+    let mut truncated = false;
+    for line in output.lines() {
+        if line == "--" {
+            recent.clear();
+            group_start = matches.len();
+            continue;
+        }
+        let tokens: Vec<&str> = line.splitn(4, '\0').collect();
+        let (path, line_number, column, text) = match tokens.as_slice() {
+            [path, line_number, column, text] => (*path, *line_number, Some(*column), *text),
+            [path, line_number, text] => (*path, *line_number, None, *text),
+            _ => {
+                return Err(GitError::UnexpectedOutput {
+                    command: String::from("grep"),
+                    text: line.to_string(),
+                })
+            }
+        };
+        let path = path.strip_prefix(path_prefix).unwrap_or(path);
+        let line_number = line_number
+            .parse::<u32>()
+            .map_err(|_| GitError::UnexpectedOutput {
+                command: String::from("grep"),
+                text: line.to_string(),
+            })?;
+        // lines of different files may be adjacent without separator
+        if path != current_path {
+            recent.clear();
+            group_start = matches.len();
+            current_path = path;
+        }
+        match column.and_then(|c| c.parse::<u32>().ok()) {
+            Some(column) => {
+                if matches.len() == max_count {
+                    truncated = true;
+                    break;
+                }
+                let before = recent
+                    .drain(..)
+                    .filter(|l| line_number <= l.line_number + context)
+                    .collect();
+                matches.push(GrepMatch {
+                    path: path.to_string(),
+                    line_number,
+                    column,
+                    text: text.to_string(),
+                    before,
+                    after: Vec::new(),
+                });
+            }
+            None => {
+                let context_line = GrepLine {
+                    line_number,
+                    text: text.to_string(),
+                };
+                if let Some(last) = matches[group_start..].last_mut() {
+                    if line_number <= last.line_number + context {
+                        last.after.push(context_line.clone());
+                    }
+                }
+                recent.push(context_line);
+            }
+        }
+    }
+    Ok(GrepResult { matches, truncated })
+}
+
+/// Searches files in `options.revspec`.
+///
+/// `revspec` can be "STAGED" (the index) or "UNSTAGED" (the working tree).
+/// A revspec starting with `-` is rejected, since it would be parsed as an option.
+pub async fn grep(repo_path: &Path, options: &GrepOptions) -> Result<GrepResult, GitError> {
+    if options.revspec.starts_with('-') {
+        return Err(GitError::ArgumentError {
+            command: String::from("grep"),
+            message: format!("Invalid revspec: {}", options.revspec),
+        });
+    }
+    let context = options.context.unwrap_or(0);
+    let context_option = format!("--context={}", context);
+    let mut args = vec!["-z", "-n", "--column", "-I", context_option.as_str()];
+    if options.fixed_strings.is_some_and(|v| v) {
+        args.push("--fixed-strings");
+    } else {
+        args.push("--extended-regexp");
+    }
+    if options.ignore_case.is_some_and(|v| v) {
+        args.push("--ignore-case");
+    }
+    if options.word_regexp.is_some_and(|v| v) {
+        args.push("--word-regexp");
+    }
+    args.push("-e");
+    args.push(&options.pattern);
+    let path_prefix = match options.revspec.as_str() {
+        "STAGED" => {
+            args.push("--cached");
+            String::new()
+        }
+        "UNSTAGED" => String::new(),
+        revspec => {
+            args.push(revspec);
+            format!("{}:", revspec)
+        }
+    };
+    args.push("--");
+    let excludes = options
+        .excludes
+        .iter()
+        .flatten()
+        .map(|p| format!(":(exclude){}", p))
+        .collect::<Vec<_>>();
+    args.extend(options.includes.iter().flatten().map(|p| p.as_str()));
+    args.extend(excludes.iter().map(|p| p.as_str()));
+    let output = exec(repo_path, "grep", &args, &[]).await?;
+    // exit code 1 means that nothing matched
+    if output.status.code() != Some(1) {
+        GitError::assert_process_output("grep", &output)?;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let max_count = options.max_count.map_or(usize::MAX, |c| c as usize);
+    parse_grep_output(&stdout, &path_prefix, context, max_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(line_number: u32, text: &str) -> GrepLine {
+        GrepLine {
+            line_number,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_grep_output() {
+        let output = "\
+            HEAD:a.txt\x001\x00a\n\
+            HEAD:a.txt\x002\x001\x00foo bar\n\
+            HEAD:a.txt\x003\x00b\n\
+            HEAD:a.txt\x004\x005\x00x foo\n\
+            HEAD:a.txt\x005\x00c\n\
+            --\n\
+            HEAD:a.txt\x009\x00d\n\
+            HEAD:a.txt\x0010\x001\x00foo\n\
+            HEAD:b:c.txt\x001\x001\x00foo\n\
+            HEAD:b:c.txt\x002\x00e\n\
+        ";
+        let result = parse_grep_output(output, "HEAD:", 1, usize::MAX).unwrap();
+        assert!(!result.truncated);
+        assert_eq!(4, result.matches.len());
+        let m = &result.matches[0];
+        assert_eq!(
+            ("a.txt", 2, 1, "foo bar"),
+            (m.path.as_str(), m.line_number, m.column, m.text.as_str())
+        );
+        assert_eq!(vec![line(1, "a")], m.before);
+        assert_eq!(vec![line(3, "b")], m.after);
+        let m = &result.matches[1];
+        assert_eq!((4, 5), (m.line_number, m.column));
+        assert_eq!(vec![line(3, "b")], m.before);
+        assert_eq!(vec![line(5, "c")], m.after);
+        let m = &result.matches[2];
+        assert_eq!(vec![line(9, "d")], m.before);
+        assert!(m.after.is_empty());
+        let m = &result.matches[3];
+        assert_eq!("b:c.txt", m.path);
+        assert!(m.before.is_empty());
+        assert_eq!(vec![line(2, "e")], m.after);
+
+        let result = parse_grep_output(output, "HEAD:", 1, 2).unwrap();
+        assert!(result.truncated);
+        assert_eq!(2, result.matches.len());
+    }
+}
@@ -0,0 +1,151 @@
+use super::{build_command, decode_output, wait_with_stderr_handler, GitError};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TRACE_FILE_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// Hooks which can stop the command by exiting with non-zero code.
+///
+/// The exit code of other hooks (e.g. post-checkout) is ignored by git.
+const BLOCKING_HOOKS: &[&str] = &[
+    "pre-commit",
+    "prepare-commit-msg",
+    "commit-msg",
+    "pre-merge-commit",
+    "pre-push",
+    "pre-rebase",
+];
+
+/// An event written by `GIT_TRACE2_EVENT` (only fields used to detect hooks)
+#[derive(Deserialize)]
+struct TraceEvent {
+    event: String,
+    sid: String,
+    child_id: Option<u32>,
+    child_class: Option<String>,
+    hook_name: Option<String>,
+    argv: Option<Vec<String>>,
+    code: Option<i32>,
+}
+
+/// Finds the blocking hook which exited with non-zero code
+/// from events written by `GIT_TRACE2_EVENT`.
+///
+/// Hooks may run git commands which append their own events to the same file,
+/// so each child process is identified by the session id and the child id.
+fn find_failed_hook(trace: &str) -> Option<String> {
+    let mut hooks: HashMap<(String, u32), String> = HashMap::new();
+    for line in trace.lines() {
+        let Ok(event) = serde_json::from_str::<TraceEvent>(line) else {
+            continue;
+        };
+        let Some(child_id) = event.child_id else {
+            continue;
+        };
+        match event.event.as_str() {
+            "child_start" if event.child_class.as_deref() == Some("hook") => {
+                // hook_name is not written by git older than 2.36
+                let name = event.hook_name.or_else(|| {
+                    let path = event.argv?.into_iter().next()?;
+                    let name = Path::new(&path).file_name()?.to_string_lossy().into_owned();
+                    Some(name)
+                });
+                if let Some(name) = name.filter(|n| BLOCKING_HOOKS.contains(&n.as_str())) {
+                    hooks.insert((event.sid, child_id), name);
+                }
+            }
+            "child_exit" if event.code.is_some_and(|c| c != 0) => {
+                if let Some(name) = hooks.remove(&(event.sid, child_id)) {
+                    return Some(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn new_trace_file() -> std::io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join("inazuma");
+    std::fs::create_dir_all(&dir)?;
+    let seq = TRACE_FILE_SEQ.fetch_add(1, Ordering::Relaxed);
+    Ok(dir.join(format!("trace2-{}-{}.json", std::process::id(), seq)))
+}
+
+/// Executes git command which may run hooks (e.g. commit, merge, push),
+/// and passes each line of stderr to `on_stderr_line` like `exec_with_stderr_handler`.
+///
+/// Since git redirects the output of most hooks to stderr, it can be shown while the hooks are
+/// running (e.g. linters in pre-commit hook).
+/// If the command fails after a hook has exited with non-zero code,
+/// returns `GitError::HookFailed` with the name of the hook.
+/// Its `stderr` includes stdout too, since the output of some hooks (e.g. pre-push)
+/// is written to stdout of git.
+pub async fn exec_with_hooks<F: FnMut(&str) -> bool>(
+    repo_path: &Path,
+    command: &str,
+    args: &[&str],
+    configs: &[&str],
+    on_stderr_line: F,
+) -> Result<Output, GitError> {
+    let trace_file = new_trace_file()?;
+    let mut cmd = build_command(repo_path, command, args, configs);
+    // trace2.eventTarget cannot be specified by `-c`, since it is read before parsing options
+    cmd.env("GIT_TRACE2_EVENT", &trace_file);
+    debug!("{}, git {}, {:?}", repo_path.display(), command, args);
+    let result = wait_with_stderr_handler(cmd, command, on_stderr_line).await;
+    let trace = tokio::fs::read_to_string(&trace_file)
+        .await
+        .unwrap_or_default();
+    if let Err(e) = tokio::fs::remove_file(&trace_file).await {
+        warn!("Failed to remove {}, {}", trace_file.display(), e);
+    }
+    let output = result?;
+    if !output.status.success() {
+        if let Some(hook) = find_failed_hook(&trace) {
+            let mut text = decode_output(&output.stdout).into_owned();
+            text.push_str(&decode_output(&output.stderr));
+            return Err(GitError::HookFailed {
+                command: command.to_owned(),
+                hook,
+                exit_code: output.status.code().unwrap_or(-1),
+                stderr: text.trim().to_string(),
+            });
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_failed_hook() {
+        let trace = r#"{"event":"version","sid":"s1","evt":"3","exe":"2.39.5"}
+{"event":"child_start","sid":"s1","child_id":0,"child_class":"?","use_shell":false,"argv":["git","maintenance","run","--auto"]}
+{"event":"child_exit","sid":"s1","child_id":0,"pid":100,"code":1}
+{"event":"child_start","sid":"s1","child_id":1,"child_class":"hook","hook_name":"pre-commit","use_shell":false,"argv":[".git/hooks/pre-commit"]}
+{"event":"child_start","sid":"s1/s2","child_id":0,"child_class":"hook","hook_name":"post-checkout","use_shell":false,"argv":[".git/hooks/post-checkout"]}
+{"event":"child_exit","sid":"s1/s2","child_id":0,"pid":102,"code":0}
+{"event":"child_exit","sid":"s1","child_id":1,"pid":101,"code":0}
+{"event":"child_start","sid":"s1","child_id":2,"child_class":"hook","use_shell":false,"argv":["/repo/.git/hooks/commit-msg",".git/COMMIT_EDITMSG"]}
+{"event":"child_exit","sid":"s1","child_id":2,"pid":103,"code":1}
+"#;
+        assert_eq!(Some("commit-msg".to_owned()), find_failed_hook(trace));
+        assert_eq!(
+            None,
+            find_failed_hook(&trace.replace(r#""code":1}"#, r#""code":0}"#))
+        );
+        assert_eq!(None, find_failed_hook(""));
+
+        // exit code of post-checkout is ignored by git
+        let trace = r#"{"event":"child_start","sid":"s1","child_id":0,"child_class":"hook","hook_name":"post-checkout","use_shell":false,"argv":[".git/hooks/post-checkout"]}
+{"event":"child_exit","sid":"s1","child_id":0,"pid":100,"code":2}
+"#;
+        assert_eq!(None, find_failed_hook(trace));
+    }
+}
@@ -0,0 +1,23 @@
+use super::clone::parent_dir;
+use super::{exec_without_repository, GitError};
+use std::path::Path;
+use types::InitOptions;
+
+pub async fn init(options: &InitOptions) -> Result<(), GitError> {
+    let path = Path::new(&options.path);
+    let cwd = parent_dir("init", path)?;
+    std::fs::create_dir_all(cwd)?;
+    let mut args: Vec<&str> = vec!["--quiet"];
+    if let Some(ref initial_branch) = options.initial_branch {
+        args.push("--initial-branch");
+        args.push(initial_branch);
+    }
+    if options.bare.is_some_and(|v| v) {
+        args.push("--bare");
+    }
+    args.push("--");
+    args.push(&options.path);
+    let output = exec_without_repository(cwd, "init", &args, |_| false).await?;
+    GitError::assert_process_output("init", &output)?;
+    Ok(())
+}
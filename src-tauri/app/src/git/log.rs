@@ -1,5 +1,5 @@
 use super::commit_detail::parse_raw_numstat_rows;
-use super::{exec, GitError};
+use super::{decode_output, exec, exec_with_stdin, exec_with_stdout_handler, parse_date, GitError};
 use std::path::Path;
 use types::*;
 
@@ -8,7 +8,11 @@ use types::*;
  * id:{id}\n
  * parents:{parents}\n
  * author:{author}\n
+ * mail:{author mail}\n
  * date:{author-date}\n
+ * committer:{committer}\n
+ * committer_mail:{committer mail}\n
+ * commit_date:{committer-date}\n
  * summary:{summary}\n
  */
 const LOG_FORMAT: &str = "\
@@ -17,6 +21,9 @@ const LOG_FORMAT: &str = "\
     author:%an%n\
     mail:%ae%n\
     date:%at%n\
+    committer:%cn%n\
+    committer_mail:%ce%n\
+    commit_date:%ct%n\
     summary:%s";
 
 const ID: &str = "id";
@@ -24,6 +31,9 @@ const PARENTS: &str = "parents";
 const AUTHOR: &str = "author";
 const MAIL: &str = "mail";
 const DATE: &str = "date";
+const COMMITTER: &str = "committer";
+const COMMITTER_MAIL: &str = "committer_mail";
+const COMMIT_DATE: &str = "commit_date";
 const SUMMARY: &str = "summary";
 
 pub fn parse_log_output(output: &str) -> Result<Vec<Commit>, GitError> {
@@ -32,6 +42,9 @@ pub fn parse_log_output(output: &str) -> Result<Vec<Commit>, GitError> {
     let mut author = "";
     let mut mail_address = "";
     let mut date: u64 = 0;
+    let mut committer = "";
+    let mut committer_mail_address = "";
+    let mut commit_date: u64 = 0;
     let mut commits: Vec<Commit> = Vec::new();
     for line in output.lines() {
         let kv: Vec<&str> = line.splitn(2, ':').collect();
@@ -49,15 +62,33 @@ pub fn parse_log_output(output: &str) -> Result<Vec<Commit>, GitError> {
                 mail_address = value;
             }
             [DATE, value] => {
-                date = value.parse::<u64>().unwrap() * 1000;
+                date = parse_date("log", value)?;
+            }
+            [COMMITTER, value] => {
+                committer = value;
+            }
+            [COMMITTER_MAIL, value] => {
+                committer_mail_address = value;
+            }
+            [COMMIT_DATE, value] => {
+                commit_date = parse_date("log", value)?;
             }
             [SUMMARY, value] => {
-                commits.push(Commit::new(id, parents, author, mail_address, date, value));
+                commits.push(
+                    Commit::new(id, parents, author, mail_address, date, value).with_committer(
+                        committer,
+                        committer_mail_address,
+                        commit_date,
+                    ),
+                );
                 id = "";
                 parents = "";
                 author = "";
                 mail_address = "";
                 date = 0;
+                committer = "";
+                committer_mail_address = "";
+                commit_date = 0;
             }
             _ => {
                 return Err(GitError::UnexpectedOutput {
@@ -114,11 +145,261 @@ pub async fn log(
     let args = build_args(format.as_str(), max_count_option.as_str(), all, heads);
     let output = exec(repo_path, "log", &args, &[]).await?;
     GitError::assert_process_output("log", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    let commits = parse_log_output(stdout)?;
+    let stdout = decode_output(&output.stdout);
+    let commits = parse_log_output(&stdout)?;
     Ok(commits)
 }
 
+/**
+ * Position in the history, which is passed to the frontend as an opaque token.
+ *
+ * Tips are resolved to ids at the first page, so that following pages are not
+ * shifted by updates of refs.
+ *
+ * {skip}:{tip},{tip},...
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogCursor {
+    pub skip: u32,
+    pub tips: Vec<String>,
+}
+
+impl LogCursor {
+    pub fn encode(&self) -> String {
+        format!("{}:{}", self.skip, self.tips.join(","))
+    }
+
+    pub fn decode(token: &str) -> Result<LogCursor, GitError> {
+        let error = || GitError::ArgumentError {
+            command: String::from("log"),
+            message: format!("Invalid cursor: {}", token),
+        };
+        let (skip, tips) = token.split_once(':').ok_or_else(error)?;
+        let skip = skip.parse::<u32>().map_err(|_| error())?;
+        let tips = tips
+            .split(',')
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>();
+        if tips
+            .iter()
+            .any(|t| !t.chars().all(|c| c.is_ascii_hexdigit()))
+        {
+            return Err(error());
+        }
+        Ok(LogCursor { skip, tips })
+    }
+}
+
+/// Resolves ids of branches, tags, remote branches, HEAD and `heads`.
+pub async fn resolve_tips(repo_path: &Path, heads: &[&str]) -> Result<Vec<String>, GitError> {
+    let args = ["--branches", "--tags", "--remotes"];
+    let output = exec(repo_path, "rev-parse", &args, &[]).await?;
+    GitError::assert_process_output("rev-parse", &output)?;
+    let stdout = decode_output(&output.stdout);
+    let head = super::rev_parse::rev_parse(repo_path, "HEAD").await?;
+    let mut tips: Vec<String> = Vec::new();
+    for id in stdout
+        .lines()
+        .chain(head.as_deref())
+        .chain(heads.iter().copied())
+    {
+        if !id.is_empty() && !tips.iter().any(|t| t == id) {
+            tips.push(id.to_string());
+        }
+    }
+    Ok(tips)
+}
+
+/// Builds revisions to be passed to `git log --stdin`
+fn build_revs_input(tips: &[String], excludes: &[String]) -> String {
+    let mut input = String::new();
+    for tip in tips {
+        input.push_str(tip);
+        input.push('\n');
+    }
+    for exclude in excludes {
+        input.push('^');
+        input.push_str(exclude);
+        input.push('\n');
+    }
+    input
+}
+
+/// Loads a page of history from `cursor`, and returns commits with the cursor of the next page.
+///
+/// If `max_count` is 0, all commits are loaded.
+pub async fn log_page(
+    repo_path: &Path,
+    cursor: &LogCursor,
+    max_count: u32,
+) -> Result<(Vec<Commit>, Option<LogCursor>), GitError> {
+    if cursor.tips.is_empty() {
+        return Ok((Vec::new(), None));
+    }
+    let format = format!("--format={}", LOG_FORMAT);
+    let skip = format!("--skip={}", cursor.skip);
+    // load one more commit to know whether the next page exists
+    let max_count_option = max_count_option(if max_count > 0 { max_count + 1 } else { 0 });
+    let mut args = build_args(format.as_str(), max_count_option.as_str(), false, &[]);
+    args.push("--stdin");
+    args.push(&skip);
+    let input = build_revs_input(&cursor.tips, &[]);
+    let output = exec_with_stdin(repo_path, "log", &args, &[], input.as_bytes()).await?;
+    GitError::assert_process_output("log", &output)?;
+    let stdout = decode_output(&output.stdout);
+    let mut commits = parse_log_output(&stdout)?;
+    if max_count > 0 && commits.len() > max_count as usize {
+        commits.truncate(max_count as usize);
+        let next = LogCursor {
+            skip: cursor.skip + max_count,
+            tips: cursor.tips.clone(),
+        };
+        Ok((commits, Some(next)))
+    } else {
+        Ok((commits, None))
+    }
+}
+
+/// Loads commits which are reachable from `tips`, but not from `known_tips`.
+pub async fn log_since(
+    repo_path: &Path,
+    tips: &[String],
+    known_tips: &[String],
+) -> Result<Vec<Commit>, GitError> {
+    if tips.is_empty() {
+        return Ok(Vec::new());
+    }
+    let format = format!("--format={}", LOG_FORMAT);
+    let mut args = build_args(format.as_str(), "", false, &[]);
+    args.push("--stdin");
+    let input = build_revs_input(tips, known_tips);
+    let output = exec_with_stdin(repo_path, "log", &args, &[], input.as_bytes()).await?;
+    GitError::assert_process_output("log", &output)?;
+    let stdout = decode_output(&output.stdout);
+    parse_log_output(&stdout)
+}
+
+/// Loads all commits reachable from `tips`, and passes them to `on_batch` every `batch_size` commits
+/// while git is running.
+pub async fn stream_log<F: FnMut(Vec<Commit>)>(
+    repo_path: &Path,
+    tips: &[String],
+    batch_size: u32,
+    mut on_batch: F,
+) -> Result<(), GitError> {
+    if tips.is_empty() {
+        return Ok(());
+    }
+    let format = format!("--format={}", LOG_FORMAT);
+    let mut args = build_args(format.as_str(), "", false, &[]);
+    args.push("--stdin");
+    let input = build_revs_input(tips, &[]);
+    let mut buf = String::new();
+    let mut count = 0;
+    let mut error: Option<GitError> = None;
+    let output = exec_with_stdout_handler(repo_path, "log", &args, &[], input.as_bytes(), |line| {
+        buf.push_str(line);
+        buf.push('\n');
+        if line.starts_with(SUMMARY) {
+            count += 1;
+            if batch_size <= count {
+                match parse_log_output(&buf) {
+                    Ok(commits) => on_batch(commits),
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+                buf.clear();
+                count = 0;
+            }
+        }
+    })
+    .await?;
+    GitError::assert_process_output("log", &output)?;
+    if let Some(e) = error {
+        return Err(e);
+    }
+    if !buf.is_empty() {
+        on_batch(parse_log_output(&buf)?);
+    }
+    Ok(())
+}
+
+fn build_filter_args(filter: &HistoryFilter) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    let mut regex_used = false;
+    if let Some(ref author) = filter.author {
+        args.push(format!("--author={}", author));
+        regex_used = true;
+    }
+    if let Some(ref committer) = filter.committer {
+        args.push(format!("--committer={}", committer));
+        regex_used = true;
+    }
+    if let Some(ref message) = filter.message {
+        args.push(format!("--grep={}", message));
+        regex_used = true;
+    }
+    if let Some(ref pickaxe) = filter.pickaxe {
+        if filter.pickaxe_regex.is_some_and(|v| v) {
+            args.push(format!("-G{}", pickaxe));
+        } else {
+            args.push(format!("-S{}", pickaxe));
+        }
+    }
+    if regex_used {
+        args.push("--extended-regexp".to_string());
+    }
+    if filter.ignore_case.is_some_and(|v| v) {
+        args.push("--regexp-ignore-case".to_string());
+    }
+    // dates are in milliseconds as same as Commit::date
+    if let Some(since) = filter.since {
+        args.push(format!("--since=@{}", since / 1000));
+    }
+    if let Some(until) = filter.until {
+        args.push(format!("--until=@{}", until / 1000));
+    }
+    match filter.merges {
+        Some(MergesFilter::MergesOnly) => args.push("--merges".to_string()),
+        Some(MergesFilter::NoMerges) => args.push("--no-merges".to_string()),
+        None => {}
+    }
+    if filter.first_parent.is_some_and(|v| v) {
+        args.push("--first-parent".to_string());
+    }
+    if let Some(max_count) = filter.max_count {
+        args.push(max_count_option(max_count));
+    }
+    args
+}
+
+/// Searches commits which match `filter` in the history loaded by `log` with same `heads`,
+/// and returns ids of them.
+pub async fn search(
+    repo_path: &Path,
+    filter: &HistoryFilter,
+    heads: &[&str],
+) -> Result<Vec<String>, GitError> {
+    let filter_args = build_filter_args(filter);
+    let mut args = build_args("--format=%H", "", true, heads);
+    args.extend(
+        filter_args
+            .iter()
+            .map(|a| a.as_str())
+            .filter(|a| !a.is_empty()),
+    );
+    args.push("--");
+    if let Some(ref paths) = filter.paths {
+        args.extend(paths.iter().map(|p| p.as_str()));
+    }
+    let output = exec(repo_path, "log", &args, &[]).await?;
+    GitError::assert_process_output("log", &output)?;
+    let stdout = decode_output(&output.stdout);
+    Ok(stdout.lines().map(|l| l.to_string()).collect())
+}
+
 pub fn parse_filelog_output(output: &str) -> Result<Vec<FileLogEntry>, GitError> {
     /* LOG FORMAT with -z --name-status
      *
@@ -126,7 +407,11 @@ pub fn parse_filelog_output(output: &str) -> Result<Vec<FileLogEntry>, GitError>
      * id:{id}\n
      * parents:{parents}\n
      * author:{author}\n
+     * mail:{author mail}\n
      * date:{author-date}\n
+     * committer:{committer}\n
+     * committer_mail:{committer mail}\n
+     * commit_date:{committer-date}\n
      * summary:{summary}\0\n  # "\0\n" added before NUMSTAT ROW
      * <NUMSTAT ROW>
      */
@@ -136,6 +421,9 @@ pub fn parse_filelog_output(output: &str) -> Result<Vec<FileLogEntry>, GitError>
     let mut author = "";
     let mut mail_address = "";
     let mut date: u64 = 0;
+    let mut committer = "";
+    let mut committer_mail_address = "";
+    let mut commit_date: u64 = 0;
     let mut summary = "";
     let mut entries: Vec<FileLogEntry> = Vec::new();
     for line in output.lines() {
@@ -157,22 +445,41 @@ pub fn parse_filelog_output(output: &str) -> Result<Vec<FileLogEntry>, GitError>
                 mail_address = value;
             }
             [DATE, value] => {
-                date = value.parse::<u64>().unwrap() * 1000;
+                date = parse_date("log", value)?;
+            }
+            [COMMITTER, value] => {
+                committer = value;
+            }
+            [COMMITTER_MAIL, value] => {
+                committer_mail_address = value;
+            }
+            [COMMIT_DATE, value] => {
+                commit_date = parse_date("log", value)?;
             }
             [SUMMARY, value] => {
                 // last char is '\0'
-                summary = &value[..value.len() - 1];
+                summary = value.strip_suffix('\0').unwrap_or(value);
             }
             _ => {
                 // stat line
-                let commit = Commit::new(id, parents, author, mail_address, date, summary);
-                let entry = parse_raw_numstat_rows(line)?.remove(0);
+                let commit = Commit::new(id, parents, author, mail_address, date, summary)
+                    .with_committer(committer, committer_mail_address, commit_date);
+                let entry = parse_raw_numstat_rows(line)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| GitError::UnexpectedOutput {
+                        command: String::from("log"),
+                        text: line.to_string(),
+                    })?;
                 entries.push(FileLogEntry { commit, entry });
                 id = "";
                 parents = "";
                 author = "";
                 mail_address = "";
                 date = 0;
+                committer = "";
+                committer_mail_address = "";
+                commit_date = 0;
                 summary = "";
             }
         }
@@ -198,8 +505,8 @@ pub async fn filelog(
     args.push(rel_path);
     let output = exec(repo_path, "log", &args, &[]).await?;
     GitError::assert_process_output("log", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    let entries = parse_filelog_output(stdout)?;
+    let stdout = decode_output(&output.stdout);
+    let entries = parse_filelog_output(&stdout)?;
     Ok(entries)
 }
 
@@ -212,8 +519,8 @@ pub async fn get_last_modify_commit(
     let args = vec![format.as_str(), "-1", revspec, "--", rel_path];
     let output = exec(repo_path, "log", &args, &[]).await?;
     GitError::assert_process_output("log", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    let mut commits = parse_log_output(stdout)?;
+    let stdout = decode_output(&output.stdout);
+    let mut commits = parse_log_output(&stdout)?;
 
     if commits.is_empty() {
         Ok(None)
@@ -234,18 +541,27 @@ mod tests {
             author:Carol\n\
             mail:carol@example.com\n\
             date:1612789513\n\
+            committer:Dave\n\
+            committer_mail:dave@example.com\n\
+            commit_date:1612790000\n\
             summary:\n\
             id:749b9a9000000000000000000000000000000000\n\
             parents:4f158cd300000000000000000000000000000000\n\
             author:Bob\n\
             mail:bob@example.com\n\
             date:1612789146\n\
+            committer:Bob\n\
+            committer_mail:bob@example.com\n\
+            commit_date:1612789146\n\
             summary:second commit\n\
             id:4f158cd300000000000000000000000000000000\n\
             parents:\n\
             author:Alice\n\
             mail:alice@example.com\n\
             date:1612789108\n\
+            committer:Alice\n\
+            committer_mail:alice@example.com\n\
+            commit_date:1612789108\n\
             summary:first commit\n\
         ";
         let expected =
@@ -256,21 +572,24 @@ mod tests {
                 "Carol",
                 "carol@example.com",
                 1612789513000,
-                ""),
+                "")
+            .with_committer("Dave", "dave@example.com", 1612790000000),
             Commit::new(
                 "749b9a9000000000000000000000000000000000",
                 "4f158cd300000000000000000000000000000000",
                 "Bob",
                 "bob@example.com",
                 1612789146000,
-                "second commit"),
+                "second commit")
+            .with_committer("Bob", "bob@example.com", 1612789146000),
             Commit::new(
                 "4f158cd300000000000000000000000000000000",
                 "",
                 "Alice",
                 "alice@example.com",
                 1612789108000,
-                "first commit"),
+                "first commit")
+            .with_committer("Alice", "alice@example.com", 1612789108000),
         ];
 
         let commits = parse_log_output(LOG_OUTPUT).unwrap();
@@ -279,4 +598,66 @@ mod tests {
             assert_eq!(exp, commit);
         }
     }
+
+    #[test]
+    fn cursor_test() {
+        let cursor = LogCursor {
+            skip: 100,
+            tips: vec![
+                "5cc9b4bc00000000000000000000000000000000".to_string(),
+                "749b9a9000000000000000000000000000000000".to_string(),
+            ],
+        };
+        let token = cursor.encode();
+        assert_eq!(
+            "100:5cc9b4bc00000000000000000000000000000000,749b9a9000000000000000000000000000000000",
+            token
+        );
+        assert_eq!(cursor, LogCursor::decode(&token).unwrap());
+        let empty = LogCursor::decode("0:").unwrap();
+        assert_eq!(0, empty.skip);
+        assert!(empty.tips.is_empty());
+        assert!(LogCursor::decode("100").is_err());
+        assert!(LogCursor::decode("x:5cc9b4bc").is_err());
+        assert!(LogCursor::decode("0:--all").is_err());
+    }
+
+    #[test]
+    fn filter_args_test() {
+        let filter = HistoryFilter {
+            author: Some("alice".to_string()),
+            committer: None,
+            since: Some(1612789108000),
+            until: None,
+            message: Some("fix(es)?".to_string()),
+            ignore_case: Some(true),
+            pickaxe: Some("needle".to_string()),
+            pickaxe_regex: None,
+            paths: Some(vec!["src".to_string()]),
+            merges: Some(MergesFilter::NoMerges),
+            first_parent: Some(true),
+            max_count: Some(10),
+        };
+        assert_eq!(
+            vec![
+                "--author=alice",
+                "--grep=fix(es)?",
+                "-Sneedle",
+                "--extended-regexp",
+                "--regexp-ignore-case",
+                "--since=@1612789108",
+                "--no-merges",
+                "--first-parent",
+                "-10",
+            ],
+            build_filter_args(&filter)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_date_test() {
+        const LOG_OUTPUT: &str =
+            "id:5cc9b4bc\nparents:\nauthor:Carol\nmail:carol@example.com\ndate:x\n";
+        assert!(parse_log_output(LOG_OUTPUT).is_err());
+    }
 }
@@ -1,55 +1,64 @@
-use super::{exec, GitError};
-use regex::Regex;
+use super::{decode_output, exec, quote_invalid_path, GitError};
+use regex::bytes::Regex;
 use std::{collections::HashMap, path::Path};
 use types::*;
 
+// paths are kept as raw bytes, since they may not be valid UTF-8
 enum EntryBase<'a> {
-    Blob(&'a str),
-    Tree(&'a str),
+    Blob(&'a [u8]),
+    Tree(&'a [u8]),
+    Submodule(&'a [u8]),
 }
 
-fn to_entry(base: &EntryBase, entries: &HashMap<&str, Vec<EntryBase>>) -> LstreeEntry {
-    match base {
-        EntryBase::Blob(path) => LstreeEntry {
-            data: LstreeData::Blob(path.to_string()),
-            children: None,
-        },
+fn to_entry(base: &EntryBase, entries: &HashMap<&[u8], Vec<EntryBase>>) -> LstreeEntry {
+    let entry = match base {
+        EntryBase::Blob(path) => {
+            LstreeEntry::new(LstreeData::Blob(decode_output(path).into_owned()), None)
+        }
+        EntryBase::Submodule(path) => LstreeEntry::new(
+            LstreeData::Submodule(decode_output(path).into_owned()),
+            None,
+        ),
         EntryBase::Tree(path) => {
             let children = entries.get(path).unwrap();
-            LstreeEntry {
-                data: LstreeData::Tree(path.to_string()),
-                children: Some(
+            LstreeEntry::new(
+                LstreeData::Tree(decode_output(path).into_owned()),
+                Some(
                     children
                         .iter()
                         .map(|child| to_entry(child, entries))
                         .collect(),
                 ),
-            }
+            )
         }
-    }
+    };
+    let (EntryBase::Blob(path) | EntryBase::Tree(path) | EntryBase::Submodule(path)) = base;
+    entry.with_quoted_path(quote_invalid_path(path))
 }
 
-fn parse_lstree_output<'a>(output: &'a str) -> Vec<LstreeEntry> {
-    let regex = Regex::new(r"^[0-9]+ (blob|tree) [^ ]+\t(.*)$").unwrap();
-    let mut entries: HashMap<&'a str, Vec<EntryBase<'a>>> = HashMap::new();
-    entries.insert("", Vec::new());
-    output.lines().for_each(|line| {
+fn parse_lstree_output<'a>(output: &'a [u8]) -> Vec<LstreeEntry> {
+    // `(?-u)` to match paths which are not valid UTF-8
+    let regex = Regex::new(r"(?-u)^[0-9]+ (blob|tree|commit) [^ ]+\t(.*)$").unwrap();
+    let mut entries: HashMap<&'a [u8], Vec<EntryBase<'a>>> = HashMap::new();
+    entries.insert(b"", Vec::new());
+    output.split(|b| *b == b'\n').for_each(|line| {
         if let Some(c) = regex.captures(line) {
-            let entry_type = c.get(1).unwrap().as_str();
-            let path = c.get(2).unwrap().as_str();
-            let parent_path = Path::new(path)
-                .parent()
-                .and_then(|p| p.to_str())
-                .unwrap_or("");
-            let entry = if entry_type == "tree" {
-                EntryBase::Tree(path)
-            } else {
-                EntryBase::Blob(path)
+            let entry_type = c.get(1).unwrap().as_bytes();
+            let path = c.get(2).unwrap().as_bytes();
+            let parent_path = match path.iter().rposition(|b| *b == b'/') {
+                Some(pos) => &path[..pos],
+                None => b"",
+            };
+            let entry = match entry_type {
+                b"tree" => EntryBase::Tree(path),
+                // gitlink
+                b"commit" => EntryBase::Submodule(path),
+                _ => EntryBase::Blob(path),
             };
             entries.entry(parent_path).or_insert(Vec::new()).push(entry);
         }
     });
-    let root = to_entry(&EntryBase::Tree(""), &entries);
+    let root = to_entry(&EntryBase::Tree(b""), &entries);
     if let Some(children) = root.children {
         children
     } else {
@@ -62,6 +71,5 @@ pub async fn lstree(repo_path: &Path, sha: &str) -> Result<Vec<LstreeEntry>, Git
     let configs = vec!["core.quotePath=false"];
     let output = exec(repo_path, "ls-tree", &args, &configs).await?;
     GitError::assert_process_output("ls-tree", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    Ok(parse_lstree_output(stdout))
+    Ok(parse_lstree_output(&output.stdout))
 }
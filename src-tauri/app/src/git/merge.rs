@@ -0,0 +1,57 @@
+use super::{exec, hook, operation, GitError};
+use std::path::Path;
+use types::{MergeOptions, OperationKind, OperationState};
+
+/// Merges a commit, passing each line of stderr (including the output of hooks) to `on_output`.
+///
+/// If the merge is stopped by a hook (pre-merge-commit or commit-msg), it is aborted
+/// so that it can be retried with `no_verify`.
+pub async fn merge<F: FnMut(&str)>(
+    repo_path: &Path,
+    options: &MergeOptions,
+    mut on_output: F,
+) -> Result<Option<OperationState>, GitError> {
+    let mut args: Vec<&str> = vec!["--no-edit"];
+    if options.no_ff.is_some_and(|v| v) {
+        args.push("--no-ff");
+    }
+    if options.ff_only.is_some_and(|v| v) {
+        args.push("--ff-only");
+    }
+    if options.squash.is_some_and(|v| v) {
+        args.push("--squash");
+    }
+    if options.no_commit.is_some_and(|v| v) {
+        args.push("--no-commit");
+    }
+    if options.no_verify.is_some_and(|v| v) {
+        args.push("--no-verify");
+    }
+    if let Some(ref message) = options.message {
+        if message.len() > 0 {
+            args.push("-m");
+            args.push(message);
+        }
+    }
+    args.push(&options.commit_id);
+    let before = operation::get_operation_state(repo_path).await?;
+    // the editor is not launched, since `--no-edit` is specified
+    let result = hook::exec_with_hooks(repo_path, "merge", &args, &[], |line| {
+        on_output(line);
+        false
+    })
+    .await;
+    let output = match result {
+        Ok(output) => output,
+        Err(e @ GitError::HookFailed { .. }) => {
+            let state = operation::get_operation_state(repo_path).await?;
+            if state.is_some_and(|s| s.kind == OperationKind::Merge) {
+                let output = exec(repo_path, "merge", &["--abort"], &[]).await?;
+                GitError::assert_process_output("merge", &output)?;
+            }
+            return Err(e);
+        }
+        Err(e) => return Err(e),
+    };
+    operation::check_operation_output(repo_path, "merge", &output, before).await
+}
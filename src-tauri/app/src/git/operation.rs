@@ -0,0 +1,199 @@
+use super::{exec_with_envs, worktree, GitError};
+use std::path::Path;
+use std::process::Output;
+use tokio::fs::read_to_string;
+use types::{OperationAction, OperationKind, OperationState};
+
+async fn read_trimmed(path: &Path) -> Result<Option<String>, GitError> {
+    if path.exists() {
+        let content = read_to_string(path).await?;
+        Ok(Some(content.trim_end().to_owned()))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn read_number(path: &Path) -> Result<Option<u32>, GitError> {
+    Ok(read_trimmed(path).await?.and_then(|v| v.parse().ok()))
+}
+
+fn new_state(kind: OperationKind, head: Option<String>) -> OperationState {
+    let actions = match kind {
+        OperationKind::Merge => vec![OperationAction::Continue, OperationAction::Abort],
+        OperationKind::Bisect => vec![OperationAction::Abort],
+        _ => vec![
+            OperationAction::Continue,
+            OperationAction::Skip,
+            OperationAction::Abort,
+        ],
+    };
+    OperationState {
+        kind,
+        head,
+        head_name: None,
+        onto: None,
+        step: None,
+        total: None,
+        actions,
+    }
+}
+
+/**
+ * parse the first command of `.git/sequencer/todo`, which is left while
+ * cherry-picking or reverting multiple commits
+ *
+ * pick 1234567 summary
+ * revert 89abcde summary
+ */
+fn parse_sequencer_todo(todo: &str) -> Option<OperationKind> {
+    let line = todo.lines().find(|l| l.len() > 0 && !l.starts_with('#'))?;
+    match line.split(' ').next()? {
+        "pick" | "p" => Some(OperationKind::CherryPick),
+        "revert" => Some(OperationKind::Revert),
+        _ => None,
+    }
+}
+
+async fn get_rebase_state(
+    git_dir: &Path,
+    dir_name: &str,
+) -> Result<Option<OperationState>, GitError> {
+    let dir = git_dir.join(dir_name);
+    if !dir.exists() || dir.join("applying").exists() {
+        // "rebase-apply/applying" exists while running `git am`
+        return Ok(None);
+    }
+    let (step_file, total_file) = if dir_name == "rebase-merge" {
+        ("msgnum", "end")
+    } else {
+        ("next", "last")
+    };
+    let mut state = new_state(
+        OperationKind::Rebase,
+        read_trimmed(&git_dir.join("REBASE_HEAD")).await?,
+    );
+    state.head_name = read_trimmed(&dir.join("head-name"))
+        .await?
+        .map(|v| v.trim_start_matches("refs/heads/").to_owned());
+    state.onto = read_trimmed(&dir.join("onto")).await?;
+    state.step = read_number(&dir.join(step_file)).await?;
+    state.total = read_number(&dir.join(total_file)).await?;
+    Ok(Some(state))
+}
+
+/// Detects an operation (merge, rebase, cherry-pick, revert or bisect) in progress.
+pub async fn get_operation_state(repo_path: &Path) -> Result<Option<OperationState>, GitError> {
+    let git_dir = worktree::resolve_git_dir(repo_path);
+    for dir_name in ["rebase-merge", "rebase-apply"] {
+        if let Some(state) = get_rebase_state(&git_dir, dir_name).await? {
+            return Ok(Some(state));
+        }
+    }
+    let heads = [
+        ("MERGE_HEAD", OperationKind::Merge),
+        ("CHERRY_PICK_HEAD", OperationKind::CherryPick),
+        ("REVERT_HEAD", OperationKind::Revert),
+    ];
+    for (file_name, kind) in heads {
+        if let Some(content) = read_trimmed(&git_dir.join(file_name)).await? {
+            let head = content.lines().next().map(|v| v.to_owned());
+            return Ok(Some(new_state(kind, head)));
+        }
+    }
+    if let Some(todo) = read_trimmed(&git_dir.join("sequencer/todo")).await? {
+        if let Some(kind) = parse_sequencer_todo(&todo) {
+            return Ok(Some(new_state(kind, None)));
+        }
+    }
+    if git_dir.join("BISECT_LOG").exists() {
+        return Ok(Some(new_state(OperationKind::Bisect, None)));
+    }
+    Ok(None)
+}
+
+/// Executes a command which may stop in the middle of an operation (e.g. on conflicts).
+///
+/// Returns the operation state after the command.
+/// A failure of the command is not treated as an error if it has stopped at a new point
+/// of the operation, so that the caller can show the state to resolve it.
+/// `envs` are added to the environment variables of the command.
+pub async fn exec_operation(
+    repo_path: &Path,
+    command: &str,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> Result<Option<OperationState>, GitError> {
+    let before = get_operation_state(repo_path).await?;
+    // accept default messages instead of launching an editor.
+    // `core.editor` is not used, since GIT_EDITOR inherited by the app takes precedence.
+    let mut envs = envs.to_vec();
+    envs.push(("GIT_EDITOR", "true"));
+    let output = exec_with_envs(repo_path, command, args, &[], &envs).await?;
+    check_operation_output(repo_path, command, &output, before).await
+}
+
+/// Returns the operation state after the command which has been started at `before` state.
+///
+/// Returns an error if the command has failed without stopping at a new point of the operation.
+pub async fn check_operation_output(
+    repo_path: &Path,
+    command: &str,
+    output: &Output,
+    before: Option<OperationState>,
+) -> Result<Option<OperationState>, GitError> {
+    let after = get_operation_state(repo_path).await?;
+    if !output.status.success() && (after.is_none() || after == before) {
+        GitError::assert_process_output(command, output)?;
+    }
+    Ok(after)
+}
+
+pub async fn run_action(
+    repo_path: &Path,
+    action: OperationAction,
+) -> Result<Option<OperationState>, GitError> {
+    let Some(state) = get_operation_state(repo_path).await? else {
+        return Err(GitError::ArgumentError {
+            command: String::from("operation"),
+            message: String::from("no operation in progress"),
+        });
+    };
+    if !state.actions.contains(&action) {
+        return Err(GitError::ArgumentError {
+            command: String::from("operation"),
+            message: format!("{:?} is not available for {:?}", action, state.kind),
+        });
+    }
+    let command = match state.kind {
+        OperationKind::Merge => "merge",
+        OperationKind::Rebase => "rebase",
+        OperationKind::CherryPick => "cherry-pick",
+        OperationKind::Revert => "revert",
+        OperationKind::Bisect => "bisect",
+    };
+    let arg = match (state.kind, action) {
+        (OperationKind::Bisect, _) => "reset",
+        (_, OperationAction::Continue) => "--continue",
+        (_, OperationAction::Skip) => "--skip",
+        (_, OperationAction::Abort) => "--abort",
+    };
+    exec_operation(repo_path, command, &[arg], &[]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sequencer_todo() {
+        assert_eq!(
+            Some(OperationKind::CherryPick),
+            parse_sequencer_todo("pick 1234567 summary\npick 89abcde summary\n")
+        );
+        assert_eq!(
+            Some(OperationKind::Revert),
+            parse_sequencer_todo("# comment\nrevert 1234567 summary\n")
+        );
+        assert_eq!(None, parse_sequencer_todo(""));
+    }
+}
@@ -0,0 +1,488 @@
+use super::GitError;
+use regex::bytes::Regex;
+use types::*;
+
+struct RawHunk<'a> {
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+    section: &'a [u8],
+    lines: Vec<&'a [u8]>,
+}
+
+struct RawPatch<'a> {
+    header: Vec<&'a [u8]>,
+    binary: bool,
+    hunks: Vec<RawHunk<'a>>,
+}
+
+fn parse_number(value: Option<regex::bytes::Match>, default: u32) -> u32 {
+    value
+        .and_then(|m| std::str::from_utf8(m.as_bytes()).ok())
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(default)
+}
+
+/**
+ * parse output generated by `git diff -- {path}` (single file)
+ *
+ * diff --git a/{path} b/{path}
+ * index xxxxxxx..xxxxxxx 100644
+ * --- a/{path}
+ * +++ b/{path}
+ * @@ -{old_start},{old_lines} +{new_start},{new_lines} @@ {section}
+ *  context
+ * -deleted
+ * +added
+ * \ No newline at end of file
+ */
+fn parse_raw_patch(udiff: &[u8]) -> Result<RawPatch<'_>, GitError> {
+    let hunk_regex = Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@(.*)$").unwrap();
+    let mut lines: Vec<&[u8]> = udiff.split(|b| *b == b'\n').collect();
+    if lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    let mut patch = RawPatch {
+        header: Vec::new(),
+        binary: false,
+        hunks: Vec::new(),
+    };
+    for line in lines {
+        if let Some(c) = hunk_regex.captures(line) {
+            patch.hunks.push(RawHunk {
+                old_start: parse_number(c.get(1), 0),
+                old_lines: parse_number(c.get(2), 1),
+                new_start: parse_number(c.get(3), 0),
+                new_lines: parse_number(c.get(4), 1),
+                section: c.get(5).unwrap().as_bytes(),
+                lines: Vec::new(),
+            });
+        } else if let Some(hunk) = patch.hunks.last_mut() {
+            match line.first() {
+                Some(b' ' | b'+' | b'-' | b'\\') => hunk.lines.push(line),
+                _ => {
+                    return Err(GitError::UnexpectedOutput {
+                        command: "diff".to_owned(),
+                        text: String::from_utf8_lossy(line).into_owned(),
+                    });
+                }
+            }
+        } else {
+            if line.starts_with(b"Binary files ") || line.starts_with(b"GIT binary patch") {
+                patch.binary = true;
+            }
+            patch.header.push(line);
+        }
+    }
+    Ok(patch)
+}
+
+pub fn parse_file_diff(path: &str, udiff: &[u8]) -> Result<FileDiff, GitError> {
+    let patch = parse_raw_patch(udiff)?;
+    let hunks = patch
+        .hunks
+        .iter()
+        .map(|h| {
+            let mut old_line_no = h.old_start;
+            let mut new_line_no = h.new_start;
+            let lines = h
+                .lines
+                .iter()
+                .map(|line| {
+                    let content = String::from_utf8_lossy(&line[1..]).into_owned();
+                    match line[0] {
+                        b'+' => {
+                            new_line_no += 1;
+                            DiffLine {
+                                kind: DiffLineKind::Addition,
+                                content,
+                                old_line_no: None,
+                                new_line_no: Some(new_line_no - 1),
+                            }
+                        }
+                        b'-' => {
+                            old_line_no += 1;
+                            DiffLine {
+                                kind: DiffLineKind::Deletion,
+                                content,
+                                old_line_no: Some(old_line_no - 1),
+                                new_line_no: None,
+                            }
+                        }
+                        b'\\' => DiffLine {
+                            kind: DiffLineKind::NoNewline,
+                            content,
+                            old_line_no: None,
+                            new_line_no: None,
+                        },
+                        _ => {
+                            old_line_no += 1;
+                            new_line_no += 1;
+                            DiffLine {
+                                kind: DiffLineKind::Context,
+                                content,
+                                old_line_no: Some(old_line_no - 1),
+                                new_line_no: Some(new_line_no - 1),
+                            }
+                        }
+                    }
+                })
+                .collect();
+            DiffHunk {
+                header: String::from_utf8_lossy(h.section).trim().to_owned(),
+                old_start: h.old_start,
+                old_lines: h.old_lines,
+                new_start: h.new_start,
+                new_lines: h.new_lines,
+                lines,
+            }
+        })
+        .collect();
+    Ok(FileDiff {
+        path: path.to_owned(),
+        binary: patch.binary,
+        hunks,
+    })
+}
+
+/// Fixes `\ No newline at end of file` markers which are left in the middle of a side
+/// of a partial hunk, since `git apply` joins the marked line with the following line.
+///
+/// - A context line (unselected deletion) followed by additions must end with a newline
+///   in the new side, so it is replaced with the deletion and the addition of the same line.
+/// - A deletion followed by context lines (unselected additions, for `--reverse`) gets
+///   a newline in the old side, so the marker is dropped.
+fn fix_no_newline_markers(lines: &mut Vec<Vec<u8>>) {
+    let Some(i) = lines.iter().position(|l| l[0] == b'\\') else {
+        return;
+    };
+    if i == 0 {
+        return;
+    }
+    let following = &lines[i + 1..];
+    match lines[i - 1][0] {
+        b' ' if following.iter().any(|l| l[0] == b'+') => {
+            let mut addition = lines[i - 1].clone();
+            addition[0] = b'+';
+            lines[i - 1][0] = b'-';
+            lines.insert(i + 1, addition);
+        }
+        b'-' if following.iter().any(|l| l[0] == b' ') => {
+            lines.remove(i);
+        }
+        _ => {}
+    }
+}
+
+/// Builds a patch which contains only selected lines of `udiff`.
+///
+/// Unselected changes are turned into context or dropped, depending on which side
+/// of the diff the patch will be applied to.
+/// If `reverse` is true, the result is expected to be applied with `git apply --reverse`.
+pub fn build_partial_patch(
+    udiff: &[u8],
+    selections: &[HunkSelection],
+    reverse: bool,
+) -> Result<Vec<u8>, GitError> {
+    let patch = parse_raw_patch(udiff)?;
+    if patch.binary {
+        return Err(GitError::ArgumentError {
+            command: "apply".to_owned(),
+            message: "partial patch of binary file is not supported".to_owned(),
+        });
+    }
+    let mut ret: Vec<u8> = Vec::new();
+    for line in patch.header.iter() {
+        ret.extend_from_slice(line);
+        ret.push(b'\n');
+    }
+    let mut offset: i64 = 0;
+    let mut hunk_count = 0;
+    for (index, hunk) in patch.hunks.iter().enumerate() {
+        let Some(selection) = selections.iter().find(|s| s.hunk_index as usize == index) else {
+            continue;
+        };
+        let is_selected = |i: usize| {
+            selection
+                .line_indices
+                .as_ref()
+                .map_or(true, |v| v.contains(&(i as u32)))
+        };
+        let mut lines: Vec<Vec<u8>> = Vec::new();
+        let mut old_lines: u32 = 0;
+        let mut new_lines: u32 = 0;
+        let mut changed = false;
+        let mut previous_kept = true;
+        for (i, &line) in hunk.lines.iter().enumerate() {
+            match line[0] {
+                b'+' | b'-' => {
+                    let addition = line[0] == b'+';
+                    if is_selected(i) {
+                        lines.push(line.to_vec());
+                        if addition {
+                            new_lines += 1;
+                        } else {
+                            old_lines += 1;
+                        }
+                        changed = true;
+                        previous_kept = true;
+                    } else if addition == reverse {
+                        // keep the line which exists in the target as context
+                        let mut context = line.to_vec();
+                        context[0] = b' ';
+                        lines.push(context);
+                        old_lines += 1;
+                        new_lines += 1;
+                        previous_kept = true;
+                    } else {
+                        previous_kept = false;
+                    }
+                }
+                b'\\' => {
+                    if previous_kept {
+                        lines.push(line.to_vec());
+                    }
+                }
+                _ => {
+                    lines.push(line.to_vec());
+                    old_lines += 1;
+                    new_lines += 1;
+                    previous_kept = true;
+                }
+            }
+        }
+        if !changed {
+            continue;
+        }
+        fix_no_newline_markers(&mut lines);
+        let (old_start, new_start) = if reverse {
+            let old_start = (hunk.new_start as i64 - offset).max(0) as u32;
+            (old_start, hunk.new_start)
+        } else {
+            let new_start = (hunk.old_start as i64 + offset).max(0) as u32;
+            (hunk.old_start, new_start)
+        };
+        offset += new_lines as i64 - old_lines as i64;
+        ret.extend_from_slice(
+            format!(
+                "@@ -{},{} +{},{} @@",
+                old_start, old_lines, new_start, new_lines
+            )
+            .as_bytes(),
+        );
+        ret.extend_from_slice(hunk.section);
+        ret.push(b'\n');
+        for line in lines {
+            ret.extend_from_slice(&line);
+            ret.push(b'\n');
+        }
+        hunk_count += 1;
+    }
+    if hunk_count == 0 {
+        return Err(GitError::ArgumentError {
+            command: "apply".to_owned(),
+            message: "no changes selected".to_owned(),
+        });
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UDIFF: &str = "\
+diff --git a/src/a.txt b/src/a.txt
+index 1111111..2222222 100644
+--- a/src/a.txt
++++ b/src/a.txt
+@@ -1,4 +1,4 @@ fn main
+ line1
+-line2
++line2 modified
+ line3
+ line4
+@@ -10,3 +10,4 @@
+ line10
++line10.5
+ line11
+-line12
+\\ No newline at end of file
++line12
+";
+
+    const HEADER: &str = "\
+diff --git a/src/a.txt b/src/a.txt
+index 1111111..2222222 100644
+--- a/src/a.txt
++++ b/src/a.txt
+";
+
+    fn select(hunk_index: u32, line_indices: Option<Vec<u32>>) -> HunkSelection {
+        HunkSelection {
+            hunk_index,
+            line_indices,
+        }
+    }
+
+    #[test]
+    fn test_parse_file_diff() {
+        let diff = parse_file_diff("src/a.txt", UDIFF.as_bytes()).unwrap();
+        assert!(!diff.binary);
+        assert_eq!(2, diff.hunks.len());
+        let hunk = &diff.hunks[0];
+        assert_eq!("fn main", hunk.header);
+        assert_eq!(
+            (1, 4, 1, 4),
+            (
+                hunk.old_start,
+                hunk.old_lines,
+                hunk.new_start,
+                hunk.new_lines
+            )
+        );
+        assert_eq!(
+            DiffLine {
+                kind: DiffLineKind::Deletion,
+                content: "line2".to_owned(),
+                old_line_no: Some(2),
+                new_line_no: None,
+            },
+            hunk.lines[1]
+        );
+        assert_eq!(
+            DiffLine {
+                kind: DiffLineKind::Context,
+                content: "line3".to_owned(),
+                old_line_no: Some(3),
+                new_line_no: Some(3),
+            },
+            hunk.lines[3]
+        );
+        let hunk = &diff.hunks[1];
+        assert_eq!(6, hunk.lines.len());
+        assert_eq!(DiffLineKind::NoNewline, hunk.lines[4].kind);
+        assert_eq!(Some(13), hunk.lines[5].new_line_no);
+    }
+
+    #[test]
+    fn test_parse_binary() {
+        const OUTPUT: &str = "\
+diff --git a/image.png b/image.png
+index 1111111..2222222 100644
+Binary files a/image.png and b/image.png differ
+";
+        let diff = parse_file_diff("image.png", OUTPUT.as_bytes()).unwrap();
+        assert!(diff.binary);
+        assert!(diff.hunks.is_empty());
+    }
+
+    #[test]
+    fn test_build_whole_hunk() {
+        let patch = build_partial_patch(UDIFF.as_bytes(), &[select(0, None)], false).unwrap();
+        let expected = format!(
+            "{}{}",
+            HEADER,
+            "\
+@@ -1,4 +1,4 @@ fn main
+ line1
+-line2
++line2 modified
+ line3
+ line4
+"
+        );
+        assert_eq!(expected, String::from_utf8(patch).unwrap());
+    }
+
+    #[test]
+    fn test_build_selected_lines() {
+        // select only "+line2 modified", "+line10.5" and "-line12"
+        let selections = [select(0, Some(vec![2])), select(1, Some(vec![1, 3]))];
+        let patch = build_partial_patch(UDIFF.as_bytes(), &selections, false).unwrap();
+        let expected = format!(
+            "{}{}",
+            HEADER,
+            "\
+@@ -1,4 +1,5 @@ fn main
+ line1
+ line2
++line2 modified
+ line3
+ line4
+@@ -10,3 +11,3 @@
+ line10
++line10.5
+ line11
+-line12
+\\ No newline at end of file
+"
+        );
+        assert_eq!(expected, String::from_utf8(patch).unwrap());
+    }
+
+    #[test]
+    fn test_build_selected_lines_reverse() {
+        // unstage only "-line2"
+        let selections = [select(0, Some(vec![1]))];
+        let patch = build_partial_patch(UDIFF.as_bytes(), &selections, true).unwrap();
+        let expected = format!(
+            "{}{}",
+            HEADER,
+            "\
+@@ -1,5 +1,4 @@ fn main
+ line1
+-line2
+ line2 modified
+ line3
+ line4
+"
+        );
+        assert_eq!(expected, String::from_utf8(patch).unwrap());
+    }
+
+    #[test]
+    fn test_build_no_newline_marker() {
+        // select only "+line12", then "line12" without newline must be terminated
+        let selections = [select(1, Some(vec![5]))];
+        let patch = build_partial_patch(UDIFF.as_bytes(), &selections, false).unwrap();
+        let expected = format!(
+            "{}{}",
+            HEADER,
+            "\
+@@ -10,3 +10,4 @@
+ line10
+ line11
+-line12
+\\ No newline at end of file
++line12
++line12
+"
+        );
+        assert_eq!(expected, String::from_utf8(patch).unwrap());
+
+        // unstage only "-line12", then "line12" restored before "+line12" gets newline
+        let selections = [select(1, Some(vec![3]))];
+        let patch = build_partial_patch(UDIFF.as_bytes(), &selections, true).unwrap();
+        let expected = format!(
+            "{}{}",
+            HEADER,
+            "\
+@@ -10,5 +10,4 @@
+ line10
+ line10.5
+ line11
+-line12
+ line12
+"
+        );
+        assert_eq!(expected, String::from_utf8(patch).unwrap());
+    }
+
+    #[test]
+    fn test_build_nothing_selected() {
+        let selections = [select(1, Some(vec![0, 2]))];
+        assert!(build_partial_patch(UDIFF.as_bytes(), &selections, false).is_err());
+    }
+}
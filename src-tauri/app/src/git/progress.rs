@@ -0,0 +1,220 @@
+use regex::Regex;
+use std::sync::LazyLock;
+use types::{RefUpdate, RefUpdateStatus, RemoteProgress};
+
+// compiled once, since every line of stderr is parsed while fetching or pushing
+static PROGRESS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:remote: )?([A-Za-z][A-Za-z ]*):\s+(?:(\d+)% \((\d+)/(\d+)\)|(\d+))(.*)$")
+        .unwrap()
+});
+static FETCH_REF_UPDATE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^ (.) (\[[^\]]+\]|\S+)\s+(\S+)\s+->\s+(\S+)(?:\s+\((.+)\))?$").unwrap()
+});
+
+/**
+ * parse progress line written to stderr by `git {fetch|push|pull|clone} --progress`
+ *
+ * Enumerating objects: 5, done.
+ * remote: Counting objects: 100% (5/5), done.
+ * Receiving objects:  40% (2/5), 1.00 KiB | 1.00 MiB/s
+ */
+pub fn parse_progress_line(line: &str) -> Option<RemoteProgress> {
+    let c = PROGRESS_REGEX.captures(line)?;
+    let phase = c[1].trim().to_owned();
+    let done = c[6].contains("done");
+    if let Some(percent) = c.get(2) {
+        Some(RemoteProgress {
+            phase,
+            percent: percent.as_str().parse().ok(),
+            current: c[3].parse().ok(),
+            total: c[4].parse().ok(),
+            done,
+        })
+    } else {
+        Some(RemoteProgress {
+            phase,
+            percent: None,
+            current: c[5].parse().ok(),
+            total: None,
+            done,
+        })
+    }
+}
+
+fn to_ref_update_status(flag: &str) -> Option<RefUpdateStatus> {
+    match flag {
+        " " => Some(RefUpdateStatus::FastForward),
+        "+" => Some(RefUpdateStatus::Forced),
+        "*" => Some(RefUpdateStatus::New),
+        "-" => Some(RefUpdateStatus::Deleted),
+        "t" => Some(RefUpdateStatus::TagUpdate),
+        "=" => Some(RefUpdateStatus::UpToDate),
+        "!" => Some(RefUpdateStatus::Rejected),
+        _ => None,
+    }
+}
+
+/**
+ * parse ref update line written to stderr by `git fetch`
+ *
+ *  * [new branch]      feature    -> origin/feature
+ *    1234567..89abcde  main       -> origin/main
+ *    ! [rejected]        main       -> main  (non-fast-forward)
+ */
+pub fn parse_fetch_ref_update_line(line: &str) -> Option<RefUpdate> {
+    let c = FETCH_REF_UPDATE_REGEX.captures(line)?;
+    Some(RefUpdate {
+        status: to_ref_update_status(&c[1])?,
+        summary: c[2].to_owned(),
+        from: c[3].to_owned(),
+        to: c[4].to_owned(),
+        reason: c.get(5).map(|m| m.as_str().to_owned()),
+    })
+}
+
+/**
+ * parse output generated by `git push --porcelain`
+ *
+ * To {url}
+ * {flag}\t{from}:{to}\t{summary} ({reason})
+ * Done
+ */
+pub fn parse_push_porcelain_output(output: &str) -> Vec<RefUpdate> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.splitn(3, '\t').collect();
+            match tokens.as_slice() {
+                [flag, refs, summary] => {
+                    let (from, to) = refs.split_once(':')?;
+                    let (summary, reason) = match summary.split_once(" (") {
+                        Some((summary, reason)) => {
+                            (summary, Some(reason.trim_end_matches(')').to_owned()))
+                        }
+                        None => (*summary, None),
+                    };
+                    Some(RefUpdate {
+                        status: to_ref_update_status(flag)?,
+                        from: from.to_owned(),
+                        to: to.to_owned(),
+                        summary: summary.to_owned(),
+                        reason,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress_line() {
+        assert_eq!(
+            Some(RemoteProgress {
+                phase: "Receiving objects".to_owned(),
+                percent: Some(40),
+                current: Some(2),
+                total: Some(5),
+                done: false,
+            }),
+            parse_progress_line("Receiving objects:  40% (2/5), 1.00 KiB | 1.00 MiB/s")
+        );
+        assert_eq!(
+            Some(RemoteProgress {
+                phase: "Counting objects".to_owned(),
+                percent: Some(100),
+                current: Some(5),
+                total: Some(5),
+                done: true,
+            }),
+            parse_progress_line("remote: Counting objects: 100% (5/5), done.")
+        );
+        assert_eq!(
+            Some(RemoteProgress {
+                phase: "Enumerating objects".to_owned(),
+                percent: None,
+                current: Some(5),
+                total: None,
+                done: true,
+            }),
+            parse_progress_line("Enumerating objects: 5, done.")
+        );
+        assert_eq!(None, parse_progress_line("From ../remote"));
+    }
+
+    #[test]
+    fn test_parse_fetch_ref_update_line() {
+        assert_eq!(
+            Some(RefUpdate {
+                status: RefUpdateStatus::New,
+                from: "feature".to_owned(),
+                to: "origin/feature".to_owned(),
+                summary: "[new branch]".to_owned(),
+                reason: None,
+            }),
+            parse_fetch_ref_update_line(" * [new branch]      feature    -> origin/feature")
+        );
+        assert_eq!(
+            Some(RefUpdate {
+                status: RefUpdateStatus::FastForward,
+                from: "main".to_owned(),
+                to: "origin/main".to_owned(),
+                summary: "1234567..89abcde".to_owned(),
+                reason: None,
+            }),
+            parse_fetch_ref_update_line("   1234567..89abcde  main       -> origin/main")
+        );
+        assert_eq!(
+            Some(RefUpdate {
+                status: RefUpdateStatus::Rejected,
+                from: "main".to_owned(),
+                to: "main".to_owned(),
+                summary: "[rejected]".to_owned(),
+                reason: Some("non-fast-forward".to_owned()),
+            }),
+            parse_fetch_ref_update_line(
+                " ! [rejected]        main       -> main  (non-fast-forward)"
+            )
+        );
+        assert_eq!(None, parse_fetch_ref_update_line("From ../remote"));
+    }
+
+    #[test]
+    fn test_parse_push_porcelain_output() {
+        const OUTPUT: &str = "\
+            To ../remote.git\n\
+            *\trefs/heads/feature:refs/heads/feature\t[new branch]\n\
+            !\trefs/heads/main:refs/heads/main\t[rejected] (fetch first)\n\
+            -\t:refs/heads/old\t[deleted]\n\
+            Done\n\
+            ";
+        let expected = vec![
+            RefUpdate {
+                status: RefUpdateStatus::New,
+                from: "refs/heads/feature".to_owned(),
+                to: "refs/heads/feature".to_owned(),
+                summary: "[new branch]".to_owned(),
+                reason: None,
+            },
+            RefUpdate {
+                status: RefUpdateStatus::Rejected,
+                from: "refs/heads/main".to_owned(),
+                to: "refs/heads/main".to_owned(),
+                summary: "[rejected]".to_owned(),
+                reason: Some("fetch first".to_owned()),
+            },
+            RefUpdate {
+                status: RefUpdateStatus::Deleted,
+                from: "".to_owned(),
+                to: "refs/heads/old".to_owned(),
+                summary: "[deleted]".to_owned(),
+                reason: None,
+            },
+        ];
+        assert_eq!(expected, parse_push_porcelain_output(OUTPUT));
+    }
+}
@@ -0,0 +1,31 @@
+use super::fetch::{exec_with_progress, push_tags_option};
+use super::GitError;
+use std::path::Path;
+use types::{PullOptions, RefUpdate, RemoteProgress};
+
+pub async fn pull<F: FnMut(RemoteProgress)>(
+    repo_path: &Path,
+    options: &PullOptions,
+    on_progress: F,
+) -> Result<Vec<RefUpdate>, GitError> {
+    let mut args: Vec<&str> = vec!["--progress", "--no-edit"];
+    match options.rebase {
+        Some(true) => args.push("--rebase"),
+        Some(false) => args.push("--no-rebase"),
+        None => {}
+    }
+    if options.ff_only.is_some_and(|v| v) {
+        args.push("--ff-only");
+    }
+    if options.prune.is_some_and(|v| v) {
+        args.push("--prune");
+    }
+    push_tags_option(&mut args, options.tags);
+    if let Some(ref remote) = options.remote {
+        args.push(remote);
+        if let Some(ref refspec) = options.refspec {
+            args.push(refspec);
+        }
+    }
+    exec_with_progress(repo_path, "pull", &args, on_progress).await
+}
@@ -0,0 +1,67 @@
+use super::progress::{parse_progress_line, parse_push_porcelain_output};
+use super::{hook, GitError};
+use std::path::Path;
+use types::{PushOptions, RefUpdate, RefUpdateStatus, RemoteProgress};
+
+/// Pushes to a remote repository.
+///
+/// Progress lines are passed to `on_progress`, and other lines of stderr
+/// (including the output of pre-push hook) are passed to `on_output`.
+/// The output of pre-push hook written to stdout is passed after the push,
+/// since git does not redirect it to stderr.
+pub async fn push<F: FnMut(RemoteProgress), G: FnMut(&str)>(
+    repo_path: &Path,
+    options: &PushOptions,
+    mut on_progress: F,
+    mut on_output: G,
+) -> Result<Vec<RefUpdate>, GitError> {
+    let mut args: Vec<&str> = vec!["--progress", "--porcelain"];
+    if options.force_with_lease.is_some_and(|v| v) {
+        args.push("--force-with-lease");
+    }
+    if options.set_upstream.is_some_and(|v| v) {
+        args.push("--set-upstream");
+    }
+    if options.tags.is_some_and(|v| v) {
+        args.push("--tags");
+    }
+    if options.no_verify.is_some_and(|v| v) {
+        args.push("--no-verify");
+    }
+    if let Some(ref remote) = options.remote {
+        args.push(remote);
+        if let Some(ref refspec) = options.refspec {
+            args.push(refspec);
+        }
+    }
+    let output = hook::exec_with_hooks(repo_path, "push", &args, &[], |line| {
+        if let Some(progress) = parse_progress_line(line) {
+            on_progress(progress);
+            true
+        } else {
+            on_output(line);
+            false
+        }
+    })
+    .await?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // the porcelain output starts with "To <url>"
+    stdout
+        .lines()
+        .take_while(|line| !line.starts_with("To "))
+        .for_each(&mut on_output);
+    let updates = parse_push_porcelain_output(&stdout);
+    let rejected: Vec<RefUpdate> = updates
+        .iter()
+        .filter(|u| u.status == RefUpdateStatus::Rejected)
+        .cloned()
+        .collect();
+    if !rejected.is_empty() {
+        return Err(GitError::RefsRejected {
+            command: "push".to_owned(),
+            refs: rejected,
+        });
+    }
+    GitError::assert_process_output("push", &output)?;
+    Ok(updates)
+}
@@ -0,0 +1,211 @@
+use super::{decode_output, exec, operation, GitError};
+use crate::state::repositories::Repository;
+use std::path::{Path, PathBuf};
+use tokio::fs::{create_dir_all, write};
+use types::{
+    InteractiveRebaseOptions, OperationState, RebaseOptions, RebaseTodoAction, RebaseTodoEntry,
+};
+
+pub async fn rebase(
+    repo_path: &Path,
+    options: &RebaseOptions,
+) -> Result<Option<OperationState>, GitError> {
+    let mut args: Vec<&str> = Vec::new();
+    if options.autostash.is_some_and(|v| v) {
+        args.push("--autostash");
+    }
+    if let Some(ref onto) = options.onto {
+        args.push("--onto");
+        args.push(onto);
+    }
+    args.push(&options.upstream);
+    operation::exec_operation(repo_path, "rebase", &args, &[]).await
+}
+
+/**
+ * parse output generated by `git log --reverse --no-merges --format=%H%x00%s`
+ *
+ * {id}<NUL>{summary}
+ */
+fn parse_todo_output(output: &str) -> Result<Vec<RebaseTodoEntry>, GitError> {
+    output
+        .lines()
+        .filter(|line| line.len() > 0)
+        .map(|line| match line.split_once('\0') {
+            Some((id, summary)) => Ok(RebaseTodoEntry {
+                action: RebaseTodoAction::Pick,
+                id: id.to_owned(),
+                summary: summary.to_owned(),
+                message: None,
+            }),
+            None => Err(GitError::UnexpectedOutput {
+                command: String::from("log"),
+                text: line.to_owned(),
+            }),
+        })
+        .collect()
+}
+
+/// Gets the default todo list of `git rebase -i {base}`, the oldest commit first.
+pub async fn get_todo(repo_path: &Path, base: &str) -> Result<Vec<RebaseTodoEntry>, GitError> {
+    let range = format!("{}..HEAD", base);
+    let args = vec![
+        "--reverse",
+        "--no-merges",
+        "--format=%H%x00%s",
+        range.as_str(),
+        "--",
+    ];
+    let output = exec(repo_path, "log", &args, &[]).await?;
+    GitError::assert_process_output("log", &output)?;
+    let stdout = decode_output(&output.stdout);
+    parse_todo_output(&stdout)
+}
+
+fn quote(path: &Path) -> String {
+    format!("'{}'", path.to_str().unwrap().replace('\'', "'\\''"))
+}
+
+/// Formats entries as the content of `git-rebase-todo`.
+///
+/// New messages of `Reword` entries are not passed to the editor, but are applied by amending
+/// the commit with `exec` commands. `message_file` gives the path of the message file of each entry.
+/// Messages of other entries are ignored.
+fn format_todo<F: Fn(usize) -> PathBuf>(
+    entries: &[RebaseTodoEntry],
+    message_file: F,
+) -> Result<String, GitError> {
+    let mut todo = String::new();
+    let mut has_commit = false;
+    for (index, entry) in entries.iter().enumerate() {
+        let action = match entry.action {
+            RebaseTodoAction::Pick => "pick",
+            RebaseTodoAction::Reword => "pick",
+            RebaseTodoAction::Edit => "edit",
+            RebaseTodoAction::Squash => "squash",
+            RebaseTodoAction::Fixup => "fixup",
+            RebaseTodoAction::Drop => "drop",
+        };
+        match entry.action {
+            RebaseTodoAction::Squash | RebaseTodoAction::Fixup if !has_commit => {
+                return Err(GitError::ArgumentError {
+                    command: String::from("rebase"),
+                    message: format!("cannot '{}' without a previous commit", action),
+                });
+            }
+            RebaseTodoAction::Reword if entry.message.is_none() => {
+                return Err(GitError::ArgumentError {
+                    command: String::from("rebase"),
+                    message: format!("no message is given to reword {}", entry.id),
+                });
+            }
+            RebaseTodoAction::Drop => {}
+            _ => has_commit = true,
+        }
+        todo.push_str(&format!("{} {}\n", action, entry.id));
+        if entry.action == RebaseTodoAction::Reword {
+            todo.push_str(&format!(
+                "exec git commit --amend --allow-empty --no-verify -F {}\n",
+                quote(&message_file(index))
+            ));
+        }
+    }
+    Ok(todo)
+}
+
+/// Runs `git rebase -i {base}` with the todo list given by `options.entries`.
+///
+/// The todo list and messages are written to the temp directory of the repository,
+/// and GIT_SEQUENCE_EDITOR is set to copy the list instead of launching an editor.
+/// (`sequence.editor` is not used, since GIT_SEQUENCE_EDITOR inherited by the app takes precedence)
+pub async fn interactive_rebase(
+    repo: &Repository,
+    options: &InteractiveRebaseOptions,
+) -> Result<Option<OperationState>, GitError> {
+    let plan_dir = repo.temp_dir.join("REBASE");
+    create_dir_all(&plan_dir).await?;
+    let message_file = |index: usize| plan_dir.join(format!("MESSAGE_{}", index));
+    let todo = format_todo(&options.entries, message_file)?;
+    for (index, entry) in options.entries.iter().enumerate() {
+        if let (RebaseTodoAction::Reword, Some(message)) = (entry.action, &entry.message) {
+            write(message_file(index), message).await?;
+        }
+    }
+    let todo_file = plan_dir.join("git-rebase-todo");
+    write(&todo_file, todo).await?;
+
+    let sequence_editor = format!("cp {}", quote(&todo_file));
+    let mut args: Vec<&str> = vec!["--interactive"];
+    if options.autostash.is_some_and(|v| v) {
+        args.push("--autostash");
+    }
+    args.push(&options.base);
+    let envs = [("GIT_SEQUENCE_EDITOR", sequence_editor.as_str())];
+    operation::exec_operation(&repo.path, "rebase", &args, &envs).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: RebaseTodoAction, id: &str, message: Option<&str>) -> RebaseTodoEntry {
+        RebaseTodoEntry {
+            action,
+            id: id.to_owned(),
+            summary: String::new(),
+            message: message.map(|v| v.to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_parse_todo_output() {
+        const OUTPUT: &str = "\
+            adf90a114e89d529c23d76ad32dd015271e0324f\0first\n\
+            6eccbc19dea5f2243ce93e48176e578d31533d6c\0second: with colon\n\
+            ";
+        let entries = parse_todo_output(OUTPUT).unwrap();
+        assert_eq!(2, entries.len());
+        assert_eq!(RebaseTodoAction::Pick, entries[0].action);
+        assert_eq!("first", entries[0].summary);
+        assert_eq!("6eccbc19dea5f2243ce93e48176e578d31533d6c", entries[1].id);
+        assert_eq!("second: with colon", entries[1].summary);
+    }
+
+    #[test]
+    fn test_format_todo() {
+        let entries = vec![
+            entry(RebaseTodoAction::Pick, "1111111", Some("ignored")),
+            entry(RebaseTodoAction::Reword, "2222222", Some("new message")),
+            entry(RebaseTodoAction::Squash, "3333333", Some("ignored")),
+            entry(RebaseTodoAction::Drop, "4444444", Some("ignored")),
+            entry(RebaseTodoAction::Edit, "5555555", None),
+        ];
+        let todo = format_todo(&entries, |i| PathBuf::from(format!("/tmp/it's/MSG_{}", i)));
+        assert_eq!(
+            "\
+            pick 1111111\n\
+            pick 2222222\n\
+            exec git commit --amend --allow-empty --no-verify -F '/tmp/it'\\''s/MSG_1'\n\
+            squash 3333333\n\
+            drop 4444444\n\
+            edit 5555555\n\
+            ",
+            todo.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_todo_squash_first() {
+        let entries = vec![
+            entry(RebaseTodoAction::Drop, "1111111", None),
+            entry(RebaseTodoAction::Fixup, "2222222", None),
+        ];
+        assert!(format_todo(&entries, |i| PathBuf::from(format!("{}", i))).is_err());
+    }
+
+    #[test]
+    fn test_format_todo_reword_without_message() {
+        let entries = vec![entry(RebaseTodoAction::Reword, "1111111", None)];
+        assert!(format_todo(&entries, |i| PathBuf::from(format!("{}", i))).is_err());
+    }
+}
@@ -1,28 +1,65 @@
-use super::{exec, merge_heads, rev_parse, GitError};
+use super::{bisect, decode_output, exec, merge_heads, rev_parse, GitError};
+use regex::Regex;
 use std::path::Path;
+use std::sync::LazyLock;
 use types::*;
 
+static UPSTREAM_TRACK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(ahead|behind) (\d+)").unwrap());
+
+/**
+ * parse `%(upstream:track)` and returns (ahead, behind)
+ *
+ * [ahead 1, behind 2]
+ * [ahead 1]
+ * [behind 2]
+ * [gone]
+ */
+fn parse_upstream_track(track: &str) -> (u32, u32) {
+    let mut ahead = 0;
+    let mut behind = 0;
+    for c in UPSTREAM_TRACK_REGEX.captures_iter(track) {
+        let count = c[2].parse::<u32>().unwrap_or(0);
+        if &c[1] == "ahead" {
+            ahead = count;
+        } else {
+            behind = count;
+        }
+    }
+    (ahead, behind)
+}
+
 fn parse_refs_output(output: &str) -> Result<Refs, GitError> {
     let mut refs = Refs {
         head: None,
         merge_heads: Vec::new(),
         refs: Vec::new(),
+        worktrees: Vec::new(),
     };
     for line in output.lines() {
         let tokens: Vec<&str> = line.split('\0').collect();
         match tokens.as_slice() {
-            [sha, head, deref, object_type, fullname] => {
+            [sha, head, deref, object_type, fullname, upstream, track] => {
                 let refname_components: Vec<&str> = fullname.split('/').collect();
                 if refname_components[0] != "refs" {
                     continue;
                 }
                 match refname_components[1] {
                     "heads" => {
+                        let (ahead, behind) = parse_upstream_track(track);
                         let branch = Ref::Branch {
                             fullname: fullname.to_string(),
                             id: sha.to_string(),
                             name: refname_components[2..].join("/").to_string(),
                             current: *head == "*",
+                            upstream: if upstream.len() > 0 {
+                                Some(upstream.to_string())
+                            } else {
+                                None
+                            },
+                            ahead,
+                            behind,
+                            worktree: None,
                         };
                         refs.refs.push(branch);
                         if *head == "*" {
@@ -46,6 +83,9 @@ fn parse_refs_output(output: &str) -> Result<Refs, GitError> {
                             name: refname_components[3..].join("/").to_string(),
                         });
                     }
+                    "bisect" => {
+                        refs.refs.extend(bisect::bisect_ref(fullname, sha));
+                    }
                     _ => {
                         // do nothing
                     }
@@ -68,12 +108,12 @@ pub async fn get_refs(repo_path: &Path) -> Result<Refs, GitError> {
         "--sort",
         "-creatordate",
         "--format",
-        "%(objectname)%00%(HEAD)%00%(*objectname)%00%(objecttype)%00%(refname)",
+        "%(objectname)%00%(HEAD)%00%(*objectname)%00%(objecttype)%00%(refname)%00%(upstream:short)%00%(upstream:track)",
     ];
     let output = exec(repo_path, "for-each-ref", &args, &[]).await?;
     GitError::assert_process_output("for-each-ref", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    let mut refs = parse_refs_output(stdout)?;
+    let stdout = decode_output(&output.stdout);
+    let mut refs = parse_refs_output(&stdout)?;
     if refs.head.is_none() {
         refs.head = rev_parse::rev_parse(repo_path, "HEAD").await?;
     }
@@ -81,6 +121,32 @@ pub async fn get_refs(repo_path: &Path) -> Result<Refs, GitError> {
     Ok(refs)
 }
 
+/// Returns short names of branches, remote branches and tags which contain the commit,
+/// like `git branch --all --contains` and `git tag --contains`.
+///
+/// Symbolic refs (e.g. origin/HEAD) are excluded.
+pub async fn get_refs_containing(
+    repo_path: &Path,
+    commit_id: &str,
+) -> Result<Vec<String>, GitError> {
+    let contains = format!("--contains={}", commit_id);
+    let args = vec![
+        "--format=%(if)%(symref)%(then)%(else)%(refname:short)%(end)",
+        &contains,
+        "refs/heads",
+        "refs/remotes",
+        "refs/tags",
+    ];
+    let output = exec(repo_path, "for-each-ref", &args, &[]).await?;
+    GitError::assert_process_output("for-each-ref", &output)?;
+    let stdout = decode_output(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter(|l| l.len() > 0)
+        .map(|l| l.to_string())
+        .collect())
+}
+
 pub async fn get_reflog(repo_path: &Path, count: u32) -> Result<Vec<(String, String)>, GitError> {
     if count == 0 {
         return Ok(Vec::new());
@@ -89,15 +155,72 @@ pub async fn get_reflog(repo_path: &Path, count: u32) -> Result<Vec<(String, Str
     let args = vec!["--format=%gd %H", "-z", &count_option];
     let output = exec(repo_path, "reflog", &args, &[]).await?;
     GitError::assert_process_output("reflog", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    let stdout = decode_output(&output.stdout);
     let lines = stdout.split('\0').collect::<Vec<_>>();
-    let ret = lines
+    lines
         .iter()
         .filter(|&l| l.len() > 0)
-        .map(|&l| {
-            let tokens = l.split(' ').collect::<Vec<_>>();
-            (tokens[0].to_string(), tokens[1].to_string())
+        .map(|&l| match l.split_once(' ') {
+            Some((selector, id)) => Ok((selector.to_string(), id.to_string())),
+            None => Err(GitError::UnexpectedOutput {
+                command: String::from("reflog"),
+                text: l.to_string(),
+            }),
         })
-        .collect::<Vec<_>>();
-    Ok(ret)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_branch_upstream() {
+        const OUTPUT: &str = "\
+            1111111111111111111111111111111111111111\0*\0\0commit\0refs/heads/main\0origin/main\0[ahead 1, behind 2]\n\
+            2222222222222222222222222222222222222222\0 \0\0commit\0refs/heads/feature/a\0\0\n\
+            3333333333333333333333333333333333333333\0 \0\0commit\0refs/heads/old\0origin/old\0[gone]\n\
+            ";
+        let refs = parse_refs_output(OUTPUT).unwrap();
+        assert_eq!(
+            Some("1111111111111111111111111111111111111111".to_owned()),
+            refs.head
+        );
+        assert_eq!(
+            vec![
+                Ref::Branch {
+                    id: "1111111111111111111111111111111111111111".to_owned(),
+                    fullname: "refs/heads/main".to_owned(),
+                    name: "main".to_owned(),
+                    current: true,
+                    upstream: Some("origin/main".to_owned()),
+                    ahead: 1,
+                    behind: 2,
+                    worktree: None,
+                },
+                Ref::Branch {
+                    id: "2222222222222222222222222222222222222222".to_owned(),
+                    fullname: "refs/heads/feature/a".to_owned(),
+                    name: "feature/a".to_owned(),
+                    current: false,
+                    upstream: None,
+                    ahead: 0,
+                    behind: 0,
+                    worktree: None,
+                },
+                Ref::Branch {
+                    id: "3333333333333333333333333333333333333333".to_owned(),
+                    fullname: "refs/heads/old".to_owned(),
+                    name: "old".to_owned(),
+                    current: false,
+                    upstream: Some("origin/old".to_owned()),
+                    ahead: 0,
+                    behind: 0,
+                    worktree: None,
+                },
+            ],
+            refs.refs
+        );
+        assert_eq!((0, 3), parse_upstream_track("[behind 3]"));
+    }
 }
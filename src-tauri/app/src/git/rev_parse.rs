@@ -1,11 +1,10 @@
-use super::{exec, GitError};
+use super::{decode_output, exec, GitError};
 use std::path::Path;
 
 pub async fn rev_parse(repo_path: &Path, revspec: &str) -> Result<Option<String>, GitError> {
     let ret = exec(repo_path, "rev-parse", &vec![revspec], &[]).await?;
     if ret.status.success() {
-        let sha = std::str::from_utf8(&ret.stdout)
-            .unwrap()
+        let sha = decode_output(&ret.stdout)
             .trim_end_matches('\n')
             .to_string();
         Ok(Some(sha))
@@ -0,0 +1,20 @@
+use super::{operation, GitError};
+use std::path::Path;
+use types::{OperationState, RevertOptions};
+
+pub async fn revert(
+    repo_path: &Path,
+    options: &RevertOptions,
+) -> Result<Option<OperationState>, GitError> {
+    let mainline = options.mainline.map(|v| v.to_string());
+    let mut args: Vec<&str> = vec!["--no-edit"];
+    if let Some(ref mainline) = mainline {
+        args.push("--mainline");
+        args.push(mainline);
+    }
+    if options.no_commit.is_some_and(|v| v) {
+        args.push("--no-commit");
+    }
+    args.extend(options.commit_ids.iter().map(|v| v.as_str()));
+    operation::exec_operation(repo_path, "revert", &args, &[]).await
+}
@@ -0,0 +1,231 @@
+use super::commit_detail::parse_raw_numstat_rows;
+use super::{decode_output, exec, exec_with_stdin, GitError};
+use std::collections::HashSet;
+use std::path::Path;
+use types::{Commit, CreateStashOptions, FileEntry, Stash};
+
+fn stash_name(index: usize) -> String {
+    format!("stash@{{{}}}", index)
+}
+
+/**
+ * parse output generated by `git stash list --format=%gd%x00%H%x00%at%x00%gs`
+ *
+ * stash@{0}<NUL>{id}<NUL>{date}<NUL>On main: message
+ * stash@{1}<NUL>{id}<NUL>{date}<NUL>WIP on main: 1234567 summary
+ */
+pub fn parse_stash_list_output(output: &str) -> Result<Vec<Stash>, GitError> {
+    let mut ret: Vec<Stash> = Vec::new();
+    for line in output.lines().filter(|l| l.len() > 0) {
+        let tokens: Vec<&str> = line.split('\0').collect();
+        let stash = match tokens.as_slice() {
+            [name, id, date, message] => name
+                .strip_prefix("stash@{")
+                .and_then(|v| v.strip_suffix('}'))
+                .and_then(|v| v.parse::<usize>().ok())
+                .zip(date.parse::<u64>().ok())
+                .map(|(index, date)| Stash {
+                    index,
+                    id: id.to_string(),
+                    date: date * 1000,
+                    message: message.to_string(),
+                }),
+            _ => None,
+        };
+        if let Some(stash) = stash {
+            ret.push(stash);
+        } else {
+            return Err(GitError::UnexpectedOutput {
+                command: String::from("stash"),
+                text: line.to_string(),
+            });
+        }
+    }
+    Ok(ret)
+}
+
+pub async fn list(repo_path: &Path) -> Result<Vec<Stash>, GitError> {
+    let args = vec!["list", "--format=%gd%x00%H%x00%at%x00%gs"];
+    let output = exec(repo_path, "stash", &args, &[]).await?;
+    GitError::assert_process_output("stash", &output)?;
+    let stdout = decode_output(&output.stdout);
+    parse_stash_list_output(&stdout)
+}
+
+/**
+ * parse output generated by `git rev-list --no-walk --parents {stash ids}`
+ *
+ * {stash id} {HEAD} {index commit} [{untracked files commit}]
+ */
+fn parse_stash_parents_output(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .flat_map(|line| line.split(' ').skip(2))
+        .filter(|id| !id.is_empty())
+        .map(|id| id.to_string())
+        .collect()
+}
+
+/// Gets ids of the commits which record the index and untracked files of stashes
+/// (`stash^2` and `stash^3`).
+///
+/// They are created by `git stash` internally, so they are hidden from the history.
+pub async fn helper_commits(
+    repo_path: &Path,
+    stashes: &[Stash],
+) -> Result<HashSet<String>, GitError> {
+    if stashes.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let mut args: Vec<&str> = vec!["--no-walk", "--parents"];
+    args.extend(stashes.iter().map(|s| s.id.as_str()));
+    let output = exec(repo_path, "rev-list", &args, &[]).await?;
+    GitError::assert_process_output("rev-list", &output)?;
+    let stdout = decode_output(&output.stdout);
+    Ok(parse_stash_parents_output(&stdout))
+}
+
+/// Removes helper commits of stashes from `commits`, and from parents of stash commits,
+/// so that only the first parent (HEAD at the time of stash) is shown in the graph.
+pub fn hide_helper_commits(commits: &mut Vec<Commit>, helpers: &HashSet<String>) {
+    if helpers.is_empty() {
+        return;
+    }
+    commits.retain(|c| !helpers.contains(&c.id));
+    for commit in commits.iter_mut() {
+        commit.parent_ids.retain(|id| !helpers.contains(id));
+    }
+}
+
+pub async fn create(repo_path: &Path, options: &CreateStashOptions) -> Result<(), GitError> {
+    let mut args: Vec<&str> = vec!["push"];
+    if let Some(ref message) = options.message {
+        if message.len() > 0 {
+            args.push("-m");
+            args.push(message);
+        }
+    }
+    if options.include_untracked.is_some_and(|v| v) {
+        args.push("--include-untracked");
+    }
+    if options.keep_index.is_some_and(|v| v) {
+        args.push("--keep-index");
+    }
+    let output = match options.pathspecs {
+        Some(ref pathspecs) if pathspecs.len() > 0 => {
+            args.push("--pathspec-from-file=-");
+            let stdin_data = pathspecs.join("\n");
+            exec_with_stdin(repo_path, "stash", &args, &[], stdin_data.as_bytes()).await?
+        }
+        _ => exec(repo_path, "stash", &args, &[]).await?,
+    };
+    GitError::assert_process_output("stash", &output)?;
+    Ok(())
+}
+
+async fn apply_or_pop(
+    repo_path: &Path,
+    subcommand: &str,
+    index: usize,
+    restore_index: bool,
+) -> Result<(), GitError> {
+    let name = stash_name(index);
+    let mut args: Vec<&str> = vec![subcommand];
+    if restore_index {
+        args.push("--index");
+    }
+    args.push(&name);
+    let output = exec(repo_path, "stash", &args, &[]).await?;
+    GitError::assert_process_output("stash", &output)?;
+    Ok(())
+}
+
+pub async fn apply(repo_path: &Path, index: usize, restore_index: bool) -> Result<(), GitError> {
+    apply_or_pop(repo_path, "apply", index, restore_index).await
+}
+
+pub async fn pop(repo_path: &Path, index: usize, restore_index: bool) -> Result<(), GitError> {
+    apply_or_pop(repo_path, "pop", index, restore_index).await
+}
+
+pub async fn drop(repo_path: &Path, index: usize) -> Result<(), GitError> {
+    let name = stash_name(index);
+    let args = vec!["drop", name.as_str()];
+    let output = exec(repo_path, "stash", &args, &[]).await?;
+    GitError::assert_process_output("stash", &output)?;
+    Ok(())
+}
+
+pub async fn show(repo_path: &Path, index: usize) -> Result<Vec<FileEntry>, GitError> {
+    let name = stash_name(index);
+    let args = vec![
+        "show",
+        "--raw",
+        "--numstat",
+        "--find-renames",
+        "--include-untracked",
+        "-z",
+        name.as_str(),
+    ];
+    let output = exec(repo_path, "stash", &args, &[]).await?;
+    GitError::assert_process_output("stash", &output)?;
+    let stdout = decode_output(&output.stdout);
+    parse_raw_numstat_rows(&stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stash_list() {
+        const OUTPUT: &str = "\
+            stash@{0}\0adf90a114e89d529c23d76ad32dd015271e0324f\01612789513\0On main: my message\n\
+            stash@{1}\06eccbc19dea5f2243ce93e48176e578d31533d6c\01612789146\0WIP on main: dabb562 init\n\
+            ";
+        let expected = vec![
+            Stash {
+                index: 0,
+                id: "adf90a114e89d529c23d76ad32dd015271e0324f".to_owned(),
+                date: 1612789513000,
+                message: "On main: my message".to_owned(),
+            },
+            Stash {
+                index: 1,
+                id: "6eccbc19dea5f2243ce93e48176e578d31533d6c".to_owned(),
+                date: 1612789146000,
+                message: "WIP on main: dabb562 init".to_owned(),
+            },
+        ];
+        assert_eq!(expected, parse_stash_list_output(OUTPUT).unwrap());
+    }
+
+    #[test]
+    fn test_hide_helper_commits() {
+        const OUTPUT: &str = "\
+            s0 h0 i0 u0\n\
+            s1 h1 i1\n\
+            ";
+        let helpers = parse_stash_parents_output(OUTPUT);
+        let expected: HashSet<String> = ["i0", "u0", "i1"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(expected, helpers);
+
+        let commit = |id: &str, parents: &str| Commit::new(id, parents, "a", "a@a", 0, "");
+        let mut commits = vec![
+            commit("s0", "h0 i0 u0"),
+            commit("u0", ""),
+            commit("i0", "h0"),
+            commit("h0", "c0"),
+            commit("m0", "h0 c0"),
+            commit("c0", ""),
+        ];
+        hide_helper_commits(&mut commits, &helpers);
+        let expected = vec![
+            commit("s0", "h0"),
+            commit("h0", "c0"),
+            commit("m0", "h0 c0"),
+            commit("c0", ""),
+        ];
+        assert_eq!(expected, commits);
+    }
+}
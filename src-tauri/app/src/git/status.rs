@@ -1,80 +1,128 @@
 use super::commit_detail::parse_numstat_tokens;
-use super::{exec, merge_heads, rev_parse, GitError};
+use super::{decode_output, exec, merge_heads, quote_invalid_path, rev_parse, GitError};
 use std::path::Path;
 use types::*;
 
-pub fn parse_status_row(text: &str) -> Result<Vec<WorkingTreeFileEntry>, GitError> {
+/// Splits `<XY>` of a status row into the status codes of the index and the working tree.
+fn split_xy(line: &str) -> Option<(&str, &str)> {
+    let xy = line.get(2..4).filter(|xy| xy.is_ascii())?;
+    Some((&xy[0..1], &xy[1..2]))
+}
+
+/// Parses `<sub>` of a status row, which is `N...` for ordinary files, and `S<c><m><u>`
+/// for submodules. (`C`: commit changed, `M`: tracked changes, `U`: untracked files)
+fn parse_submodule_state(sub: &str) -> Option<SubmoduleChange> {
+    let flags = sub.strip_prefix('S')?.as_bytes();
+    match flags {
+        [c, m, u] => Some(SubmoduleChange {
+            commit_changed: *c == b'C',
+            modified: *m == b'M',
+            untracked: *u == b'U',
+        }),
+        _ => None,
+    }
+}
+
+/// Gets the path of a status row, which is the `n`th field separated by spaces.
+///
+/// Returns the path decoded lossily, and the raw bytes quoted if it is not valid UTF-8.
+fn nth_path(line: &[u8], n: usize) -> Option<(String, Option<String>)> {
+    let raw = line.splitn(n + 1, |b| *b == b' ').nth(n)?;
+    Some((decode_output(raw).into_owned(), quote_invalid_path(raw)))
+}
+
+/// Pushes entries of the index and the working tree of a status row.
+fn push_ordinal_entries(
+    ret: &mut Vec<WorkingTreeFileEntry>,
+    line: &str,
+    path: &str,
+    quoted_path: Option<&str>,
+    old_path: Option<&str>,
+) -> Result<(), GitError> {
+    let unexpected = || GitError::UnexpectedOutput {
+        command: "status".to_owned(),
+        text: format!("unexpected output: {}", line),
+    };
+    let (x, y) = split_xy(line).ok_or_else(unexpected)?;
+    let sub = line.split(' ').nth(2).ok_or_else(unexpected)?;
+    for (status_code, unstaged) in [(x, false), (y, true)] {
+        match (status_code, old_path) {
+            ("M" | "T" | "A" | "D", _) => {
+                let mut entry = WorkingTreeFileEntry::ordinal(path, status_code, unstaged)
+                    .with_quoted_path(quoted_path.map(str::to_owned));
+                if unstaged && status_code == "M" {
+                    // changes of the working tree of submodules
+                    entry.submodule = parse_submodule_state(sub);
+                }
+                ret.push(entry);
+            }
+            ("R" | "C", Some(old_path)) => ret.push(
+                WorkingTreeFileEntry::renamed_or_copied(path, status_code, old_path, unstaged)
+                    .with_quoted_path(quoted_path.map(str::to_owned)),
+            ),
+            (".", _) => {
+                // unchanged
+            }
+            _ => {
+                return Err(GitError::UnexpectedOutput {
+                    command: "status".to_owned(),
+                    text: format!("unexpected status code: {}, {}", status_code, line),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses the output of `git status --porcelain=v2 -z`.
+///
+/// The output is given as bytes, since paths may not be valid UTF-8.
+pub fn parse_status_row(output: &[u8]) -> Result<Vec<WorkingTreeFileEntry>, GitError> {
     let mut ret: Vec<WorkingTreeFileEntry> = Vec::new();
-    let mut lines: Vec<&str> = text.split("\0").collect();
-    lines.reverse();
-    while !lines.is_empty() {
-        let line = lines.pop().unwrap();
-        if line.len() == 0 {
+    let mut raw_lines: Vec<&[u8]> = output.split(|b| *b == b'\0').collect();
+    raw_lines.reverse();
+    while let Some(raw_line) = raw_lines.pop() {
+        if raw_line.len() == 0 {
             break;
         }
-        let mode = &line[0..1];
+        let line = &*decode_output(raw_line);
+        let unexpected = || GitError::UnexpectedOutput {
+            command: "status".to_owned(),
+            text: format!("unexpected output: {}", line),
+        };
+        let mode = line.get(0..1).ok_or_else(unexpected)?;
         match mode {
             "1" => {
                 // Ordinary changed entries
                 // 1 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <path>
-                let path = line.splitn(9, " ").nth(8).unwrap();
-                for (status_code, unstaged) in [(&line[2..3], false), (&line[3..4], true)] {
-                    match status_code {
-                        "M" | "T" | "A" | "D" => {
-                            ret.push(WorkingTreeFileEntry::ordinal(path, status_code, unstaged))
-                        }
-                        "." => {
-                            // unchanged
-                        }
-                        _ => {
-                            return Err(GitError::UnexpectedOutput {
-                                command: "status".to_owned(),
-                                text: format!("unexpected status code: {}, {}", status_code, line),
-                            });
-                        }
-                    }
-                }
+                let (path, quoted_path) = nth_path(raw_line, 8).ok_or_else(unexpected)?;
+                push_ordinal_entries(&mut ret, line, &path, quoted_path.as_deref(), None)?;
             }
             "2" => {
                 // Renamed or copied entries
                 // 2 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <X><score> <path><sep><origPath>
-                let path = line.splitn(10, " ").nth(9).unwrap();
-                let old_path = lines.pop().unwrap();
-                for (status_code, unstaged) in [(&line[2..3], false), (&line[3..4], true)] {
-                    match status_code {
-                        "M" | "T" | "A" | "D" => {
-                            ret.push(WorkingTreeFileEntry::ordinal(path, status_code, unstaged))
-                        }
-                        "R" | "C" => ret.push(WorkingTreeFileEntry::renamed_or_copied(
-                            path,
-                            status_code,
-                            old_path,
-                            unstaged,
-                        )),
-                        "." => {
-                            // unchanged
-                        }
-                        _ => {
-                            return Err(GitError::UnexpectedOutput {
-                                command: "status".to_owned(),
-                                text: format!("unexpected status code: {}, {}", status_code, line),
-                            });
-                        }
-                    }
-                }
+                let (path, quoted_path) = nth_path(raw_line, 9).ok_or_else(unexpected)?;
+                let old_path = decode_output(raw_lines.pop().ok_or_else(unexpected)?);
+                push_ordinal_entries(
+                    &mut ret,
+                    line,
+                    &path,
+                    quoted_path.as_deref(),
+                    Some(&old_path),
+                )?;
             }
             "u" => {
                 // Unmerged entries
                 // u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>
-                let xy = &line[2..4];
-                let path = line.splitn(11, " ").nth(10).unwrap();
-                ret.push(WorkingTreeFileEntry::unmerged(path, xy));
+                let xy = line.get(2..4).ok_or_else(unexpected)?;
+                let (path, quoted_path) = nth_path(raw_line, 10).ok_or_else(unexpected)?;
+                ret.push(WorkingTreeFileEntry::unmerged(&path, xy).with_quoted_path(quoted_path));
             }
             "?" => {
                 // Untracked entries
                 // ? <path>
-                let path = &line[2..];
-                ret.push(WorkingTreeFileEntry::untracked(path))
+                let (path, quoted_path) = nth_path(raw_line, 1).ok_or_else(unexpected)?;
+                ret.push(WorkingTreeFileEntry::untracked(&path).with_quoted_path(quoted_path))
             }
             _ => {
                 return Err(GitError::UnexpectedOutput {
@@ -91,8 +139,7 @@ pub async fn status(repo_path: &Path) -> Result<Vec<WorkingTreeFileEntry>, GitEr
     let args = vec!["--porcelain=v2", "--find-renames", "--untracked=all", "-z"];
     let output = exec(repo_path, "status", &args, &[]).await?;
     GitError::assert_process_output("status", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
-    parse_status_row(stdout)
+    parse_status_row(&output.stdout)
 }
 
 pub async fn get_numstat(
@@ -105,7 +152,7 @@ pub async fn get_numstat(
     }
     let output = exec(repo_path, "diff", &args, &[]).await?;
     GitError::assert_process_output("diff", &output)?;
-    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    let stdout = decode_output(&output.stdout);
     let tokens = stdout
         .split("\0")
         .filter(|v| v.len() > 0)
@@ -149,7 +196,28 @@ mod tests {
             WorkingTreeFileEntry::ordinal("src/removed unstaged.rs", "D", true),
         ];
 
-        let actuals = parse_status_row(STATUS_OUTPUT).unwrap();
+        let actuals = parse_status_row(STATUS_OUTPUT.as_bytes()).unwrap();
+        assert_eq!(expecteds, actuals);
+    }
+
+    #[test]
+    fn test_parse_submodule() {
+        const STATUS_OUTPUT: &str = "\
+            1 .M SC.U 160000 160000 160000 xxxxxxx xxxxxxx lib/sub\0\
+            1 M. S... 160000 160000 160000 xxxxxxx xxxxxxx lib/staged\0\
+            ";
+        let mut modified = WorkingTreeFileEntry::ordinal("lib/sub", "M", true);
+        modified.submodule = Some(SubmoduleChange {
+            commit_changed: true,
+            modified: false,
+            untracked: true,
+        });
+        let expecteds = vec![
+            modified,
+            WorkingTreeFileEntry::ordinal("lib/staged", "M", false),
+        ];
+
+        let actuals = parse_status_row(STATUS_OUTPUT.as_bytes()).unwrap();
         assert_eq!(expecteds, actuals);
     }
 
@@ -182,7 +250,7 @@ mod tests {
             WorkingTreeFileEntry::ordinal("src/modified.rs", "M", true),
         ];
 
-        let actuals = parse_status_row(STATUS_OUTPUT).unwrap();
+        let actuals = parse_status_row(STATUS_OUTPUT.as_bytes()).unwrap();
         assert_eq!(expecteds, actuals);
     }
 
@@ -207,7 +275,7 @@ mod tests {
             WorkingTreeFileEntry::unmerged("src/delete by them.rs", "UD"),
         ];
 
-        let actuals = parse_status_row(STATUS_OUTPUT).unwrap();
+        let actuals = parse_status_row(STATUS_OUTPUT.as_bytes()).unwrap();
         assert_eq!(expecteds, actuals);
     }
 
@@ -218,6 +286,32 @@ mod tests {
             ";
         let expecteds = vec![WorkingTreeFileEntry::untracked("src/untracked file.rs")];
 
+        let actuals = parse_status_row(STATUS_OUTPUT.as_bytes()).unwrap();
+        assert_eq!(expecteds, actuals);
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert!(parse_status_row(b"1 M\0").is_err());
+        assert!(parse_status_row(b"2 R. N... 100644 100644 100644 x x R100 src/a.rs").is_err());
+        assert!(
+            parse_status_row("1 \u{3042} N... 100644 100644 100644 x x a.rs\0".as_bytes()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_utf8() {
+        const STATUS_OUTPUT: &[u8] = b"\
+            1 .M N... 100644 100644 100644 xxxxxxx xxxxxxx src/\x82\xa0 file.rs\0\
+            ? \xef\xbf\xbd.txt\0\
+            ";
+        let expecteds = vec![
+            WorkingTreeFileEntry::ordinal("src/\u{FFFD}\u{FFFD} file.rs", "M", true)
+                .with_quoted_path(Some(r#""src/\202\240 file.rs""#.to_owned())),
+            // valid UTF-8 which contains U+FFFD
+            WorkingTreeFileEntry::untracked("\u{FFFD}.txt"),
+        ];
+
         let actuals = parse_status_row(STATUS_OUTPUT).unwrap();
         assert_eq!(expecteds, actuals);
     }
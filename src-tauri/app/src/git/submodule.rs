@@ -0,0 +1,216 @@
+use super::fetch::exec_with_progress;
+use super::{decode_output, exec, rev_parse, status, GitError};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use types::{RemoteProgress, Submodule, UpdateSubmoduleOptions};
+
+/**
+ * parse output generated by `git ls-files --stage -z`, and returns gitlinks
+ *
+ * {mode} {id} {stage}<TAB>{path}<NUL>
+ */
+fn parse_gitlinks(output: &str) -> Result<Vec<(String, String)>, GitError> {
+    let mut ret: Vec<(String, String)> = Vec::new();
+    for entry in output.split('\0').filter(|e| e.len() > 0) {
+        let unexpected = || GitError::UnexpectedOutput {
+            command: String::from("ls-files"),
+            text: entry.to_string(),
+        };
+        let (info, path) = entry.split_once('\t').ok_or_else(unexpected)?;
+        let tokens: Vec<&str> = info.split(' ').collect();
+        match tokens.as_slice() {
+            ["160000", id, _] => {
+                // unmerged gitlinks appear for each stage
+                if !matches!(ret.last(), Some((p, _)) if p == path) {
+                    ret.push((path.to_string(), id.to_string()));
+                }
+            }
+            [_, _, _] => {}
+            _ => return Err(unexpected()),
+        }
+    }
+    Ok(ret)
+}
+
+/**
+ * parse output generated by `git config -z --get-regexp ^submodule\.`
+ *
+ * submodule.{name}.{key}<LF>{value}<NUL>
+ *
+ * Returns values by name and key. (name may contain dots)
+ */
+fn parse_submodule_config(output: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut ret: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for entry in output.split('\0').filter(|e| e.len() > 0) {
+        let (key, value) = entry.split_once('\n').unwrap_or((entry, ""));
+        let Some((name, key)) = key
+            .strip_prefix("submodule.")
+            .and_then(|k| k.rsplit_once('.'))
+        else {
+            continue;
+        };
+        ret.entry(name.to_string())
+            .or_default()
+            .insert(key.to_string(), value.to_string());
+    }
+    ret
+}
+
+async fn get_submodule_config(
+    repo_path: &Path,
+    file: Option<&str>,
+) -> Result<HashMap<String, HashMap<String, String>>, GitError> {
+    let mut args = vec!["-z"];
+    if let Some(file) = file {
+        args.push("--file");
+        args.push(file);
+    }
+    args.push("--get-regexp");
+    args.push(r"^submodule\.");
+    let output = exec(repo_path, "config", &args, &[]).await?;
+    // exit code is 1 if nothing is found
+    if output.status.code() == Some(1) {
+        return Ok(HashMap::new());
+    }
+    GitError::assert_process_output("config", &output)?;
+    Ok(parse_submodule_config(&decode_output(&output.stdout)))
+}
+
+/// Lists submodules with the commit recorded in the index and the commit checked out.
+pub async fn list(repo_path: &Path) -> Result<Vec<Submodule>, GitError> {
+    let output = exec(repo_path, "ls-files", &["--stage", "-z"], &[]).await?;
+    GitError::assert_process_output("ls-files", &output)?;
+    let gitlinks = parse_gitlinks(&decode_output(&output.stdout))?;
+    if gitlinks.is_empty() {
+        return Ok(Vec::new());
+    }
+    let gitmodules = if repo_path.join(".gitmodules").exists() {
+        get_submodule_config(repo_path, Some(".gitmodules")).await?
+    } else {
+        HashMap::new()
+    };
+    // submodules are initialized by copying url to .git/config
+    let initialized = get_submodule_config(repo_path, None)
+        .await?
+        .into_iter()
+        .filter(|(_, values)| values.contains_key("url"))
+        .map(|(name, _)| name)
+        .collect::<HashSet<_>>();
+    let mut changes = status::status(repo_path)
+        .await?
+        .into_iter()
+        .filter_map(|e| e.submodule.map(|s| (e.path, s)))
+        .collect::<HashMap<_, _>>();
+
+    let mut ret: Vec<Submodule> = Vec::new();
+    for (path, recorded_id) in gitlinks {
+        let (name, url) = gitmodules
+            .iter()
+            .find(|(_, values)| values.get("path") == Some(&path))
+            .map(|(name, values)| (name.clone(), values.get("url").cloned()))
+            .unwrap_or_else(|| (path.clone(), None));
+        let checked_out_id = if repo_path.join(&path).join(".git").exists() {
+            rev_parse::rev_parse(&repo_path.join(&path), "HEAD").await?
+        } else {
+            None
+        };
+        ret.push(Submodule {
+            initialized: initialized.contains(&name),
+            change: changes.remove(&path),
+            name,
+            path,
+            url,
+            recorded_id,
+            checked_out_id,
+        });
+    }
+    Ok(ret)
+}
+
+fn push_paths<'a>(args: &mut Vec<&'a str>, paths: &'a [String]) {
+    args.push("--");
+    args.extend(paths.iter().map(|p| p.as_str()));
+}
+
+/// Copies urls of submodules from `.gitmodules` to `.git/config`.
+pub async fn init(repo_path: &Path, paths: &[String]) -> Result<(), GitError> {
+    let mut args = vec!["init"];
+    push_paths(&mut args, paths);
+    let output = exec(repo_path, "submodule", &args, &[]).await?;
+    GitError::assert_process_output("submodule", &output)?;
+    Ok(())
+}
+
+/// Clones missing submodules and checks out the recorded commits.
+pub async fn update<F: FnMut(RemoteProgress)>(
+    repo_path: &Path,
+    options: &UpdateSubmoduleOptions,
+    on_progress: F,
+) -> Result<(), GitError> {
+    let mut args = vec!["update", "--progress"];
+    if options.init.is_some_and(|v| v) {
+        args.push("--init");
+    }
+    if options.recursive.is_some_and(|v| v) {
+        args.push("--recursive");
+    }
+    if options.remote.is_some_and(|v| v) {
+        args.push("--remote");
+    }
+    if options.force.is_some_and(|v| v) {
+        args.push("--force");
+    }
+    let paths = options.paths.as_deref().unwrap_or_default();
+    push_paths(&mut args, paths);
+    exec_with_progress(repo_path, "submodule", &args, on_progress).await?;
+    Ok(())
+}
+
+/// Updates urls of submodules in `.git/config` to the ones in `.gitmodules`.
+pub async fn sync(repo_path: &Path, paths: &[String], recursive: bool) -> Result<(), GitError> {
+    let mut args = vec!["sync"];
+    if recursive {
+        args.push("--recursive");
+    }
+    push_paths(&mut args, paths);
+    let output = exec(repo_path, "submodule", &args, &[]).await?;
+    GitError::assert_process_output("submodule", &output)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gitlinks() {
+        const OUTPUT: &str = "\
+            100644 1111 0\t.gitmodules\0\
+            160000 2222 0\tlib/sub\0\
+            160000 3333 1\tlib/conflict\0\
+            160000 4444 2\tlib/conflict\0\
+            160000 5555 3\tlib/conflict\0";
+        assert_eq!(
+            vec![
+                ("lib/sub".to_owned(), "2222".to_owned()),
+                ("lib/conflict".to_owned(), "3333".to_owned()),
+            ],
+            parse_gitlinks(OUTPUT).unwrap()
+        );
+        assert!(parse_gitlinks("160000 2222\0").is_err());
+    }
+
+    #[test]
+    fn test_parse_submodule_config() {
+        const OUTPUT: &str = "\
+            submodule.lib/sub.path\nlib/sub\0\
+            submodule.lib/sub.url\nhttps://example.com/sub.git\0\
+            submodule.v1.0.path\nv1\0";
+        let config = parse_submodule_config(OUTPUT);
+        assert_eq!(
+            Some("https://example.com/sub.git"),
+            config["lib/sub"].get("url").map(|v| v.as_str())
+        );
+        assert_eq!(Some("v1"), config["v1.0"].get("path").map(|v| v.as_str()));
+    }
+}
@@ -0,0 +1,160 @@
+use super::rev_parse::rev_parse;
+use super::{decode_output, exec, push, GitError};
+use std::path::Path;
+use types::{CreateTagOptions, DeleteTagOptions, PushOptions, TagDetail};
+
+const TAG_FORMAT: &str = "\
+    %(objecttype)%00\
+    %(objectname)%00\
+    %(*objectname)%00\
+    %(taggername)%00\
+    %(taggeremail:trim)%00\
+    %(taggerdate:unix)%00\
+    %(contents:subject)%00\
+    %(contents:body)%00\
+    %(contents:signature)";
+
+async fn push_refspec(repo_path: &Path, remote: &str, refspec: String) -> Result<(), GitError> {
+    let options = PushOptions {
+        remote: Some(remote.to_owned()),
+        refspec: Some(refspec),
+        force_with_lease: None,
+        set_upstream: None,
+        tags: None,
+        no_verify: None,
+    };
+    push::push(repo_path, &options, |_| {}, |_| {}).await?;
+    Ok(())
+}
+
+pub async fn create_tag(repo_path: &Path, options: &CreateTagOptions) -> Result<(), GitError> {
+    let mut args: Vec<&str> = Vec::new();
+    if options.force.is_some_and(|v| v) {
+        args.push("--force");
+    }
+    if options.sign.is_some_and(|v| v) {
+        args.push("--sign");
+    }
+    if options.message.is_some() || options.sign.is_some_and(|v| v) {
+        // annotated tag
+        args.push("--annotate");
+        args.push("--message");
+        args.push(options.message.as_deref().unwrap_or(""));
+    }
+    args.push("--");
+    args.push(&options.tag_name);
+    args.push(&options.commit_id);
+    let output = exec(repo_path, "tag", &args, &[]).await?;
+    GitError::assert_process_output("tag", &output)?;
+    if let Some(ref remote) = options.push_to {
+        let force = if options.force.is_some_and(|v| v) {
+            "+"
+        } else {
+            ""
+        };
+        let refspec = format!("{}refs/tags/{}", force, options.tag_name);
+        push_refspec(repo_path, remote, refspec).await?;
+    }
+    Ok(())
+}
+
+/// Deletes a tag, and also deletes it from the remote if `remote` is given.
+///
+/// With `remote`, the local tag is deleted only if it exists, so that a tag which exists
+/// only on the remote can be deleted.
+pub async fn delete_tag(repo_path: &Path, options: &DeleteTagOptions) -> Result<(), GitError> {
+    let refname = format!("refs/tags/{}", options.tag_name);
+    let exists_locally = rev_parse(repo_path, &refname).await?.is_some();
+    if exists_locally || options.remote.is_none() {
+        let args = vec!["--delete", "--", options.tag_name.as_str()];
+        let output = exec(repo_path, "tag", &args, &[]).await?;
+        GitError::assert_process_output("tag", &output)?;
+    }
+    if let Some(ref remote) = options.remote {
+        let refspec = format!(":refs/tags/{}", options.tag_name);
+        push_refspec(repo_path, remote, refspec).await?;
+    }
+    Ok(())
+}
+
+/**
+ * parse output generated by `git for-each-ref --format={TAG_FORMAT} refs/tags/{name}`
+ *
+ * {type}<NUL>{tag sha}<NUL>{target id}<NUL>{tagger}<NUL>{mail}<NUL>{date}<NUL>
+ * {subject}<NUL>{body}<NUL>{signature}
+ *
+ * Returns None for a lightweight tag.
+ */
+fn parse_tag_output(name: &str, output: &str) -> Result<Option<TagDetail>, GitError> {
+    let tokens: Vec<&str> = output.split('\0').collect();
+    match tokens.as_slice() {
+        ["tag", tag_sha, target_id, tagger, mail, date, subject, body, signature] => {
+            let body = body.trim_end();
+            let message = if body.len() > 0 {
+                format!("{}\n\n{}", subject, body)
+            } else {
+                subject.to_string()
+            };
+            Ok(Some(TagDetail {
+                name: name.to_owned(),
+                tag_sha: tag_sha.to_string(),
+                target_id: target_id.to_string(),
+                tagger: tagger.to_string(),
+                mail_address: mail.to_string(),
+                date: date.parse::<u64>().unwrap_or(0) * 1000,
+                message,
+                signed: signature.trim().len() > 0,
+            }))
+        }
+        [_, _, _, _, _, _, _, _, _] => Ok(None),
+        _ => Err(GitError::UnexpectedOutput {
+            command: String::from("for-each-ref"),
+            text: output.to_owned(),
+        }),
+    }
+}
+
+pub async fn get_tag_detail(repo_path: &Path, name: &str) -> Result<Option<TagDetail>, GitError> {
+    let format = format!("--format={}", TAG_FORMAT);
+    let refname = format!("refs/tags/{}", name);
+    let args = vec![format.as_str(), refname.as_str()];
+    let output = exec(repo_path, "for-each-ref", &args, &[]).await?;
+    GitError::assert_process_output("for-each-ref", &output)?;
+    let stdout = decode_output(&output.stdout);
+    if stdout.len() == 0 {
+        return Err(GitError::ArgumentError {
+            command: String::from("for-each-ref"),
+            message: format!("tag {} does not exist", name),
+        });
+    }
+    parse_tag_output(name, &stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_annotated_tag() {
+        const OUTPUT: &str = "tag\00f5113161cccc3fa2ffea8c886cf2ace46ba91f5\0\
+            3d38177fbc3da0acc1942882dffdd50782ebfdd9\0Taro\0taro@example.com\01612789513\0\
+            subject line\0body text\n\0\n";
+        let expected = TagDetail {
+            name: "v1.0".to_owned(),
+            tag_sha: "0f5113161cccc3fa2ffea8c886cf2ace46ba91f5".to_owned(),
+            target_id: "3d38177fbc3da0acc1942882dffdd50782ebfdd9".to_owned(),
+            tagger: "Taro".to_owned(),
+            mail_address: "taro@example.com".to_owned(),
+            date: 1612789513000,
+            message: "subject line\n\nbody text".to_owned(),
+            signed: false,
+        };
+        assert_eq!(Some(expected), parse_tag_output("v1.0", OUTPUT).unwrap());
+    }
+
+    #[test]
+    fn test_parse_lightweight_tag() {
+        const OUTPUT: &str = "commit\03d38177fbc3da0acc1942882dffdd50782ebfdd9\0\0\0\0\0init\0\0\n";
+        assert_eq!(None, parse_tag_output("v1.0", OUTPUT).unwrap());
+    }
+}
@@ -1,7 +1,8 @@
 use regex::Regex;
 
-use super::{exec, exec_with_stdin, rev_parse, GitError};
+use super::{decode_output, diff, exec, exec_with_stdin, patch, rev_parse, GitError};
 use std::path::Path;
+use types::{FileDiff, HunkSelection};
 
 pub async fn stage(repo_path: &Path, rel_paths: &[&str]) -> Result<(), GitError> {
     let args = vec!["--pathspec-from-file=-"];
@@ -34,6 +35,65 @@ pub async fn restore(repo_path: &Path, rel_paths: &[&str]) -> Result<(), GitErro
     Ok(())
 }
 
+pub async fn get_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    cached: bool,
+) -> Result<FileDiff, GitError> {
+    let udiff = diff::get_workingtree_patch(repo_path, rel_path, cached).await?;
+    patch::parse_file_diff(rel_path, &udiff)
+}
+
+async fn apply_selected_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    selections: &[HunkSelection],
+    cached: bool,
+    to_index: bool,
+    reverse: bool,
+) -> Result<(), GitError> {
+    let udiff = diff::get_workingtree_patch(repo_path, rel_path, cached).await?;
+    let patch = patch::build_partial_patch(&udiff, selections, reverse)?;
+    let mut args = vec!["--recount", "--whitespace=nowarn"];
+    if to_index {
+        args.push("--cached");
+    }
+    if reverse {
+        args.push("--reverse");
+    }
+    args.push("-");
+    let output = exec_with_stdin(repo_path, "apply", &args, &[], &patch).await?;
+    GitError::assert_process_output("apply", &output)?;
+    Ok(())
+}
+
+pub async fn stage_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    selections: &[HunkSelection],
+) -> Result<(), GitError> {
+    // apply selected part of unstaged diff to the index
+    apply_selected_hunks(repo_path, rel_path, selections, false, true, false).await
+}
+
+pub async fn unstage_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    selections: &[HunkSelection],
+) -> Result<(), GitError> {
+    // revert selected part of staged diff from the index
+    apply_selected_hunks(repo_path, rel_path, selections, true, true, true).await
+}
+
+pub async fn restore_hunks(
+    repo_path: &Path,
+    rel_path: &str,
+    selections: &[HunkSelection],
+) -> Result<(), GitError> {
+    // revert selected part of unstaged diff from the working tree
+    apply_selected_hunks(repo_path, rel_path, selections, false, false, true).await
+}
+
 struct Index {
     pub mode: u32,
     pub hash: String,
@@ -73,9 +133,9 @@ pub async fn update_index(
     )
     .await?;
     GitError::assert_process_output("hash-object", &output)?;
-    let hash = std::str::from_utf8(&output.stdout)
-        .unwrap()
-        .trim_end_matches('\n');
+    let hash = decode_output(&output.stdout)
+        .trim_end_matches('\n')
+        .to_owned();
 
     let output = exec(
         repo_path,
@@ -86,12 +146,12 @@ pub async fn update_index(
     .await?;
     GitError::assert_process_output("ls-files", &output)?;
 
-    let index_info = std::str::from_utf8(&output.stdout).unwrap();
-    let mut idx = parse_index_info(index_info).ok_or_else(|| GitError::UnexpectedOutput {
+    let index_info = decode_output(&output.stdout);
+    let mut idx = parse_index_info(&index_info).ok_or_else(|| GitError::UnexpectedOutput {
         command: "ls-files".to_owned(),
-        text: index_info.to_owned(),
+        text: index_info.into_owned(),
     })?;
-    idx.hash = hash.to_owned();
+    idx.hash = hash;
     let new_index_info = idx.format_z();
     let output = exec_with_stdin(
         repo_path,
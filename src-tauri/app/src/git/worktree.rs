@@ -0,0 +1,235 @@
+use super::{decode_output, exec, GitError};
+use std::path::{Component, Path, PathBuf};
+use types::{AddWorktreeOptions, Worktree};
+
+/// Removes `.` and `..` components without accessing the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut ret = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                ret.pop();
+            }
+            _ => ret.push(c),
+        }
+    }
+    ret
+}
+
+/// Resolves the git dir of the working tree at `repo_path`.
+///
+/// `.git` is a directory in the main working tree, but it is a file which contains
+/// `gitdir: <path>` in linked worktrees (and submodules).
+pub fn resolve_git_dir(repo_path: &Path) -> PathBuf {
+    let dot_git = repo_path.join(".git");
+    if dot_git.is_file() {
+        if let Ok(content) = std::fs::read_to_string(&dot_git) {
+            if let Some(path) = content.trim_end().strip_prefix("gitdir:") {
+                return normalize(&repo_path.join(path.trim()));
+            }
+        }
+    }
+    dot_git
+}
+
+/// Resolves the common dir, which contains refs, objects and config shared by all worktrees.
+///
+/// It is the same as the git dir except for linked worktrees.
+pub fn resolve_common_dir(git_dir: &Path) -> PathBuf {
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(content) => normalize(&git_dir.join(content.trim_end())),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+fn is_same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/**
+ * parse output generated by `git worktree list --porcelain -z`
+ *
+ * worktree /path/to/main<NUL>HEAD {id}<NUL>branch refs/heads/main<NUL><NUL>
+ * worktree /path/to/linked<NUL>HEAD {id}<NUL>detached<NUL>locked {reason}<NUL><NUL>
+ * worktree /path/to/bare<NUL>bare<NUL><NUL>
+ *
+ * The first one is the main worktree.
+ */
+pub fn parse_worktree_list_output(output: &str) -> Result<Vec<Worktree>, GitError> {
+    let mut ret: Vec<Worktree> = Vec::new();
+    for record in output.split("\0\0").filter(|r| r.len() > 0) {
+        let mut worktree = Worktree {
+            path: String::new(),
+            head: None,
+            branch: None,
+            main: ret.is_empty(),
+            current: false,
+            bare: false,
+            detached: false,
+            locked: false,
+            lock_reason: None,
+            prunable: false,
+            prunable_reason: None,
+        };
+        for line in record.split('\0') {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "worktree" => worktree.path = value.to_string(),
+                "HEAD" => worktree.head = Some(value.to_string()),
+                "branch" => worktree.branch = Some(value.to_string()),
+                "bare" => worktree.bare = true,
+                "detached" => worktree.detached = true,
+                "locked" => {
+                    worktree.locked = true;
+                    worktree.lock_reason = Some(value.to_string()).filter(|v| v.len() > 0);
+                }
+                "prunable" => {
+                    worktree.prunable = true;
+                    worktree.prunable_reason = Some(value.to_string()).filter(|v| v.len() > 0);
+                }
+                _ => {}
+            }
+        }
+        if worktree.path.len() == 0 {
+            return Err(GitError::UnexpectedOutput {
+                command: String::from("worktree"),
+                text: record.replace('\0', "\n"),
+            });
+        }
+        ret.push(worktree);
+    }
+    Ok(ret)
+}
+
+pub async fn list(repo_path: &Path) -> Result<Vec<Worktree>, GitError> {
+    let args = vec!["list", "--porcelain", "-z"];
+    let output = exec(repo_path, "worktree", &args, &[]).await?;
+    GitError::assert_process_output("worktree", &output)?;
+    let stdout = decode_output(&output.stdout);
+    let mut worktrees = parse_worktree_list_output(&stdout)?;
+    for worktree in worktrees.iter_mut() {
+        worktree.current = is_same_path(Path::new(&worktree.path), repo_path);
+    }
+    Ok(worktrees)
+}
+
+pub async fn add(repo_path: &Path, options: &AddWorktreeOptions) -> Result<(), GitError> {
+    let mut args: Vec<&str> = vec!["add"];
+    if options.force.is_some_and(|v| v) {
+        args.push("--force");
+    }
+    if options.detach.is_some_and(|v| v) {
+        args.push("--detach");
+    }
+    if let Some(ref new_branch) = options.new_branch {
+        args.push("-b");
+        args.push(new_branch);
+    }
+    if let Some(ref reason) = options.lock_reason {
+        args.push("--lock");
+        if reason.len() > 0 {
+            args.push("--reason");
+            args.push(reason);
+        }
+    }
+    args.push("--");
+    args.push(&options.path);
+    if let Some(ref commitish) = options.commitish {
+        args.push(commitish);
+    }
+    let output = exec(repo_path, "worktree", &args, &[]).await?;
+    GitError::assert_process_output("worktree", &output)?;
+    Ok(())
+}
+
+pub async fn remove(repo_path: &Path, worktree_path: &str, force: bool) -> Result<(), GitError> {
+    let mut args: Vec<&str> = vec!["remove"];
+    if force {
+        // twice to remove a locked worktree
+        args.push("--force");
+        args.push("--force");
+    }
+    args.push("--");
+    args.push(worktree_path);
+    let output = exec(repo_path, "worktree", &args, &[]).await?;
+    GitError::assert_process_output("worktree", &output)?;
+    Ok(())
+}
+
+pub async fn prune(repo_path: &Path) -> Result<(), GitError> {
+    let output = exec(repo_path, "worktree", &["prune"], &[]).await?;
+    GitError::assert_process_output("worktree", &output)?;
+    Ok(())
+}
+
+pub async fn lock(
+    repo_path: &Path,
+    worktree_path: &str,
+    reason: Option<&str>,
+) -> Result<(), GitError> {
+    let mut args: Vec<&str> = vec!["lock"];
+    if let Some(reason) = reason.filter(|r| r.len() > 0) {
+        args.push("--reason");
+        args.push(reason);
+    }
+    args.push("--");
+    args.push(worktree_path);
+    let output = exec(repo_path, "worktree", &args, &[]).await?;
+    GitError::assert_process_output("worktree", &output)?;
+    Ok(())
+}
+
+pub async fn unlock(repo_path: &Path, worktree_path: &str) -> Result<(), GitError> {
+    let args = vec!["unlock", "--", worktree_path];
+    let output = exec(repo_path, "worktree", &args, &[]).await?;
+    GitError::assert_process_output("worktree", &output)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_worktree_list() {
+        const OUTPUT: &str = "\
+            worktree /repo/main\0HEAD 1111\0branch refs/heads/main\0\0\
+            worktree /repo/linked\0HEAD 2222\0detached\0locked on usb\0\0\
+            worktree /repo/gone\0HEAD 3333\0branch refs/heads/gone\0locked\0\
+            prunable gitdir file points to non-existent location\0\0";
+        let actual = parse_worktree_list_output(OUTPUT).unwrap();
+        assert_eq!(3, actual.len());
+        assert_eq!("/repo/main", actual[0].path);
+        assert!(actual[0].main);
+        assert_eq!(Some("refs/heads/main"), actual[0].branch.as_deref());
+        assert!(!actual[1].main);
+        assert!(actual[1].detached);
+        assert_eq!(None, actual[1].branch);
+        assert!(actual[1].locked);
+        assert_eq!(Some("on usb"), actual[1].lock_reason.as_deref());
+        assert!(actual[2].locked);
+        assert_eq!(None, actual[2].lock_reason);
+        assert!(actual[2].prunable);
+        assert_eq!(Some("3333"), actual[2].head.as_deref());
+    }
+
+    #[test]
+    fn test_parse_bare_worktree() {
+        let actual = parse_worktree_list_output("worktree /repo.git\0bare\0\0").unwrap();
+        assert!(actual[0].bare);
+        assert_eq!(None, actual[0].head);
+        assert!(parse_worktree_list_output("HEAD 1111\0\0").is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            PathBuf::from("/repo/.git"),
+            normalize(Path::new("/repo/.git/worktrees/linked/../.."))
+        );
+    }
+}
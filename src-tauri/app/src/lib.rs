@@ -17,7 +17,9 @@ use state::env::{EnvState, EnvStateMutex};
 use state::pty::PtyStateMutex;
 use state::repo_config::RepoConfigStateMutex;
 use state::repositories::RepositoriesStateMutex;
+use state::requests::RequestsStateMutex;
 use state::stager::StagerStateMutex;
+use state::watcher::WatcherStateMutex;
 use std::{error::Error, fs::create_dir_all};
 use sync::get_sync;
 use tauri::{
@@ -134,16 +136,47 @@ pub fn run() {
         .manage(RepoConfigStateMutex::new())
         .manage(PtyStateMutex::new())
         .manage(RepositoriesStateMutex::new())
+        .manage(RequestsStateMutex::new())
         .manage(StagerStateMutex::new())
+        .manage(WatcherStateMutex::new())
         .manage(AvatarsState::new())
         .invoke_handler(generate_handler![
             commands::open_repository,
             commands::close_repository,
+            commands::clone_repository,
+            commands::init_repository,
             commands::fetch_history,
+            commands::fetch_history_page,
+            commands::stream_history,
+            commands::fetch_history_update,
+            commands::search_history,
+            commands::cancel_request,
             commands::get_reflog,
+            commands::get_stash_list,
+            commands::create_stash,
+            commands::apply_stash,
+            commands::pop_stash,
+            commands::drop_stash,
+            commands::get_stash_changes,
             commands::commit,
+            commands::get_commit_signing,
+            commands::commit_with_pty,
             commands::create_branch,
             commands::delete_branch,
+            commands::rename_branch,
+            commands::set_branch_upstream,
+            commands::create_tag,
+            commands::delete_tag,
+            commands::get_tag_detail,
+            commands::list_worktrees,
+            commands::add_worktree,
+            commands::remove_worktree,
+            commands::prune_worktrees,
+            commands::lock_worktree,
+            commands::list_submodules,
+            commands::init_submodules,
+            commands::update_submodules,
+            commands::sync_submodules,
             commands::get_current_branch,
             commands::get_blame,
             commands::get_last_modify_commit,
@@ -153,9 +186,12 @@ pub fn run() {
             custom_command::exec_custom_command_detached,
             commands::get_changes_between,
             commands::get_commit_detail,
+            commands::get_commit_signature,
+            commands::get_refs_containing,
             commands::get_content_base64,
             commands::get_system_fonts,
             commands::get_tree,
+            commands::grep,
             commands::get_workingtree_stat,
             commands::get_workingtree_udiff_base64,
             commands::load_persist_data,
@@ -166,11 +202,37 @@ pub fn run() {
             commands::store_state,
             commands::switch,
             commands::reset,
+            commands::merge,
+            commands::rebase,
+            commands::get_rebase_todo,
+            commands::interactive_rebase,
+            commands::cherry_pick,
+            commands::revert,
+            commands::get_operation_state,
+            commands::run_operation_action,
+            commands::start_bisect,
+            commands::mark_bisect,
+            commands::run_bisect,
+            commands::reset_bisect,
+            commands::get_bisect_log,
             commands::unstage,
             commands::restore,
+            commands::get_workingtree_hunks,
+            commands::stage_hunks,
+            commands::unstage_hunks,
+            commands::restore_hunks,
+            commands::get_conflict_versions,
+            commands::get_conflict_regions,
+            commands::resolve_conflict,
+            commands::mark_resolved,
+            commands::take_conflict_side,
             commands::show_external_diff,
+            commands::show_external_merge,
             commands::get_user_info,
             commands::get_remote_list,
+            commands::fetch,
+            commands::pull,
+            commands::push,
             commands::yank_text,
             commands::open_pty,
             commands::write_pty,
@@ -228,6 +290,10 @@ pub fn run() {
                 let mut stager = state.0.lock().await;
                 stager.dispose().await;
 
+                let state = app_handle_clone.state::<WatcherStateMutex>();
+                let mut watcher = state.0.lock().await;
+                watcher.dispose();
+
                 let state = app_handle_clone.state::<RepositoriesStateMutex>();
                 let mut repositories = state.0.lock().await;
                 repositories.dispose();
@@ -1,13 +1,45 @@
-use portable_pty::{native_pty_system, CommandBuilder, ExitStatus, PtyPair, PtySize};
+use portable_pty::{native_pty_system, CommandBuilder, ExitStatus, MasterPty, PtyPair, PtySize};
 use std::error::Error;
 use std::io::Write;
 use std::path::Path;
 use tokio::sync::mpsc::{channel, Sender};
 use tokio::{spawn, task::spawn_blocking};
 
-use crate::platform::split_commandline;
 use crate::sync::get_sync;
 
+/// Returns the length of the incomplete UTF-8 sequence at the end of `bytes`.
+fn incomplete_utf8_suffix_len(bytes: &[u8]) -> usize {
+    for i in 1..=bytes.len().min(3) {
+        let b = bytes[bytes.len() - i];
+        if b & 0xC0 != 0x80 {
+            let char_len = match b {
+                0xF0.. => 4,
+                0xE0.. => 3,
+                0xC0.. => 2,
+                _ => 1,
+            };
+            return if char_len > i { i } else { 0 };
+        }
+    }
+    0
+}
+
+/// Returns the device name of the slave side of the pty (e.g. `/dev/pts/3`).
+///
+/// It is passed as `GPG_TTY`, so that gpg-agent can show pinentry (curses/tty) in the pty
+/// to enter the passphrase of the signing key.
+#[cfg(unix)]
+fn slave_tty_name(master: &dyn MasterPty) -> Option<String> {
+    let fd = master.as_raw_fd()?;
+    // ptsname is not thread-safe, but ptys are opened one by one (see `PtyState::open`, which is called under `PtyStateMutex`)
+    let name = unsafe { libc::ptsname(fd) };
+    if name.is_null() {
+        return None;
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     Resize { rows: u16, cols: u16 },
@@ -21,15 +53,18 @@ pub struct Pty {
 }
 
 impl Pty {
+    /// Runs `args[0]` with the rest of `args` in a new pty.
     pub fn open<F1: Fn(&[u8]) + Send + 'static, F2: FnOnce(ExitStatus) + Send + 'static>(
-        command_line: &str,
+        args: &[String],
         cwd: &Path,
         rows: u16,
         cols: u16,
         on_data: F1,
         on_exit: F2,
     ) -> Result<Pty, Box<dyn Error + Send + Sync>> {
-        let args = split_commandline(command_line)?;
+        if args.is_empty() {
+            return Err("Command line is empty".into());
+        }
         let pty_system = native_pty_system();
         let PtyPair { master, slave } = pty_system.openpty(PtySize {
             rows,
@@ -44,11 +79,22 @@ impl Pty {
         spawn_blocking(move || {
             notify.notify();
             let mut buf = [0u8; 8162];
-            while let Ok(len) = reader.read(&mut buf) {
+            // length of an incomplete UTF-8 sequence at the start of buf, carried over from the last read
+            let mut pending = 0;
+            while let Ok(len) = reader.read(&mut buf[pending..]) {
                 if len == 0 {
                     break;
                 }
-                on_data(&buf[0..len]);
+                let end = pending + len;
+                // a character may be split between reads, so each chunk must end at
+                // a character boundary to be decoded
+                let tail = incomplete_utf8_suffix_len(&buf[0..end]);
+                on_data(&buf[0..end - tail]);
+                buf.copy_within(end - tail..end, 0);
+                pending = tail;
+            }
+            if pending > 0 {
+                on_data(&buf[0..pending]);
             }
             debug!("pty reader thread has finished");
         });
@@ -60,6 +106,10 @@ impl Pty {
         let mut cmd = CommandBuilder::new(&args[0]);
         cmd.args(&args[1..]);
         cmd.cwd(cwd);
+        #[cfg(unix)]
+        if let Some(tty) = slave_tty_name(master.as_ref()) {
+            cmd.env("GPG_TTY", tty);
+        }
         let mut child = slave.spawn_command(cmd)?;
 
         let mut killer = child.clone_killer();
@@ -118,3 +168,19 @@ impl Pty {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incomplete_utf8_suffix_len() {
+        let text = "aあ".as_bytes();
+        assert_eq!(0, incomplete_utf8_suffix_len(text));
+        assert_eq!(2, incomplete_utf8_suffix_len(&text[0..3]));
+        assert_eq!(1, incomplete_utf8_suffix_len(&text[0..2]));
+        assert_eq!(0, incomplete_utf8_suffix_len(&text[0..1]));
+        assert_eq!(0, incomplete_utf8_suffix_len(b""));
+        assert_eq!(0, incomplete_utf8_suffix_len(b"\x80\x80\x80\x80"));
+    }
+}
@@ -4,4 +4,6 @@ pub mod env;
 pub mod pty;
 pub mod repo_config;
 pub mod repositories;
+pub mod requests;
 pub mod stager;
+pub mod watcher;
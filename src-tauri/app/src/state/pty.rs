@@ -25,7 +25,7 @@ impl PtyState {
     >(
         &mut self,
         id: PtyId,
-        command_line: &str,
+        args: &[String],
         cwd: &Path,
         rows: u16,
         cols: u16,
@@ -42,7 +42,7 @@ impl PtyState {
             });
             on_exit(id, result);
         };
-        let pty = Pty::open(command_line, cwd, rows, cols, on_data_, on_exit_)?;
+        let pty = Pty::open(args, cwd, rows, cols, on_data_, on_exit_)?;
         if let Some(old) = self.map.lock().await.insert(id.0, pty) {
             old.kill().await?;
         }
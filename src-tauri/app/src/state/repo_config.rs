@@ -2,12 +2,19 @@ use std::{
     error::Error,
     fs::{write, File},
     io::BufReader,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+use crate::git::worktree;
 use tokio::sync::Mutex;
 use types::RepositoryConfig;
 
+/// The config file is in the common dir, so that it is shared by all worktrees.
+fn config_file_path(repo_path: &Path) -> PathBuf {
+    let git_dir = worktree::resolve_git_dir(repo_path);
+    worktree::resolve_common_dir(&git_dir).join("inazuma.json")
+}
+
 pub struct RepoConfigState {
     pub repo_path: Option<PathBuf>,
     pub config: RepositoryConfig,
@@ -22,7 +29,7 @@ impl RepoConfigState {
     }
 
     pub fn load(&mut self, repo_path: &str) -> Result<RepositoryConfig, Box<dyn Error>> {
-        let path = PathBuf::from(repo_path).join(".git").join("inazuma.json");
+        let path = config_file_path(Path::new(repo_path));
 
         if !path.exists() {
             // Return default config if file doesn't exist
@@ -43,7 +50,7 @@ impl RepoConfigState {
     pub fn save(&mut self, new_config: RepositoryConfig) -> Result<(), Box<dyn Error>> {
         let repo_path = self.repo_path.as_ref().ok_or("No repository path set")?;
 
-        let path = repo_path.join(".git").join("inazuma.json");
+        let path = config_file_path(repo_path);
 
         self.config = new_config;
 
@@ -1,20 +1,30 @@
+use crate::git::backend::Backend;
+use crate::git::cat_file::ObjectReader;
+use crate::git::worktree;
 use sha1_smol::Sha1;
 use std::collections::HashMap;
 use std::env;
 use std::fs::{create_dir_all, remove_dir_all};
 use std::path::{Path, PathBuf};
 use tokio::sync::Mutex;
+use types::GitBackendKind;
 
 #[derive(Clone)]
 pub struct Repository {
     pub name: String,
     pub path: PathBuf,
+    /// `.git` of the main worktree, or `.git/worktrees/<name>` of a linked worktree.
+    pub git_dir: PathBuf,
+    /// Directory shared by all worktrees, which contains refs, objects and config.
+    pub common_dir: PathBuf,
     pub temp_dir: PathBuf,
     pub stage_file_dir: PathBuf,
+    pub object_reader: ObjectReader,
+    pub backend: Backend,
 }
 
 impl Repository {
-    pub fn new(path: PathBuf) -> Self {
+    pub fn new(path: PathBuf, backend_kind: GitBackendKind) -> Self {
         let name = path.file_name().unwrap().to_str().unwrap();
         let hash = Sha1::from(path.to_str().unwrap()).hexdigest();
         let temp_dir_name = format!("{}-{}", name, &hash[..8]);
@@ -26,11 +36,25 @@ impl Repository {
         if !stage_file_dir.exists() {
             create_dir_all(&stage_file_dir).expect("Failed to create stage file directory.");
         }
+        let git_dir = worktree::resolve_git_dir(&path);
+        let common_dir = worktree::resolve_common_dir(&git_dir);
+        let object_reader = ObjectReader::new(&path);
+        let backend = Backend::new(backend_kind, &path, object_reader.clone());
         Repository {
             name: name.into(),
             path,
+            git_dir,
+            common_dir,
             temp_dir,
             stage_file_dir,
+            object_reader,
+            backend,
+        }
+    }
+
+    pub fn set_backend(&mut self, kind: GitBackendKind) {
+        if self.backend.kind() != kind {
+            self.backend = Backend::new(kind, &self.path, self.object_reader.clone());
         }
     }
 
@@ -62,16 +86,30 @@ impl RepositoriesState {
         self.repositories.get(repo_path.to_str().unwrap())
     }
 
-    pub fn get_or_insert(&mut self, repo_path: &Path) -> &mut Repository {
+    pub fn get_or_insert(
+        &mut self,
+        repo_path: &Path,
+        backend_kind: GitBackendKind,
+    ) -> &mut Repository {
         let key = repo_path.to_str().unwrap();
         if !self.repositories.contains_key(key) {
-            self.repositories
-                .insert(key.to_owned(), Repository::new(repo_path.to_path_buf()));
+            self.repositories.insert(
+                key.to_owned(),
+                Repository::new(repo_path.to_path_buf(), backend_kind),
+            );
         }
         let repo = self.repositories.get_mut(key).unwrap();
+        repo.set_backend(backend_kind);
         repo
     }
 
+    /// Switches backends of all repositories.
+    pub fn set_backend(&mut self, kind: GitBackendKind) {
+        for repo in self.repositories.values_mut() {
+            repo.set_backend(kind);
+        }
+    }
+
     pub fn dispose(&mut self) {
         for (_, mut repo) in self.repositories.drain() {
             repo.dispose();
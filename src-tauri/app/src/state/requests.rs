@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use crate::git::cancel::CancelToken;
+
+/// Cancel tokens of running requests, keyed by request ids which are given by the frontend.
+pub struct RequestsState {
+    requests: HashMap<String, CancelToken>,
+}
+
+impl RequestsState {
+    pub fn new() -> Self {
+        RequestsState {
+            requests: HashMap::new(),
+        }
+    }
+
+    /// Registers a new request.
+    ///
+    /// If a request with the same id is still running, it is cancelled.
+    pub fn register(&mut self, request_id: &str) -> CancelToken {
+        let token = CancelToken::new();
+        if let Some(old) = self.requests.insert(request_id.to_owned(), token.clone()) {
+            old.cancel();
+        }
+        token
+    }
+
+    /// Removes the request if it is not replaced by another one.
+    pub fn unregister(&mut self, request_id: &str, token: &CancelToken) {
+        if self.requests.get(request_id) == Some(token) {
+            self.requests.remove(request_id);
+        }
+    }
+
+    /// Cancels the request. Returns false if the request is not running.
+    pub fn cancel(&mut self, request_id: &str) -> bool {
+        match self.requests.remove(request_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub struct RequestsStateMutex(pub Mutex<RequestsState>);
+
+impl RequestsStateMutex {
+    pub fn new() -> Self {
+        RequestsStateMutex(Mutex::new(RequestsState::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_cancel() {
+        let mut state = RequestsState::new();
+        let token1 = state.register("a");
+        let token2 = state.register("a");
+        assert!(token1.is_cancelled());
+        assert!(!token2.is_cancelled());
+        state.unregister("a", &token1);
+        assert!(state.cancel("a"));
+        assert!(token2.is_cancelled());
+        assert!(!state.cancel("a"));
+    }
+}
@@ -9,7 +9,7 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::Receiver;
 use tokio::sync::Mutex;
 use tokio_stream::StreamExt;
-use types::FileSpec;
+use types::{FileSpec, ReloadRequest};
 
 use crate::git;
 use notify::{Error as NotifyError, Event, EventKind, Watcher};
@@ -29,7 +29,10 @@ pub struct StagerState {
 }
 
 async fn update_index(f: &TempStageFile) -> Result<(), String> {
-    match git::rev_parse::rev_parse(&f.repo_path, "HEAD").await? {
+    match git::rev_parse::rev_parse(&f.repo_path, "HEAD")
+        .await
+        .map_err(|e| format!("{}", e))?
+    {
         Some(head) => {
             if head.eq(&f.head) {
                 git::workingtree::update_index(&f.repo_path, &f.rel_path, &f.temp_path)
@@ -68,7 +71,13 @@ fn handle_event<R: Runtime>(
                 if let Err(e) = update_index(&f).await {
                     warn!("{}", e);
                 } else {
-                    if let Err(e) = app_handle.emit("request_reload", &f.repo_path) {
+                    let request = ReloadRequest {
+                        repo_path: f.repo_path.to_string_lossy().into_owned(),
+                        refs: false,
+                        index: true,
+                        working_tree: false,
+                    };
+                    if let Err(e) = app_handle.emit("request_reload", &request) {
                         error!("Failed to emit event: request_reload, {}", e);
                     }
                 }
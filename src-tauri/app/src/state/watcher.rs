@@ -0,0 +1,295 @@
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::spawn;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::Receiver;
+use tokio::sync::Mutex;
+use tokio::time::{timeout, Instant};
+use types::ReloadRequest;
+
+use crate::git;
+use notify::{Error as NotifyError, Event, EventKind, RecursiveMode, Watcher};
+
+use super::repositories::Repository;
+
+/// Changes are notified after no more changes are detected for this duration.
+const QUIET_PERIOD: Duration = Duration::from_millis(300);
+/// Changes are notified at least once in this duration even if changes are detected continuously.
+const MAX_DELAY: Duration = Duration::from_millis(2000);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ChangeKind {
+    Refs,
+    Index,
+    WorkingTree,
+}
+
+/**
+ * Classifies a changed path (relative to the repository root).
+ *
+ * Files in .git directory other than refs, index and files which represent
+ * the state of ongoing operations are ignored. (e.g. objects, lock files)
+ */
+fn classify(rel_path: &Path) -> Option<ChangeKind> {
+    let mut components = rel_path.components().filter_map(|c| match c {
+        Component::Normal(name) => Some(name),
+        _ => None,
+    });
+    let first = components.next()?;
+    if first != ".git" {
+        return Some(ChangeKind::WorkingTree);
+    }
+    if rel_path.extension().is_some_and(|ext| ext == "lock") {
+        return None;
+    }
+    match components.next()?.to_str()? {
+        "index" => Some(ChangeKind::Index),
+        "HEAD" | "refs" | "packed-refs" | "logs" | "MERGE_HEAD" | "CHERRY_PICK_HEAD"
+        | "REVERT_HEAD" | "REBASE_HEAD" | "BISECT_LOG" | "rebase-merge" | "rebase-apply"
+        | "sequencer" => Some(ChangeKind::Refs),
+        _ => None,
+    }
+}
+
+/// Receives changes until changes are settled.
+async fn receive_changes(
+    rx: &mut Receiver<(ChangeKind, PathBuf)>,
+) -> Option<Vec<(ChangeKind, PathBuf)>> {
+    let first = rx.recv().await?;
+    let deadline = Instant::now() + MAX_DELAY;
+    let mut changes = vec![first];
+    loop {
+        let wait = QUIET_PERIOD.min(deadline.saturating_duration_since(Instant::now()));
+        match timeout(wait, rx.recv()).await {
+            Ok(Some(change)) => changes.push(change),
+            _ => break,
+        }
+    }
+    Some(changes)
+}
+
+async fn build_reload_request(
+    repo_path: &Path,
+    changes: Vec<(ChangeKind, PathBuf)>,
+) -> ReloadRequest {
+    let mut refs = false;
+    let mut index = false;
+    let mut working_tree_paths = BTreeSet::<String>::new();
+    for (kind, rel_path) in changes {
+        match kind {
+            ChangeKind::Refs => refs = true,
+            ChangeKind::Index => index = true,
+            ChangeKind::WorkingTree => {
+                working_tree_paths.insert(rel_path.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    let working_tree_paths = working_tree_paths.into_iter().collect::<Vec<_>>();
+    let working_tree = match git::check_ignore::filter_ignored(repo_path, &working_tree_paths).await
+    {
+        Ok(paths) => !paths.is_empty(),
+        Err(e) => {
+            warn!("watcher: Failed to check ignored files, {}", e);
+            !working_tree_paths.is_empty()
+        }
+    };
+    ReloadRequest {
+        repo_path: repo_path.to_string_lossy().into_owned(),
+        refs,
+        index,
+        working_tree,
+    }
+}
+
+/// Directories and prefixes to convert changed paths to paths relative to the repository root.
+///
+/// In linked worktrees, `.git` is a file and the git dir and the common dir are outside
+/// the working tree, so changes in them are converted to paths in `.git`.
+/// The git dir precedes the common dir, since it may be in the common dir.
+fn path_roots(repo: &Repository) -> Vec<(PathBuf, PathBuf)> {
+    let roots = [
+        (repo.git_dir.clone(), PathBuf::from(".git")),
+        (repo.common_dir.clone(), PathBuf::from(".git")),
+        (repo.path.clone(), PathBuf::new()),
+    ];
+    let mut ret = Vec::new();
+    for (dir, prefix) in roots {
+        // notify may report canonicalized paths (e.g. FSEvents on macOS)
+        if let Ok(canonical_dir) = dir.canonicalize() {
+            if canonical_dir != dir {
+                ret.push((canonical_dir, prefix.clone()));
+            }
+        }
+        ret.push((dir, prefix));
+    }
+    ret
+}
+
+/// Converts a changed path to the path relative to the repository root.
+fn relative_path(path: &Path, roots: &[(PathBuf, PathBuf)]) -> Option<PathBuf> {
+    roots.iter().find_map(|(dir, prefix)| {
+        path.strip_prefix(dir)
+            .ok()
+            .map(|rel_path| prefix.join(rel_path))
+    })
+}
+
+/// Directories to be watched: the working tree, and the common dir and the git dir
+/// if they are not in the working tree.
+fn watch_dirs(repo: &Repository) -> Vec<PathBuf> {
+    let mut dirs = vec![repo.path.clone()];
+    for dir in [&repo.common_dir, &repo.git_dir] {
+        if !dirs.iter().any(|d| dir.starts_with(d)) {
+            dirs.push(dir.clone());
+        }
+    }
+    dirs
+}
+
+fn handle_event<R: Runtime>(
+    app_handle: AppHandle<R>,
+    repo_path: PathBuf,
+    roots: Vec<(PathBuf, PathBuf)>,
+    mut rx: Receiver<Result<Event, NotifyError>>,
+) {
+    let (inner_tx, mut inner_rx) = mpsc::channel::<(ChangeKind, PathBuf)>(1000);
+
+    spawn(async move {
+        debug!("Watcher: start watching {:?}", repo_path);
+        while let Some(changes) = receive_changes(&mut inner_rx).await {
+            let request = build_reload_request(&repo_path, changes).await;
+            if request.refs || request.index || request.working_tree {
+                if let Err(e) = app_handle.emit("request_reload", &request) {
+                    error!("Failed to emit event: request_reload, {}", e);
+                }
+            }
+        }
+        debug!("Watcher: finish watching {:?}", repo_path);
+    });
+
+    spawn(async move {
+        while let Some(res) = rx.recv().await {
+            match res {
+                Ok(Event {
+                    kind: EventKind::Create(..) | EventKind::Modify(..) | EventKind::Remove(..),
+                    paths,
+                    ..
+                }) => {
+                    for path in paths {
+                        let Some(rel_path) = relative_path(&path, &roots) else {
+                            continue;
+                        };
+                        if let Some(kind) = classify(&rel_path) {
+                            if let Err(e) = inner_tx.send((kind, rel_path)).await {
+                                error!("watcher: Failed to send to change channel, {}", e);
+                            }
+                        }
+                    }
+                }
+                Err(e) => error!("watcher: Failed to receive from watcher channel, {}", e),
+                _ => {}
+            }
+        }
+    });
+}
+
+pub struct WatcherState {
+    watchers: HashMap<PathBuf, Box<dyn Watcher + Send + Sync>>,
+}
+
+impl WatcherState {
+    pub fn new() -> WatcherState {
+        WatcherState {
+            watchers: HashMap::new(),
+        }
+    }
+
+    pub fn watch<R: Runtime>(
+        &mut self,
+        app_handle: AppHandle<R>,
+        repo: &Repository,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.watchers.contains_key(&repo.path) {
+            return Ok(());
+        }
+        let (tx, rx) = mpsc::channel::<Result<Event, NotifyError>>(100);
+        handle_event(app_handle, repo.path.clone(), path_roots(repo), rx);
+
+        let mut watcher = notify::recommended_watcher(move |res| {
+            // sending fails only after the watcher has been dropped.
+            let _ = tx.blocking_send(res);
+        })?;
+        for dir in watch_dirs(repo) {
+            watcher.watch(&dir, RecursiveMode::Recursive)?;
+        }
+        self.watchers.insert(repo.path.clone(), Box::new(watcher));
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, repo: &Repository) {
+        // dropping the watcher closes channels, and then event handlers finish.
+        drop(self.watchers.remove(&repo.path));
+    }
+
+    pub fn dispose(&mut self) {
+        self.watchers.clear();
+    }
+}
+
+pub struct WatcherStateMutex(pub Mutex<WatcherState>);
+
+impl WatcherStateMutex {
+    pub fn new() -> WatcherStateMutex {
+        WatcherStateMutex(Mutex::new(WatcherState::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let classify = |p: &str| classify(Path::new(p));
+        assert_eq!(Some(ChangeKind::WorkingTree), classify("src/main.rs"));
+        assert_eq!(Some(ChangeKind::WorkingTree), classify(".gitignore"));
+        assert_eq!(Some(ChangeKind::Index), classify(".git/index"));
+        assert_eq!(None, classify(".git/index.lock"));
+        assert_eq!(Some(ChangeKind::Refs), classify(".git/HEAD"));
+        assert_eq!(Some(ChangeKind::Refs), classify(".git/refs/heads/main"));
+        assert_eq!(None, classify(".git/refs/heads/main.lock"));
+        assert_eq!(Some(ChangeKind::Refs), classify(".git/packed-refs"));
+        assert_eq!(Some(ChangeKind::Refs), classify(".git/rebase-merge/msgnum"));
+        assert_eq!(None, classify(".git/objects/12/3456"));
+        assert_eq!(None, classify(".git"));
+    }
+    #[test]
+    fn test_relative_path() {
+        // linked worktree
+        let roots = vec![
+            (
+                PathBuf::from("/repo/.git/worktrees/linked"),
+                PathBuf::from(".git"),
+            ),
+            (PathBuf::from("/repo/.git"), PathBuf::from(".git")),
+            (PathBuf::from("/linked"), PathBuf::new()),
+        ];
+        let relative_path = |p: &str| relative_path(Path::new(p), &roots);
+        assert_eq!(
+            Some(PathBuf::from(".git/HEAD")),
+            relative_path("/repo/.git/worktrees/linked/HEAD")
+        );
+        assert_eq!(
+            Some(PathBuf::from(".git/refs/heads/main")),
+            relative_path("/repo/.git/refs/heads/main")
+        );
+        assert_eq!(
+            Some(PathBuf::from("src/main.rs")),
+            relative_path("/linked/src/main.rs")
+        );
+        assert_eq!(None, relative_path("/other/file"));
+    }
+}
@@ -46,6 +46,16 @@ impl LogLevel {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub enum GitBackendKind {
+    #[default]
+    #[serde(rename = "cli")]
+    Cli,
+    #[serde(rename = "gitoxide")]
+    Gitoxide,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export)]
@@ -99,6 +109,8 @@ pub struct ConfigBase {
     #[ts(optional)]
     pub external_diff_tool: Option<String>,
     #[ts(optional)]
+    pub external_merge_tool: Option<String>,
+    #[ts(optional)]
     pub interactive_shell: Option<String>,
     #[serde(default = "default_recent_count")]
     pub recent_list_count: u32,
@@ -109,6 +121,8 @@ pub struct ConfigBase {
     #[serde(default)]
     pub log_level: LogLevel,
     #[serde(default)]
+    pub git_backend: GitBackendKind,
+    #[serde(default)]
     pub custom_commands: Vec<CommitCustomCommand>,
     #[serde(default)]
     pub custom_file_commands: Vec<FileCustomCommand>,
@@ -120,11 +134,13 @@ impl Into<Config> for ConfigBase {
             font_family: self.font_family,
             font_size: self.font_size,
             external_diff_tool: self.external_diff_tool,
+            external_merge_tool: self.external_merge_tool,
             interactive_shell: self.interactive_shell,
             recent_list_count: self.recent_list_count,
             avatar_shape: self.avatar_shape,
             use_gravatar: self.use_gravatar,
             log_level: self.log_level,
+            git_backend: self.git_backend,
             custom_commands: self.custom_commands,
             custom_file_commands: self.custom_file_commands,
         }
@@ -140,11 +156,14 @@ pub struct Config {
     #[ts(optional)]
     pub external_diff_tool: Option<String>,
     #[ts(optional)]
+    pub external_merge_tool: Option<String>,
+    #[ts(optional)]
     pub interactive_shell: Option<String>,
     pub recent_list_count: u32,
     pub avatar_shape: AvatarShape,
     pub use_gravatar: bool,
     pub log_level: LogLevel,
+    pub git_backend: GitBackendKind,
     #[serde(default)]
     pub custom_commands: Vec<CommitCustomCommand>,
     #[serde(default)]
@@ -157,11 +176,13 @@ impl Default for Config {
             font_family: FontFamily::default(),
             font_size: FontSize::default(),
             external_diff_tool: None,
+            external_merge_tool: None,
             interactive_shell: None,
             recent_list_count: default_recent_count(),
             avatar_shape: AvatarShape::default(),
             use_gravatar: default_use_gravatar(),
             log_level: LogLevel::default(),
+            git_backend: GitBackendKind::default(),
             custom_commands: Vec::new(),
             custom_file_commands: Vec::new(),
         }
@@ -270,6 +291,11 @@ pub struct Commit {
     pub mail_address: String,
     #[ts(type = "number")]
     pub date: u64,
+    // differ from author and author date after rebase or amend
+    pub committer: String,
+    pub committer_mail_address: String,
+    #[ts(type = "number")]
+    pub commit_date: u64,
     pub summary: String,
 }
 
@@ -288,9 +314,21 @@ impl Commit {
             author: author.to_string(),
             mail_address: mail_address.to_string(),
             date,
+            committer: String::new(),
+            committer_mail_address: String::new(),
+            commit_date: 0,
             summary: summary.to_string(),
         };
     }
+
+    pub fn with_committer(self, committer: &str, mail_address: &str, date: u64) -> Commit {
+        Commit {
+            committer: committer.to_string(),
+            committer_mail_address: mail_address.to_string(),
+            commit_date: date,
+            ..self
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
@@ -330,6 +368,15 @@ pub enum WorkingTreeFileKind {
     Unmerged { conflict_type: String },
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SubmoduleChange {
+    pub commit_changed: bool,
+    pub modified: bool,
+    pub untracked: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export)]
@@ -341,6 +388,13 @@ pub struct WorkingTreeFileEntry {
     pub status_code: String,
     #[ts(optional)]
     pub delta: Option<FileDelta>,
+    #[ts(optional)]
+    pub submodule: Option<SubmoduleChange>,
+    // raw bytes of `path` quoted like `core.quotePath` (e.g. "\202\240.txt"),
+    // only if it is not valid UTF-8 and `path` is decoded lossily.
+    // it is passed to git instead of `path`.
+    #[ts(optional)]
+    pub quoted_path: Option<String>,
 }
 
 impl WorkingTreeFileEntry {
@@ -355,6 +409,8 @@ impl WorkingTreeFileEntry {
             old_path: None,
             status_code: status_code.to_owned(),
             delta: None,
+            submodule: None,
+            quoted_path: None,
         }
     }
 
@@ -374,6 +430,8 @@ impl WorkingTreeFileEntry {
             old_path: Some(old_path.to_owned()),
             status_code: status_code.to_owned(),
             delta: None,
+            submodule: None,
+            quoted_path: None,
         }
     }
 
@@ -386,6 +444,8 @@ impl WorkingTreeFileEntry {
             old_path: None,
             status_code: "U".to_owned(),
             delta: None,
+            submodule: None,
+            quoted_path: None,
         }
     }
 
@@ -396,6 +456,15 @@ impl WorkingTreeFileEntry {
             old_path: None,
             status_code: "?".to_owned(),
             delta: None,
+            submodule: None,
+            quoted_path: None,
+        }
+    }
+
+    pub fn with_quoted_path(self, quoted_path: Option<String>) -> WorkingTreeFileEntry {
+        WorkingTreeFileEntry {
+            quoted_path,
+            ..self
         }
     }
 }
@@ -423,15 +492,106 @@ pub struct CommitDetail {
     #[serde(flatten)]
     pub commit: Commit,
     pub body: String,
+    pub trailers: Vec<Trailer>,
+    #[ts(optional)]
+    pub notes: Option<String>,
     pub files: Vec<FileEntry>,
 }
 
+// "Signed-off-by: name <mail>" at the end of commit message
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+}
+
+// `%G?` of pretty format, except "N" (no signature)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum SignatureStatus {
+    Good,
+    Bad,
+    UnknownValidity,
+    Expired,
+    ExpiredKey,
+    RevokedKey,
+    CannotCheck,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CommitSignature {
+    pub status: SignatureStatus,
+    #[ts(optional)]
+    pub signer: Option<String>,
+    #[ts(optional)]
+    pub key: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export)]
 pub struct WorkingTreeStat {
     pub files: Vec<WorkingTreeFileEntry>,
     pub parent_ids: Vec<String>,
+    #[ts(optional)]
+    pub operation: Option<OperationState>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ReloadRequest {
+    pub repo_path: String,
+    pub refs: bool,
+    pub index: bool,
+    pub working_tree: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConflictVersions {
+    #[ts(optional)]
+    pub base_base64: Option<String>,
+    #[ts(optional)]
+    pub ours_base64: Option<String>,
+    #[ts(optional)]
+    pub theirs_base64: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConflictHunk {
+    pub ours: String,
+    #[ts(optional)]
+    pub base: Option<String>,
+    pub theirs: String,
+    pub ours_label: String,
+    #[ts(optional)]
+    pub base_label: Option<String>,
+    pub theirs_label: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export)]
+pub enum ConflictRegion {
+    Common { text: String },
+    Conflict(ConflictHunk),
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum ConflictSide {
+    Ours,
+    Theirs,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
@@ -453,6 +613,12 @@ pub enum Ref {
         fullname: String,
         name: String,
         current: bool,
+        #[ts(optional)]
+        upstream: Option<String>,
+        ahead: u32,
+        behind: u32,
+        #[ts(optional)]
+        worktree: Option<String>,
     },
     Tag {
         id: String,
@@ -472,6 +638,19 @@ pub enum Ref {
         fullname: String,
         name: String,
     },
+    Stash {
+        id: String,
+        index: usize,
+        fullname: String,
+        name: String,
+    },
+    // refs under refs/bisect/, which delimit the candidate range of bisect
+    Bisect {
+        id: String,
+        fullname: String,
+        name: String,
+        verdict: BisectVerdict,
+    },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
@@ -482,6 +661,57 @@ pub struct Refs {
     pub head: Option<String>,
     pub merge_heads: Vec<String>,
     pub refs: Vec<Ref>,
+    pub worktrees: Vec<Worktree>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum MergesFilter {
+    MergesOnly,
+    NoMerges,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct HistoryFilter {
+    #[ts(optional)]
+    pub author: Option<String>,
+    #[ts(optional)]
+    pub committer: Option<String>,
+    #[ts(optional, type = "number")]
+    pub since: Option<u64>,
+    #[ts(optional, type = "number")]
+    pub until: Option<u64>,
+    #[ts(optional)]
+    pub message: Option<String>,
+    #[ts(optional)]
+    pub ignore_case: Option<bool>,
+    #[ts(optional)]
+    pub pickaxe: Option<String>,
+    #[ts(optional)]
+    pub pickaxe_regex: Option<bool>,
+    #[ts(optional)]
+    pub paths: Option<Vec<String>>,
+    #[ts(optional)]
+    pub merges: Option<MergesFilter>,
+    #[ts(optional)]
+    pub first_parent: Option<bool>,
+    #[ts(optional)]
+    pub max_count: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct HistoryPage {
+    pub commits: Vec<Commit>,
+    #[ts(optional)]
+    pub refs: Option<Refs>,
+    #[ts(optional)]
+    pub next_cursor: Option<String>,
+    pub refresh_token: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
@@ -490,6 +720,7 @@ pub struct Refs {
 pub enum LstreeData {
     Blob(String),
     Tree(String),
+    Submodule(String),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
@@ -499,6 +730,76 @@ pub struct LstreeEntry {
     pub data: LstreeData,
     #[ts(optional)]
     pub children: Option<Vec<LstreeEntry>>,
+    // see `WorkingTreeFileEntry::quoted_path`
+    #[ts(optional)]
+    pub quoted_path: Option<String>,
+}
+
+impl LstreeEntry {
+    pub fn new(data: LstreeData, children: Option<Vec<LstreeEntry>>) -> LstreeEntry {
+        LstreeEntry {
+            data,
+            children,
+            quoted_path: None,
+        }
+    }
+
+    pub fn with_quoted_path(self, quoted_path: Option<String>) -> LstreeEntry {
+        LstreeEntry {
+            quoted_path,
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GrepOptions {
+    pub pattern: String,
+    pub revspec: String,
+    #[ts(optional)]
+    pub fixed_strings: Option<bool>,
+    #[ts(optional)]
+    pub ignore_case: Option<bool>,
+    #[ts(optional)]
+    pub word_regexp: Option<bool>,
+    #[ts(optional)]
+    pub includes: Option<Vec<String>>,
+    #[ts(optional)]
+    pub excludes: Option<Vec<String>>,
+    #[ts(optional)]
+    pub max_count: Option<u32>,
+    #[ts(optional)]
+    pub context: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GrepLine {
+    pub line_number: u32,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GrepMatch {
+    pub path: String,
+    pub line_number: u32,
+    pub column: u32,
+    pub text: String,
+    pub before: Vec<GrepLine>,
+    pub after: Vec<GrepLine>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GrepResult {
+    pub matches: Vec<GrepMatch>,
+    pub truncated: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
@@ -522,16 +823,71 @@ pub struct Blame {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
-#[serde(rename_all = "camelCase", tag = "commitType")]
+#[serde(
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase",
+    tag = "commitType"
+)]
 #[ts(export)]
 pub enum CommitOptions {
     Normal {
         message: String,
+        #[serde(flatten)]
+        #[ts(flatten)]
+        flags: CommitFlags,
     },
     Amend {
         #[ts(optional)]
         message: Option<String>,
+        // author and author date are reset to the committer and the current time
+        #[ts(optional)]
+        reset_author: Option<bool>,
+        #[serde(flatten)]
+        #[ts(flatten)]
+        flags: CommitFlags,
     },
+    // "fixup! {summary of target}" (or "squash! ..." with message) to be melded by rebase --autosquash
+    Fixup {
+        target: String,
+        #[ts(optional)]
+        squash: Option<bool>,
+        #[ts(optional)]
+        message: Option<String>,
+        #[serde(flatten)]
+        #[ts(flatten)]
+        flags: CommitFlags,
+    },
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CommitFlags {
+    // follows `commit.gpgSign` and `gpg.format` if not specified
+    #[ts(optional)]
+    pub signing: Option<CommitSigning>,
+    // "Name <mail>"
+    #[ts(optional)]
+    pub author: Option<String>,
+    // any format accepted by `git commit --date`
+    #[ts(optional)]
+    pub author_date: Option<String>,
+    #[ts(optional)]
+    pub signoff: Option<bool>,
+    // bypasses pre-commit and commit-msg hooks
+    #[ts(optional)]
+    pub no_verify: Option<bool>,
+    #[ts(optional)]
+    pub allow_empty: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum CommitSigning {
+    Gpg,
+    Ssh,
+    None,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
@@ -555,6 +911,56 @@ pub struct DeleteBranchOptions {
     pub force: Option<bool>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RenameBranchOptions {
+    pub old_name: String,
+    pub new_name: String,
+    #[ts(optional)]
+    pub force: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CreateTagOptions {
+    pub tag_name: String,
+    pub commit_id: String,
+    #[ts(optional)]
+    pub message: Option<String>,
+    #[ts(optional)]
+    pub sign: Option<bool>,
+    #[ts(optional)]
+    pub force: Option<bool>,
+    #[ts(optional)]
+    pub push_to: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DeleteTagOptions {
+    pub tag_name: String,
+    #[ts(optional)]
+    pub remote: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TagDetail {
+    pub name: String,
+    pub tag_sha: String,
+    pub target_id: String,
+    pub tagger: String,
+    pub mail_address: String,
+    #[ts(type = "number")]
+    pub date: u64,
+    pub message: String,
+    pub signed: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export)]
@@ -606,6 +1012,519 @@ pub struct ResetOptions {
     pub commit_id: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MergeOptions {
+    pub commit_id: String,
+    #[ts(optional)]
+    pub no_ff: Option<bool>,
+    #[ts(optional)]
+    pub ff_only: Option<bool>,
+    #[ts(optional)]
+    pub squash: Option<bool>,
+    #[ts(optional)]
+    pub no_commit: Option<bool>,
+    #[ts(optional)]
+    pub message: Option<String>,
+    // bypass pre-merge-commit and commit-msg hooks
+    #[ts(optional)]
+    pub no_verify: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RebaseOptions {
+    pub upstream: String,
+    #[ts(optional)]
+    pub onto: Option<String>,
+    #[ts(optional)]
+    pub autostash: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum RebaseTodoAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseTodoAction,
+    pub id: String,
+    pub summary: String,
+    #[ts(optional)]
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct InteractiveRebaseOptions {
+    pub base: String,
+    pub entries: Vec<RebaseTodoEntry>,
+    #[ts(optional)]
+    pub autostash: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CherryPickOptions {
+    pub commit_ids: Vec<String>,
+    #[ts(optional)]
+    pub mainline: Option<u32>,
+    #[ts(optional)]
+    pub no_commit: Option<bool>,
+    #[ts(optional)]
+    pub record_origin: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RevertOptions {
+    pub commit_ids: Vec<String>,
+    #[ts(optional)]
+    pub mainline: Option<u32>,
+    #[ts(optional)]
+    pub no_commit: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum OperationKind {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum OperationAction {
+    Continue,
+    Skip,
+    Abort,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OperationState {
+    pub kind: OperationKind,
+    #[ts(optional)]
+    pub head: Option<String>,
+    #[ts(optional)]
+    pub head_name: Option<String>,
+    #[ts(optional)]
+    pub onto: Option<String>,
+    #[ts(optional)]
+    pub step: Option<u32>,
+    #[ts(optional)]
+    pub total: Option<u32>,
+    pub actions: Vec<OperationAction>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FetchOptions {
+    #[ts(optional)]
+    pub remote: Option<String>,
+    #[ts(optional)]
+    pub refspec: Option<String>,
+    #[ts(optional)]
+    pub all: Option<bool>,
+    #[ts(optional)]
+    pub prune: Option<bool>,
+    #[ts(optional)]
+    pub tags: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PullOptions {
+    #[ts(optional)]
+    pub remote: Option<String>,
+    #[ts(optional)]
+    pub refspec: Option<String>,
+    #[ts(optional)]
+    pub rebase: Option<bool>,
+    #[ts(optional)]
+    pub ff_only: Option<bool>,
+    #[ts(optional)]
+    pub prune: Option<bool>,
+    #[ts(optional)]
+    pub tags: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PushOptions {
+    #[ts(optional)]
+    pub remote: Option<String>,
+    #[ts(optional)]
+    pub refspec: Option<String>,
+    #[ts(optional)]
+    pub force_with_lease: Option<bool>,
+    #[ts(optional)]
+    pub set_upstream: Option<bool>,
+    #[ts(optional)]
+    pub tags: Option<bool>,
+    // bypass pre-push hook
+    #[ts(optional)]
+    pub no_verify: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RemoteProgress {
+    pub phase: String,
+    #[ts(optional)]
+    pub percent: Option<u32>,
+    #[ts(optional)]
+    pub current: Option<u32>,
+    #[ts(optional)]
+    pub total: Option<u32>,
+    pub done: bool,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum RefUpdateStatus {
+    FastForward,
+    Forced,
+    New,
+    Deleted,
+    TagUpdate,
+    UpToDate,
+    Rejected,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RefUpdate {
+    pub status: RefUpdateStatus,
+    pub from: String,
+    pub to: String,
+    pub summary: String,
+    #[ts(optional)]
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Stash {
+    pub index: usize,
+    pub id: String,
+    #[ts(type = "number")]
+    pub date: u64,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Worktree {
+    pub path: String,
+    #[ts(optional)]
+    pub head: Option<String>,
+    #[ts(optional)]
+    pub branch: Option<String>,
+    pub main: bool,
+    pub current: bool,
+    pub bare: bool,
+    pub detached: bool,
+    pub locked: bool,
+    #[ts(optional)]
+    pub lock_reason: Option<String>,
+    pub prunable: bool,
+    #[ts(optional)]
+    pub prunable_reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AddWorktreeOptions {
+    pub path: String,
+    #[ts(optional)]
+    pub commitish: Option<String>,
+    #[ts(optional)]
+    pub new_branch: Option<String>,
+    #[ts(optional)]
+    pub detach: Option<bool>,
+    #[ts(optional)]
+    pub force: Option<bool>,
+    #[ts(optional)]
+    pub lock_reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Submodule {
+    pub name: String,
+    pub path: String,
+    #[ts(optional)]
+    pub url: Option<String>,
+    pub recorded_id: String,
+    #[ts(optional)]
+    pub checked_out_id: Option<String>,
+    pub initialized: bool,
+    #[ts(optional)]
+    pub change: Option<SubmoduleChange>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct UpdateSubmoduleOptions {
+    #[ts(optional)]
+    pub paths: Option<Vec<String>>,
+    #[ts(optional)]
+    pub init: Option<bool>,
+    #[ts(optional)]
+    pub recursive: Option<bool>,
+    #[ts(optional)]
+    pub remote: Option<bool>,
+    #[ts(optional)]
+    pub force: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum BisectVerdict {
+    Good,
+    Bad,
+    Skip,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BisectStep {
+    #[ts(optional)]
+    pub current: Option<String>,
+    #[ts(optional)]
+    pub remaining: Option<u32>,
+    #[ts(optional)]
+    pub steps: Option<u32>,
+    #[ts(optional)]
+    pub first_bad: Option<String>,
+    pub possible_first_bad: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BisectLogEntry {
+    pub verdict: BisectVerdict,
+    pub id: String,
+    pub summary: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BisectLog {
+    pub entries: Vec<BisectLogEntry>,
+    #[ts(optional)]
+    pub first_bad: Option<String>,
+    pub possible_first_bad: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CloneOptions {
+    pub url: String,
+    pub path: String,
+    #[ts(optional)]
+    pub branch: Option<String>,
+    #[ts(optional)]
+    pub depth: Option<u32>,
+    #[ts(optional)]
+    pub recurse_submodules: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct InitOptions {
+    pub path: String,
+    #[ts(optional)]
+    pub initial_branch: Option<String>,
+    #[ts(optional)]
+    pub bare: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CreateStashOptions {
+    #[ts(optional)]
+    pub message: Option<String>,
+    #[ts(optional)]
+    pub include_untracked: Option<bool>,
+    #[ts(optional)]
+    pub keep_index: Option<bool>,
+    #[ts(optional)]
+    pub pathspecs: Option<Vec<String>>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum DiffLineKind {
+    Context,
+    Addition,
+    Deletion,
+    NoNewline,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub content: String,
+    #[ts(optional)]
+    pub old_line_no: Option<u32>,
+    #[ts(optional)]
+    pub new_line_no: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DiffHunk {
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FileDiff {
+    pub path: String,
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct HunkSelection {
+    pub hunk_index: u32,
+    #[ts(optional)]
+    pub line_indices: Option<Vec<u32>>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum CommandErrorKind {
+    ExitCodeNonZero,
+    UnexpectedOutput,
+    ExecFailed,
+    ArgumentError,
+    Gitoxide,
+    Cancelled,
+    RefsRejected,
+    HookFailed,
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum ErrorHint {
+    NotARepository,
+    IndexLocked,
+    MergeConflict,
+    AuthenticationRequired,
+    SigningFailed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CommandError {
+    pub kind: CommandErrorKind,
+    pub message: String,
+    #[ts(optional)]
+    pub command: Option<String>,
+    #[ts(optional)]
+    pub exit_code: Option<i32>,
+    #[ts(optional)]
+    pub stderr: Option<String>,
+    #[ts(optional)]
+    pub hint: Option<ErrorHint>,
+    #[ts(optional)]
+    pub rejected_refs: Option<Vec<RefUpdate>>,
+    // name of the hook which caused the failure (e.g. "pre-commit")
+    #[ts(optional)]
+    pub hook: Option<String>,
+}
+
+impl CommandError {
+    pub fn other(message: impl Into<String>) -> Self {
+        CommandError {
+            kind: CommandErrorKind::Other,
+            message: message.into(),
+            command: None,
+            exit_code: None,
+            stderr: None,
+            hint: None,
+            rejected_refs: None,
+            hook: None,
+        }
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::other(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        CommandError::other(message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -716,4 +1635,13 @@ mod tests {
         assert_eq!(deserialized.custom_file_commands.len(), 1);
         assert_eq!(deserialized.custom_file_commands[0].name, "open-file");
     }
+
+    #[test]
+    fn test_command_error_from_string() {
+        let e: CommandError = "Repository is not opened".into();
+        let json = serde_json::to_value(&e).unwrap();
+        assert_eq!(json["kind"], "other");
+        assert_eq!(json["message"], "Repository is not opened");
+        assert_eq!(json["exitCode"], serde_json::Value::Null);
+    }
 }
@@ -15,6 +15,7 @@ const configAtom = atom<Config>({
   avatarShape: "square",
   useGravatar: true,
   logLevel: "info",
+  gitBackend: "cli",
   customCommands: [],
   customFileCommands: []
 });
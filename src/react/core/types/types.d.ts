@@ -24,20 +24,30 @@ declare global {
 
   type Commit = import("@backend/Commit").Commit;
   type WorkingTreeFileKind = import("@backend/WorkingTreeFileKind").WorkingTreeFileKind;
+  type SubmoduleChange = import("@backend/SubmoduleChange").SubmoduleChange;
   type FileEntry = import("@backend/FileEntry").FileEntry & {
     kind?: WorkingTreeFileKind;
+    submodule?: SubmoduleChange;
+    quotedPath?: string;
   };
   type WorkingTreeFileEntry = import("@backend/WorkingTreeFileEntry").WorkingTreeFileEntry;
   type LstreeEntry = import("@backend/LstreeEntry").LstreeEntry;
   type LstreeData = import("@backend/LstreeData").LstreeData;
   type CommitDetail = import("@backend/CommitDetail").CommitDetail;
+  type Trailer = import("@backend/Trailer").Trailer;
+  type CommitSignature = import("@backend/CommitSignature").CommitSignature;
+  type SignatureStatus = import("@backend/SignatureStatus").SignatureStatus;
   type CommitOptions = import("@backend/CommitOptions").CommitOptions;
+  type CommitSigning = import("@backend/CommitSigning").CommitSigning;
+  type CommitFlags = import("@backend/CommitFlags").CommitFlags;
   type CreateBranchOptions = import("@backend/CreateBranchOptions").CreateBranchOptions;
   type DeleteBranchOptions = import("@backend/DeleteBranchOptions").DeleteBranchOptions;
   type SwitchOptions = import("@backend/SwitchOptions").SwitchOptions;
   type FileSpec = import("@backend/FileSpec").FileSpec;
   type RawRefs = import("@backend/Refs").Refs;
   type Ref = import("@backend/Ref").Ref;
+  type Worktree = import("@backend/Worktree").Worktree;
+  type Submodule = import("@backend/Submodule").Submodule;
   type FontSize = import("@backend/FontSize").FontSize;
   type FontFamily = import("@backend/FontFamily").FontFamily;
   type Environment = import("@backend/Environment").Environment;
@@ -48,6 +58,8 @@ declare global {
   type TagRef = Extract<Ref, { type: "tag" }>;
   type RemoteRef = Extract<Ref, { type: "remote" }>;
   type ReflogRef = Extract<Ref, { type: "reflog" }>;
+  type StashRef = Extract<Ref, { type: "stash" }>;
+  type BisectRef = Extract<Ref, { type: "bisect" }>;
 
   interface Refs {
     head?: string;
@@ -56,6 +68,7 @@ declare global {
     tags: TagRef[];
     remotes: Record<string, RemoteRef[]>;
     refsById: Record<string, Ref[]>;
+    worktrees: Worktree[];
   }
 
   interface DagNode {
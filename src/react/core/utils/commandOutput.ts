@@ -0,0 +1,24 @@
+import { listen } from "@tauri-apps/api/event";
+
+let currentCommandId = 0;
+
+/**
+ * Runs a tauri command which emits its output as `command-output:{id}` events
+ * (e.g. commit, merge and push, including the output of hooks),
+ * and passes each line to `onOutput` while running.
+ */
+export const withCommandOutput = async <T>(
+  run: (id: number) => Promise<T>,
+  onOutput: (line: string) => void
+): Promise<T> => {
+  currentCommandId += 1;
+  const id = currentCommandId;
+  const unlisten = await listen<string>(`command-output:${id}`, ({ payload }) =>
+    onOutput(payload)
+  );
+  try {
+    return await run(id);
+  } finally {
+    unlisten();
+  }
+};
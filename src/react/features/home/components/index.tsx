@@ -8,12 +8,14 @@ import {
 } from "@/core/state/root";
 import { invokeTauriCommand } from "@/core/utils/invokeTauriCommand";
 import { useBeginClone } from "@/features/home/hooks/actions/clone";
+import { useBeginInit } from "@/features/home/hooks/actions/init";
 import { useOpenRepository } from "@/features/repository/hooks/actions/openRepository";
 import { Cmd, CommandGroup } from "@/shared/components/ui/CommandGroup";
 import { RepositoryListItem, type RepositoryListItemProps } from "./RepositoryListItem";
 
 const Home: React.FC<{ active: boolean }> = ({ active }) => {
   const beginClone = useBeginClone();
+  const beginInit = useBeginInit();
   const openRepository = useOpenRepository();
   const recentOpenedRepositories = useVisibleRecentOpenedRepositoriesValue();
   const handleOpen = useCallback(
@@ -72,6 +74,14 @@ const Home: React.FC<{ active: boolean }> = ({ active }) => {
               icon="mdi:download"
               action={beginClone}
             />
+            <RepositoryListItem
+              itemId="__init__"
+              key="__init__"
+              primary="INIT..."
+              secondary="Create empty repository in the selected folder"
+              icon="mdi:folder-plus-outline"
+              action={beginInit}
+            />
           </List>
           <Divider className="mb-2" />
           <Typography variant="h6" color="primary">
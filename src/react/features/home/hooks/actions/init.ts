@@ -0,0 +1,19 @@
+import { invokeTauriCommand } from "@/core/utils/invokeTauriCommand";
+import { useOpenRepository } from "@/features/repository/hooks/actions/openRepository";
+import { useCallbackWithErrorHandler } from "@/shared/hooks/utils/useCallbackWithErrorHandler";
+import { useWithRef } from "@/shared/hooks/utils/useWithRef";
+
+/**
+ * Creates an empty repository in the folder selected by the folder selector, and opens it.
+ */
+export const useBeginInit = () => {
+  const [, openRepositoryRef] = useWithRef(useOpenRepository());
+  return useCallbackWithErrorHandler(async () => {
+    const path = await invokeTauriCommand("show_folder_selector");
+    if (!path) {
+      return;
+    }
+    const repoPath = await invokeTauriCommand("init_repository", { options: { path } });
+    await openRepositoryRef.current?.(repoPath);
+  }, []);
+};
@@ -112,6 +112,14 @@ export const GeneralTab: React.FC<TabContentProps> = ({ config, dispatch }) => {
           value={config.externalDiffTool}
           onChange={({ target }) => dispatch({ type: "externalDiff", payload: target.value })}
         />
+        <TextField
+          /* eslint-disable-next-line no-template-curly-in-string */
+          label="External merge tool (${base}, ${local}, ${remote} and ${merged} will be replaced with file path)"
+          margin="dense"
+          variant="standard"
+          value={config.externalMergeTool}
+          onChange={({ target }) => dispatch({ type: "externalMerge", payload: target.value })}
+        />
         <TextField
           label="Interactive shell"
           margin="dense"
@@ -170,6 +178,17 @@ export const GeneralTab: React.FC<TabContentProps> = ({ config, dispatch }) => {
             <FormControlLabel value="trace" control={<Radio />} label="trace" />
           </RadioGroup>
         </div>
+        <div className="flex-col-nowrap mt-4">
+          <FormLabel>Git backend (for reading repositories)</FormLabel>
+          <RadioGroup
+            row
+            value={config.gitBackend}
+            onChange={({ target }) => dispatch({ type: "gitBackend", payload: target.value })}
+          >
+            <FormControlLabel value="cli" control={<Radio />} label="git command" />
+            <FormControlLabel value="gitoxide" control={<Radio />} label="gitoxide (in-process)" />
+          </RadioGroup>
+        </div>
       </SectionContent>
     </div>
   );
@@ -56,6 +56,9 @@ const reducer = (state: PreferenceState, action: PreferenceAction): PreferenceSt
       case "externalDiff":
         newConfig.externalDiffTool = value;
         break;
+      case "externalMerge":
+        newConfig.externalMergeTool = value;
+        break;
       case "interactiveShell":
         newConfig.interactiveShell = value;
         break;
@@ -82,6 +85,9 @@ const reducer = (state: PreferenceState, action: PreferenceAction): PreferenceSt
           newConfig.logLevel = value;
         }
         break;
+      case "gitBackend":
+        newConfig.gitBackend = value === "gitoxide" ? "gitoxide" : "cli";
+        break;
       default:
         assertNever(action);
     }
@@ -14,10 +14,12 @@ export type PreferenceAction =
         | "fontFamilyMonospace"
         | "fontSize"
         | "externalDiff"
+        | "externalMerge"
         | "interactiveShell"
         | "recentListCount"
         | "avatarShape"
-        | "logLevel";
+        | "logLevel"
+        | "gitBackend";
       payload: string | null | undefined;
     }
   | {
@@ -0,0 +1,20 @@
+import { useMemo } from "react";
+import { useCreateFixupCommit } from "@/features/repository/hooks/actions/workingtree";
+import type { CommitCommand } from "./types";
+
+export const useCreateFixupCommitCommand = () => {
+  const createFixupCommit = useCreateFixupCommit();
+  return useMemo<CommitCommand>(
+    () => ({
+      type: "commit",
+      id: "CreateFixupCommit",
+      label: "Create fixup commit",
+      icon: "mdi:source-commit-start-next-local",
+      hidden: (commit) => commit.id === "--",
+      handler: (commit) => {
+        void createFixupCommit(commit);
+      }
+    }),
+    [createFixupCommit]
+  );
+};
@@ -3,12 +3,14 @@ import { useBrowseSourceTreeCommand } from "./browseSourceTree";
 import { useCopyFullHashCommand, useCopyShortHashCommand } from "./copyHash";
 import { useCopyRelativePathCommand } from "./copyRelativePath";
 import { useCreateBranchCommand } from "./createBranch";
+import { useCreateFixupCommitCommand } from "./createFixupCommit";
 import {
   useDiffUnstagedCommand,
   useDiffWithLocalCommand,
   useDiffWithParent2Command,
   useDiffWithParentCommand
 } from "./diff";
+import { useOpenSubmoduleCommand } from "./openSubmodule";
 import { useShowFileContentCommand } from "./showFileContent";
 import type { ActionItem, CommitCommand, FileCommand } from "./types";
 import { useStageCommand, useUnstageCommand } from "./workingtree";
@@ -18,9 +20,10 @@ export const useCommitCommands = () => {
   const copyShortHash = useCopyShortHashCommand();
   const browseSourceTree = useBrowseSourceTreeCommand();
   const createBranch = useCreateBranchCommand();
+  const createFixupCommit = useCreateFixupCommitCommand();
   return useMemo<CommitCommand[]>(
-    () => [copyFullHash, copyShortHash, browseSourceTree, createBranch],
-    [copyFullHash, copyShortHash, browseSourceTree, createBranch]
+    () => [copyFullHash, copyShortHash, browseSourceTree, createBranch, createFixupCommit],
+    [copyFullHash, copyShortHash, browseSourceTree, createBranch, createFixupCommit]
   );
 };
 
@@ -33,6 +36,7 @@ export const useFileCommands = () => {
   const diffWithLocal = useDiffWithLocalCommand();
   const diffUnstaged = useDiffUnstagedCommand();
   const showFileContent = useShowFileContentCommand();
+  const openSubmodule = useOpenSubmoduleCommand();
   return useMemo(
     () => [
       copyRelativePath,
@@ -42,7 +46,8 @@ export const useFileCommands = () => {
       diffWithParent2,
       diffWithLocal,
       diffUnstaged,
-      showFileContent
+      showFileContent,
+      openSubmodule
     ],
     [
       copyRelativePath,
@@ -52,7 +57,8 @@ export const useFileCommands = () => {
       diffWithParent2,
       diffWithLocal,
       diffUnstaged,
-      showFileContent
+      showFileContent,
+      openSubmodule
     ]
   );
 };
@@ -65,13 +71,22 @@ export const executeCommitCommand = (command: CommitCommand, commit: Commit) =>
   return true;
 };
 
+// paths which are not valid UTF-8 are decoded lossily, and only `quotedPath` can be passed to git
+const isFileCommandDisabled = (
+  command: CommitCommand | FileCommand,
+  commit: Commit,
+  file: FileEntry
+) =>
+  (command.type === "file" && file.quotedPath !== undefined && !command.acceptsQuotedPath) ||
+  !!command.disabled?.(commit, file);
+
 export const executeCommand = (
   command: CommitCommand | FileCommand,
   commit: Commit,
   file: FileEntry,
   localPath?: string
 ) => {
-  if (command.hidden?.(commit, file) || command.disabled?.(commit, file)) {
+  if (command.hidden?.(commit, file) || isFileCommandDisabled(command, commit, file)) {
     return false;
   }
   if (command.type === "file") {
@@ -122,7 +137,7 @@ export const commandsToActions = (
     .filter((c) => !c.hidden?.(commit, file))
     .map((c) => {
       const { id, label, icon } = c;
-      const disabled = c.disabled?.(commit, file);
+      const disabled = isFileCommandDisabled(c, commit, file);
       const handler = () => executeCommand(c, commit, file, localPath);
       return { id, label, icon, disabled, handler };
     });
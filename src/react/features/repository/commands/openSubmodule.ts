@@ -0,0 +1,20 @@
+import { useMemo } from "react";
+import { useOpenSubmodule } from "@/features/repository/hooks/actions/openRepository";
+import type { FileCommand } from "./types";
+
+export const useOpenSubmoduleCommand = () => {
+  const openSubmodule = useOpenSubmodule();
+  return useMemo<FileCommand>(
+    () => ({
+      type: "file",
+      id: "OpenSubmodule",
+      label: "Open submodule as repository",
+      icon: "octicon:file-submodule-16",
+      hidden: (commit, file) => commit.id !== "--" || !file.submodule,
+      handler(_, file) {
+        void openSubmodule(file.path);
+      }
+    }),
+    [openSubmodule]
+  );
+};
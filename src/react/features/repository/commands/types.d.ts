@@ -22,4 +22,6 @@ export interface FileCommand {
   handler(commit: Commit, file: FileEntry, localPath: string): unknown;
   hidden?(commit: Commit, file: FileEntry): boolean;
   disabled?(commit: Commit, file: FileEntry): boolean;
+  // true if the handler passes `file.quotedPath` to git instead of `file.path` if it is set
+  acceptsQuotedPath?: boolean;
 }
@@ -13,8 +13,9 @@ export const useStageCommand = () => {
       hidden: (commit, file) => {
         return commit.id !== "--" || file.kind?.type === "staged";
       },
+      acceptsQuotedPath: true,
       handler(_, file) {
-        void stage([file.path]);
+        void stage([file.quotedPath ?? file.path]);
       }
     }),
     [stage]
@@ -32,8 +33,9 @@ export const useUnstageCommand = () => {
       hidden: (commit, file) => {
         return commit.id !== "--" || file.kind?.type !== "staged";
       },
+      acceptsQuotedPath: true,
       handler(_, file) {
-        void unstage([file.path]);
+        void unstage([file.quotedPath ?? file.path]);
       }
     }),
     [unstage]
@@ -51,8 +53,9 @@ export const useRestoreCommand = () => {
       hidden: (commit, file) => {
         return commit.id !== "--" || file.kind?.type !== "unstaged" || file.statusCode === "?";
       },
+      acceptsQuotedPath: true,
       handler(_, file) {
-        void restore([file.path]);
+        void restore([file.quotedPath ?? file.path]);
       }
     }),
     [restore]
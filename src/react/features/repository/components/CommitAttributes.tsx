@@ -1,7 +1,14 @@
+import { Button } from "@mui/material";
+import { useAtomValue } from "jotai";
+import { useMemo, useState } from "react";
 import { useConfigValue } from "@/core/state/root";
 import type { IconName } from "@/core/types/IconName";
+import { invokeTauriCommand } from "@/core/utils/invokeTauriCommand";
+import { repoPathAtom } from "@/features/repository/state";
 import { GitHash } from "@/shared/components/ui/GitHash";
 import { Icon } from "@/shared/components/ui/Icon";
+import { useCallbackWithErrorHandler } from "@/shared/hooks/utils/useCallbackWithErrorHandler";
+import { usePromise } from "@/shared/hooks/utils/usePromise";
 import { formatDateTimeLong } from "@/shared/utils/date";
 import { Avatar } from "./Avatar";
 
@@ -50,3 +57,107 @@ export const CommitAttributes: React.FC<{ commit: Commit; showSummary?: boolean
     </div>
   );
 };
+
+const signatureLabels: Record<SignatureStatus, string> = {
+  good: "Good signature",
+  bad: "Bad signature",
+  unknownValidity: "Good signature with unknown validity",
+  expired: "Good signature, expired",
+  expiredKey: "Good signature made by expired key",
+  revokedKey: "Good signature made by revoked key",
+  cannotCheck: "Signature cannot be checked"
+};
+
+const signatureIcon = (status: SignatureStatus): IconName => {
+  switch (status) {
+    case "good":
+      return "mdi:shield-check";
+    case "bad":
+      return "mdi:shield-remove";
+    default:
+      return "mdi:shield-alert";
+  }
+};
+
+const ContainingRefs: React.FC<{ commitId: string }> = ({ commitId }) => {
+  const repoPath = useAtomValue(repoPathAtom);
+  const [refs, setRefs] = useState<{ commitId: string; names: string[] } | undefined>(undefined);
+  // may take time in large repositories, so refs are loaded only when requested
+  const loadRefs = useCallbackWithErrorHandler(async () => {
+    const names = await invokeTauriCommand("get_refs_containing", { repoPath, commitId });
+    setRefs({ commitId, names });
+  }, [repoPath, commitId]);
+  const names = refs?.commitId === commitId ? refs.names : undefined;
+  return (
+    <div className="grid grid-cols-[auto_1fr]" title="Branches and tags containing this commit">
+      <AttrIcon icon="mdi:source-branch" />
+      {names ? (
+        <span className="ellipsis" title={names.join("\n")}>
+          {names.length > 0 ? names.join(", ") : "(none)"}
+        </span>
+      ) : (
+        <Button
+          className="justify-start p-0 min-w-0 normal-case"
+          size="small"
+          color="inherit"
+          onClick={loadRefs}
+        >
+          Show branches and tags containing this commit
+        </Button>
+      )}
+    </div>
+  );
+};
+
+export const CommitDetailAttributes: React.FC<{ commit: CommitDetail }> = ({ commit }) => {
+  const repoPath = useAtomValue(repoPathAtom);
+  // rebased or amended commits have committer/commit date different from author/author date
+  const showCommitter =
+    commit.committer !== commit.author ||
+    commit.committerMailAddress !== commit.mailAddress ||
+    commit.commitDate !== commit.date;
+  // verified after the detail is shown, since gpg or ssh-keygen is run for signed commits
+  const signaturePromise = useMemo(
+    () =>
+      invokeTauriCommand("get_commit_signature", { repoPath, revspec: commit.id })
+        .then((signature) => ({ commitId: commit.id, signature }))
+        .catch(() => undefined),
+    [repoPath, commit.id]
+  );
+  const loaded = usePromise(signaturePromise, undefined);
+  // ignore the result for the previously selected commit
+  const signature = loaded?.commitId === commit.id ? loaded.signature : undefined;
+  return (
+    <div className="grid pl-18 overflow-hidden text-base">
+      {showCommitter && (
+        <div className="grid grid-cols-[auto_auto_auto_auto_1fr]" title="Committer">
+          <AttrIcon icon="mdi:account-edit" />
+          <span className="mr-12 ellipsis" title={commit.committerMailAddress}>
+            {commit.committer}
+          </span>
+          <AttrIcon icon="mdi:clock-edit-outline" />
+          <span className="ellipsis">{formatDateTimeLong(commit.commitDate)}</span>
+        </div>
+      )}
+      {signature && (
+        <div className="grid grid-cols-[auto_1fr]">
+          <AttrIcon icon={signatureIcon(signature.status)} />
+          <span className="ellipsis" title={signature.key}>
+            {signatureLabels[signature.status]}
+            {signature.signer && ` by ${signature.signer}`}
+          </span>
+        </div>
+      )}
+      <ContainingRefs commitId={commit.id} />
+      {commit.trailers.map((t, i) => (
+        <div key={i} className="grid grid-cols-[auto_auto_1fr]">
+          <AttrIcon icon="mdi:tag-text-outline" />
+          <span className="mr-2 text-greytext">{t.key}:</span>
+          <span className="ellipsis" title={t.value}>
+            {t.value}
+          </span>
+        </div>
+      ))}
+    </div>
+  );
+};
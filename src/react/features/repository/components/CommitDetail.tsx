@@ -6,9 +6,10 @@ import { useShowFileContentCommand } from "@/features/repository/commands/showFi
 import { useShowLsTree } from "@/features/repository/hooks/showLsTree";
 import { useFileContextMenu } from "@/features/repository/hooks/useContextMenu";
 import { FlexCard } from "@/shared/components/ui/FlexCard";
+import { Icon } from "@/shared/components/ui/Icon";
 import { PersistSplitterPanel } from "@/shared/components/ui/layout/PersistSplitterPanel";
 import { usePersistState } from "@/shared/hooks/utils/usePersistState";
-import { CommitAttributes } from "./CommitAttributes";
+import { CommitAttributes, CommitDetailAttributes } from "./CommitAttributes";
 import { FileList, type FileListViewType, fixView, useFileListRowEventHandler } from "./FileList";
 import { NumStat } from "./NumStat";
 import { RefBadge } from "./RefBadge";
@@ -33,6 +34,7 @@ const CommitMetadataInner: React.FC<CommitDetailProps> = ({ commit, refs }) => {
     <>
       <div className="flex-col-wrap p-2 mb-2 border-b border-greytext">
         <CommitAttributes commit={commit} />
+        <CommitDetailAttributes commit={commit} />
       </div>
       {refs && refs.length > 0 && (
         <div className="flex-row-wrap mx-2 mb-2">
@@ -46,6 +48,17 @@ const CommitMetadataInner: React.FC<CommitDetailProps> = ({ commit, refs }) => {
           {commit.body}
         </pre>
       )}
+      {commit.notes && (
+        <div className="m-1">
+          <div className="flex-row-nowrap text-greytext">
+            <Icon className="mr-1 my-auto" icon="mdi:note-text-outline" />
+            Notes
+          </div>
+          <pre className="p-2 text-lg whitespace-pre-wrap overflow-auto font-normal bg-tooltip text-greytext">
+            {commit.notes}
+          </pre>
+        </div>
+      )}
     </>
   );
   return (
@@ -141,6 +141,12 @@ const CommitListRow_: React.FC<CommitListRowProps> = ({
               {commit.author}
               <Icon className="ml-3 mr-0.5 my-auto flex-none" icon="mdi:clock-outline" />
               {formatDateTimeLong(commit.date)}
+              {commit.commitDate !== commit.date && (
+                <span className="flex-row-nowrap" title="Commit date">
+                  <Icon className="ml-3 mr-0.5 my-auto flex-none" icon="mdi:clock-edit-outline" />
+                  {formatDateTimeLong(commit.commitDate)}
+                </span>
+              )}
             </>
           )}
         </div>
@@ -45,8 +45,8 @@ const BranchActionButtons: React.FC<{
     <IconButton
       size="small"
       edge="end"
-      title="Delete this branch"
-      disabled={r.current}
+      title={r.worktree ? `Checked out in ${r.worktree}` : "Delete this branch"}
+      disabled={r.current || !!r.worktree}
       className={classNames("hidden", { "group-hover:block": !r.current })}
       data-fullname={r.fullname}
       onClick={deleteAction}
@@ -56,8 +56,8 @@ const BranchActionButtons: React.FC<{
     <IconButton
       size="small"
       edge="end"
-      title="Switch to this branch"
-      disabled={r.current}
+      title={r.worktree ? `Checked out in ${r.worktree}` : "Switch to this branch"}
+      disabled={r.current || !!r.worktree}
       className={classNames("group-hover:block", {
         "text-secondary": r.current,
         hidden: !r.current
@@ -132,7 +132,7 @@ export const CommitLogSideBar: React.FC<{
       e.stopPropagation();
       const fullname = e.currentTarget.dataset.fullname!;
       const r = refMap[fullname];
-      if (r && r.type === "branch" && !r.current) {
+      if (r && r.type === "branch" && !r.current && !r.worktree) {
         void deleteBranch(r.name);
       }
     },
@@ -11,6 +11,15 @@ const conflictTypes: Record<string, string> = {
   UD: "deleted by them"
 };
 
+const getSubmoduleChangeText = (change: SubmoduleChange) =>
+  [
+    change.commitChanged && "new commits",
+    change.modified && "modified content",
+    change.untracked && "untracked content"
+  ]
+    .filter((v) => v)
+    .join(", ");
+
 export const FileStat: React.FC<{ file: FileEntry; className?: string }> = ({
   file,
   className
@@ -20,9 +29,11 @@ export const FileStat: React.FC<{ file: FileEntry; className?: string }> = ({
       <span className="font-bold text-greytext mr-1 uppercase">
         {file.kind?.type === "unmerged"
           ? `conflict (${conflictTypes[file.kind.conflict_type]})`
-          : (file.delta?.type ?? "unknown")}
+          : file.submodule
+            ? `submodule (${getSubmoduleChangeText(file.submodule)})`
+            : (file.delta?.type ?? "unknown")}
       </span>
-      {file.delta?.type === "text" && (
+      {!file.submodule && file.delta?.type === "text" && (
         <>
           <span className="mr-1 text-[lightgreen]">+{file.delta.insertions}</span>
           <span className="mr-1 text-[hotpink]">-{file.delta.deletions}</span>
@@ -21,14 +21,19 @@ const LsTreeRow: React.FC<{
 }> = ({ item, index, getRowClass }) => {
   const selectedIndex = useSelectedIndex();
   const isFolder = item.data.type === "tree";
-  const icon = getFileIcon(item.data.path, isFolder);
+  const icon =
+    item.data.type === "submodule"
+      ? "octicon:file-submodule-16"
+      : getFileIcon(item.data.path, isFolder);
   return (
     <div
       className={classNames(
         "flex-1 min-h-full flex items-center px-2 cursor-default whitespace-nowrap overflow-hidden hover:bg-hover-highlight",
         index === selectedIndex && "bg-highlight",
-        getRowClass?.(item.data)
+        getRowClass?.(item.data),
+        item.quotedPath && "text-greytext"
       )}
+      title={item.quotedPath ? "The path is not valid UTF-8 and can't be opened" : undefined}
     >
       <span className="mr-2 flex items-center text-greytext" style={{ fontSize: "16px" }}>
         <Icon icon={icon} />
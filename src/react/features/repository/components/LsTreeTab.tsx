@@ -1,6 +1,7 @@
 import { IconButton } from "@mui/material";
 import classNames from "classnames";
 import { useCallback, useMemo, useRef, useState } from "react";
+import { useOpenSubmodule } from "@/features/repository/hooks/actions/openRepository";
 import { FlexCard } from "@/shared/components/ui/FlexCard";
 import { Icon } from "@/shared/components/ui/Icon";
 import { PersistSplitterPanel } from "@/shared/components/ui/layout/PersistSplitterPanel";
@@ -28,6 +29,7 @@ const LsTreeWithFilter: React.FC<{
   onUpdateBlamePath: (value: string | undefined) => void;
 }> = ({ orientation, commit, entries, blamePath, onUpdateBlamePath }) => {
   const [filterText, setFilterText] = useState("");
+  const openSubmodule = useOpenSubmodule();
   const filteredEntries = useMemo(() => {
     if (!filterText) {
       return entries;
@@ -53,19 +55,25 @@ const LsTreeWithFilter: React.FC<{
   const handleRowDoubleClick = useCallback(
     (event: React.MouseEvent, _index: number, { item }: TreeItemVM<LstreeData>) => {
       if (event.button === 0) {
+        if ((item as LstreeEntry).quotedPath && item.data.type !== "tree") {
+          return;
+        }
         if (item.data.type === "blob") {
           onUpdateBlamePath(item.data.path);
+        } else if (item.data.type === "submodule") {
+          void openSubmodule(item.data.path);
         } else {
           dispatchRef.current?.({ type: "toggleItem", payload: { item } });
         }
       }
     },
-    [onUpdateBlamePath]
+    [onUpdateBlamePath, openSubmodule]
   );
 
   const selectedData = useRef<LstreeData | undefined>(undefined);
   const handleSelectionChange = useCallback((_: number, item: TreeItem<LstreeData> | undefined) => {
-    selectedData.current = item?.data;
+    // paths which are not valid UTF-8 can't be opened
+    selectedData.current = (item as LstreeEntry | undefined)?.quotedPath ? undefined : item?.data;
   }, []);
 
   const handleKeyDown = useCallback(
@@ -74,10 +82,12 @@ const LsTreeWithFilter: React.FC<{
       if (event.key === "Enter") {
         if (selectedData.current?.type === "blob") {
           onUpdateBlamePath(selectedData.current.path);
+        } else if (selectedData.current?.type === "submodule") {
+          void openSubmodule(selectedData.current.path);
         }
       }
     },
-    [onUpdateBlamePath]
+    [onUpdateBlamePath, openSubmodule]
   );
   const content = (
     <div
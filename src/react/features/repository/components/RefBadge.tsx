@@ -42,6 +42,13 @@ const Branch: React.FC<{ r: BranchRef }> = ({ r }) => {
       onDragStart={handleDragStart}
     >
       {r.name}
+      {r.worktree && (
+        <span className="ml-1" title={`Checked out in ${r.worktree}`}>
+          ⎇
+        </span>
+      )}
+      {0 < r.ahead && <span className="ml-1">{`↑${r.ahead}`}</span>}
+      {0 < r.behind && <span className="ml-1">{`↓${r.behind}`}</span>}
     </Badge>
   );
 };
@@ -58,6 +65,27 @@ const Reflog: React.FC<{ r: ReflogRef }> = ({ r }) => (
   <Badge className="text-[grey] border border-current">{r.name}</Badge>
 );
 
+const Stash: React.FC<{ r: StashRef }> = ({ r }) => (
+  <Badge className="text-[grey] border border-dashed border-current">{r.name}</Badge>
+);
+
+const bisectVerdictClasses: Record<BisectRef["verdict"], string> = {
+  bad: "text-[hotpink]",
+  good: "text-[lightgreen]",
+  skip: "text-[grey]"
+};
+
+const Bisect: React.FC<{ r: BisectRef }> = ({ r }) => (
+  <Badge
+    className={classNames(
+      "border border-dotted border-current font-bold",
+      bisectVerdictClasses[r.verdict]
+    )}
+  >
+    {`bisect:${r.name}`}
+  </Badge>
+);
+
 export const RefBadge: React.FC<{ r: Ref }> = ({ r }) => {
   switch (r.type) {
     case "branch":
@@ -68,6 +96,10 @@ export const RefBadge: React.FC<{ r: Ref }> = ({ r }) => {
       return <Remote r={r} />;
     case "reflog":
       return <Reflog r={r} />;
+    case "stash":
+      return <Stash r={r} />;
+    case "bisect":
+      return <Bisect r={r} />;
     default:
       return assertNever(r);
   }
@@ -13,6 +13,7 @@ import {
   useDiffWithParent2Command,
   useDiffWithParentCommand
 } from "@/features/repository/commands/diff";
+import { useOpenSubmoduleCommand } from "@/features/repository/commands/openSubmodule";
 import type { IconActionItem } from "@/features/repository/commands/types";
 import {
   useRestoreCommand,
@@ -76,6 +77,10 @@ const getUdiff = async (repoPath: string, relPath: string, cached: boolean): Pro
   }
 };
 
+// paths which are not valid UTF-8 are decoded lossily, and only `quotedPath` can be passed to git
+const gitFilePaths = (files: readonly WorkingTreeFileEntry[]) =>
+  files.map((f) => f.quotedPath ?? f.path);
+
 const GroupHeader: React.FC<{
   header: HeaderRowType;
   index: number;
@@ -104,14 +109,14 @@ const GroupHeader: React.FC<{
           icon: "mdi:undo",
           handler: () =>
             void restore(
-              visibleFilesRef.current.filter((f) => f.statusCode !== "?").map((f) => f.path)
+              gitFilePaths(visibleFilesRef.current.filter((f) => f.statusCode !== "?"))
             )
         },
         {
           id: "StageAll",
           label: "Stage all files",
           icon: "mdi:plus",
-          handler: () => void stage(visibleFilesRef.current.map((f) => f.path))
+          handler: () => void stage(gitFilePaths(visibleFilesRef.current))
         }
       ];
     } else if (header.headerType === "staged") {
@@ -120,7 +125,7 @@ const GroupHeader: React.FC<{
           id: "UnstageAll",
           label: "Unstage all files",
           icon: "mdi:minus",
-          handler: () => void unstage(visibleFilesRef.current.map((f) => f.path))
+          handler: () => void unstage(gitFilePaths(visibleFilesRef.current))
         }
       ];
     } else {
@@ -249,9 +254,11 @@ export const WorkingTree: React.FC<WorkingTreeProps> = ({ stat, orientation }) =
   const diffWithParent2 = useDiffWithParent2Command();
   const stage = useStageCommand();
   const unstage = useUnstageCommand();
+  const openSubmodule = useOpenSubmoduleCommand();
 
   const actionCommands = useMemo(
     () => [
+      openSubmodule,
       copyRelativePath,
       restore,
       diffUnstaged,
@@ -260,7 +267,16 @@ export const WorkingTree: React.FC<WorkingTreeProps> = ({ stat, orientation }) =
       stage,
       unstage
     ],
-    [copyRelativePath, restore, diffUnstaged, diffWithParent, diffWithParent2, stage, unstage]
+    [
+      openSubmodule,
+      copyRelativePath,
+      restore,
+      diffUnstaged,
+      diffWithParent,
+      diffWithParent2,
+      stage,
+      unstage
+    ]
   );
   const { reportError } = useAlert();
 
@@ -277,7 +293,11 @@ export const WorkingTree: React.FC<WorkingTreeProps> = ({ stat, orientation }) =
           setUdiff(undefined);
         } else {
           try {
-            const udiff = await getUdiff(repoPath, data.path, data.kind.type === "staged");
+            const udiff = await getUdiff(
+              repoPath,
+              data.quotedPath ?? data.path,
+              data.kind.type === "staged"
+            );
             setUdiff(udiff);
           } catch (error) {
             reportError({ error });
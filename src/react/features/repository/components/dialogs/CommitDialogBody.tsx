@@ -1,7 +1,8 @@
-import { TextField } from "@mui/material";
+import { NativeSelect, TextField } from "@mui/material";
 import { useAtomValue } from "jotai";
 import { useCallback, useEffect, useRef, useState } from "react";
 import { useAlert } from "@/core/context/AlertContext";
+import { invokeTauriCommand } from "@/core/utils/invokeTauriCommand";
 import { useCommit } from "@/features/repository/hooks/actions/workingtree";
 import { repoPathAtom } from "@/features/repository/state";
 import {
@@ -12,15 +13,40 @@ import {
   DialogTitle,
   LabelledCheckBox
 } from "@/shared/components/ui/Dialog";
+import { Icon } from "@/shared/components/ui/Icon";
 import { useTauriQueryInvoke } from "@/shared/hooks/integration/useTauriQuery";
 import { clamp } from "@/shared/utils/util";
 
+type SigningChoice = CommitSigning | "default";
+
+const signingLabels: Record<CommitSigning, string> = {
+  gpg: "GPG",
+  ssh: "SSH",
+  none: "Don't sign"
+};
+
 export const CommitDialogBody: React.FC = () => {
   const repoPath = useAtomValue(repoPathAtom);
   const messageRef = useRef<HTMLInputElement | null>(null);
   const amendRef = useRef<HTMLInputElement | null>(null);
+  const authorRef = useRef<HTMLInputElement | null>(null);
+  const authorDateRef = useRef<HTMLInputElement | null>(null);
+  const resetAuthorRef = useRef<HTMLInputElement | null>(null);
+  const signoffRef = useRef<HTMLInputElement | null>(null);
+  const noVerifyRef = useRef<HTMLInputElement | null>(null);
+  const allowEmptyRef = useRef<HTMLInputElement | null>(null);
+  const xtermRef = useRef<HTMLDivElement>(null);
+  const outputRef = useRef<HTMLPreElement>(null);
   const { reportError } = useAlert();
   const [rows, setRows] = useState(6);
+  const [amend, setAmend] = useState(false);
+  const [signing, setSigning] = useState<SigningChoice>("default");
+  // signing method determined by commit.gpgSign and gpg.format
+  const [defaultSigning, setDefaultSigning] = useState<CommitSigning>("none");
+  // output of git (including pre-commit and commit-msg hooks) while committing
+  const [output, setOutput] = useState<string[]>([]);
+  const [running, setRunning] = useState(false);
+  const effectiveSigning = signing === "default" ? defaultSigning : signing;
 
   const fetchTauriQuery = useTauriQueryInvoke();
 
@@ -30,18 +56,49 @@ export const CommitDialogBody: React.FC = () => {
     if (!messageRef.current) {
       return "failed";
     }
-    const opt: CommitOptions = {
-      commitType: amendRef.current?.checked ? "amend" : "normal",
-      message: messageRef.current.value ?? ""
+    const message = messageRef.current.value ?? "";
+    const flags: CommitFlags = {
+      signing: signing === "default" ? undefined : signing,
+      author: authorRef.current?.value || undefined,
+      authorDate: authorDateRef.current?.value || undefined,
+      signoff: signoffRef.current?.checked,
+      noVerify: noVerifyRef.current?.checked,
+      allowEmpty: allowEmptyRef.current?.checked
     };
-    return await commit(opt);
-  }, [commit]);
+    const opt: CommitOptions = amendRef.current?.checked
+      ? { commitType: "amend", message, resetAuthor: resetAuthorRef.current?.checked, ...flags }
+      : { commitType: "normal", message, ...flags };
+    // the passphrase of the signing key is entered in the terminal
+    const xtermEl = effectiveSigning !== "none" ? (xtermRef.current ?? undefined) : undefined;
+    setOutput([]);
+    setRunning(true);
+    try {
+      return await commit(opt, xtermEl, (line) => setOutput((prev) => [...prev, line]));
+    } finally {
+      setRunning(false);
+    }
+  }, [commit, signing, effectiveSigning]);
+
+  useEffect(() => {
+    if (output.length > 0 && outputRef.current) {
+      outputRef.current.scrollTop = outputRef.current.scrollHeight;
+    }
+  }, [output]);
 
   useEffect(() => {
     setRows(6);
     setTimeout(() => messageRef.current?.focus(), 0);
   }, []);
 
+  useEffect(() => {
+    if (!repoPath) {
+      return;
+    }
+    invokeTauriCommand("get_commit_signing", { repoPath })
+      .then(setDefaultSigning)
+      .catch((error: unknown) => reportError({ error }));
+  }, [repoPath, reportError]);
+
   const handleChange = useCallback(() => {
     if (messageRef.current) {
       setRows(clamp(messageRef.current.value.split(/\n/g).length, 6, 24));
@@ -52,6 +109,7 @@ export const CommitDialogBody: React.FC = () => {
       if (!repoPath) {
         throw new Error("repoPath is empty!");
       }
+      setAmend(e.target.checked);
       if (!messageRef.current || !e.target.checked) {
         return;
       }
@@ -87,16 +145,72 @@ export const CommitDialogBody: React.FC = () => {
               inputLabel: { shrink: true }
             }}
           />
-          <LabelledCheckBox
-            label="Amend last commit"
-            inputRef={amendRef}
-            onChange={handleAmendChange as VoidReturn<typeof handleAmendChange>}
-          />
+          <div className="flex-row-wrap">
+            <LabelledCheckBox
+              label="Amend last commit"
+              inputRef={amendRef}
+              onChange={handleAmendChange as VoidReturn<typeof handleAmendChange>}
+            />
+            {amend && <LabelledCheckBox label="Reset author" inputRef={resetAuthorRef} />}
+            <LabelledCheckBox label="Add Signed-off-by" inputRef={signoffRef} />
+            <LabelledCheckBox label="Bypass hooks" inputRef={noVerifyRef} />
+            <LabelledCheckBox label="Allow empty" inputRef={allowEmptyRef} />
+          </div>
+          <div className="flex-row-nowrap my-2">
+            <TextField
+              inputRef={authorRef}
+              className="flex-1 mr-2"
+              label="Author"
+              placeholder="Name <mail> (default: user.name and user.email)"
+              slotProps={{
+                inputLabel: { shrink: true }
+              }}
+            />
+            <TextField
+              inputRef={authorDateRef}
+              className="flex-1"
+              label="Author date"
+              placeholder="e.g. 2024-01-02 03:04:05 +0900 (default: now)"
+              slotProps={{
+                inputLabel: { shrink: true }
+              }}
+            />
+          </div>
+          <div className="flex-row-nowrap">
+            <Icon icon="mdi:shield-key-outline" className="mr-2 my-auto text-2xl" />
+            <NativeSelect
+              value={signing}
+              onChange={(e) => setSigning(e.target.value as SigningChoice)}
+              variant="standard"
+              inputProps={{ name: "signing", className: "min-w-80" }}
+            >
+              <option value="default">
+                Default ({signingLabels[defaultSigning]}, from commit.gpgSign)
+              </option>
+              <option value="gpg">Sign with GPG</option>
+              <option value="ssh">Sign with SSH</option>
+              <option value="none">Don't sign</option>
+            </NativeSelect>
+          </div>
+          {effectiveSigning !== "none" && (
+            <div
+              ref={xtermRef}
+              className="border border-highlight bg-console px-2 py-1 m-0 mt-2 h-48"
+            />
+          )}
+          {output.length > 0 && (
+            <pre
+              ref={outputRef}
+              className="border border-highlight bg-console px-2 py-1 m-0 mt-2 h-48 overflow-auto whitespace-pre-wrap"
+            >
+              {output.join("\n")}
+            </pre>
+          )}
         </div>
       </DialogContent>
       <DialogActions>
-        <AcceptButton onClick={invokeCommit} default />
-        <CancelButton />
+        <AcceptButton onClick={invokeCommit} disabled={running} default />
+        <CancelButton disabled={running} />
       </DialogActions>
     </>
   );
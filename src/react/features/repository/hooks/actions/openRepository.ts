@@ -1,5 +1,6 @@
-import type { Getter, Setter } from "jotai";
+import { type Getter, type Setter, useAtomValue } from "jotai";
 import { useAtomCallback } from "jotai/utils";
+import { useCallback } from "react";
 import { addRecentOpenedRepository } from "@/core/state/root";
 import { addAppTab, getAppTabsValue, selectAppTab } from "@/core/state/tabs";
 import { invokeTauriCommand } from "@/core/utils/invokeTauriCommand";
@@ -30,6 +31,9 @@ const fetchHistory = async (repoPath: string, reflogCount: number) => {
       author: user.name,
       mailAddress: user.email,
       date: Date.now(),
+      committer: user.name,
+      committerMailAddress: user.email,
+      commitDate: Date.now(),
       summary: "<Working tree>",
       parentIds: [rawRefs.head, ...rawRefs.mergeHeads]
     });
@@ -44,14 +48,15 @@ const fetchHistory = async (repoPath: string, reflogCount: number) => {
 };
 
 const makeRefs = (rawRefs: RawRefs): Refs => {
-  const { head, mergeHeads, refs: refArray } = rawRefs;
+  const { head, mergeHeads, refs: refArray, worktrees } = rawRefs;
   const refs: Refs = {
     head,
     mergeHeads,
     branches: [],
     tags: [],
     remotes: {},
-    refsById: {}
+    refsById: {},
+    worktrees
   };
   for (const r of refArray) {
     switch (r.type) {
@@ -71,12 +76,15 @@ const makeRefs = (rawRefs: RawRefs): Refs => {
     // biome-ignore lint/suspicious/noAssignInExpressions: lazy initialization of array
     (refs.refsById[r.id] ?? (refs.refsById[r.id] = [])).push(r);
   }
-  const types: Ref["type"][] = ["branch", "tag", "remote", "reflog"];
+  const types: Ref["type"][] = ["bisect", "branch", "tag", "remote", "stash", "reflog"];
   const compare = (a: Ref, b: Ref) => {
     if (a.type === b.type) {
       if (a.type === "branch" && b.type === "branch" && a.current !== b.current) {
         return a.current ? -1 : 1;
-      } else if (a.type === "reflog" && b.type === "reflog") {
+      } else if (
+        (a.type === "reflog" && b.type === "reflog") ||
+        (a.type === "stash" && b.type === "stash")
+      ) {
         return a.index - b.index;
       } else {
         return a.fullname.localeCompare(b.fullname);
@@ -130,6 +138,18 @@ export const useOpenRepository = () => {
   );
 };
 
+/**
+ * Opens a submodule of the current repository as a separate repository.
+ */
+export const useOpenSubmodule = () => {
+  const repoPath = useAtomValue(repoPathAtom);
+  const openRepository = useOpenRepository();
+  return useCallback(
+    (path: string) => openRepository(`${repoPath}/${path}`),
+    [repoPath, openRepository]
+  );
+};
+
 const reloadSpecifiedRepository = async (get: Getter, _set: Setter, path: string) => {
   const store = get(repositoryStoresAtomFamily(path));
   const reflogCount = store.get(reflogAtom) ? 26 : 0;
@@ -1,15 +1,55 @@
-import { useAtomValue, useSetAtom } from "jotai";
+import type { CommandError } from "@backend/CommandError";
+import { type Getter, type Setter, useAtomValue, useSetAtom } from "jotai";
+import { useAtomCallback } from "jotai/utils";
 import { useCallback } from "react";
 import { useAlert } from "@/core/context/AlertContext";
 import { useConfirmDialog } from "@/core/context/ConfirmDialogContext";
 import { useDialog } from "@/core/context/DialogContext";
+import { useLoading } from "@/core/context/LoadingContext";
+import { withCommandOutput } from "@/core/utils/commandOutput";
 import { invokeTauriCommand } from "@/core/utils/invokeTauriCommand";
 import { CommitDialogBody } from "@/features/repository/components/dialogs/CommitDialogBody";
-import { repoPathAtom } from "@/features/repository/state";
+import { repoPathAtom, repositoryStoresAtomFamily } from "@/features/repository/state";
 import { workingTreeAtom } from "@/features/repository/state/workingtree";
+import { XtermDialogBody } from "@/shared/components/shell/XtermDialogBody";
+import { DialogSection } from "@/shared/components/ui/Dialog";
+import { useExecuteCommitInXterm } from "@/shared/hooks/shell/useXterm";
+import { useXtermDialog } from "@/shared/hooks/shell/useXtermDialog";
 import { useCallbackWithErrorHandler } from "@/shared/hooks/utils/useCallbackWithErrorHandler";
+import { isCommandError, shortHash } from "@/shared/utils/util";
 import { useReloadRepository } from "./openRepository";
 
+const fetchWorkingTreeStat = async (repoPath: string): Promise<WorkingTreeStat> => {
+  const [stat, user] = await Promise.all([
+    invokeTauriCommand("get_workingtree_stat", {
+      repoPath
+    }),
+    invokeTauriCommand("get_user_info", { repoPath })
+  ]);
+  const files: Record<WorkingTreeFileKind["type"], WorkingTreeFileEntry[]> = {
+    unmerged: [],
+    unstaged: [],
+    staged: []
+  };
+  for (const file of stat.files) {
+    files[file.kind.type].push(file);
+  }
+  return {
+    id: "--",
+    author: user.name,
+    mailAddress: user.email,
+    summary: "<Working tree>",
+    date: Date.now(),
+    committer: user.name,
+    committerMailAddress: user.email,
+    commitDate: Date.now(),
+    parentIds: stat.parentIds,
+    unmergedFiles: files.unmerged,
+    unstagedFiles: files.unstaged,
+    stagedFiles: files.staged
+  };
+};
+
 export const useReloadWorkingTree = () => {
   const repoPath = useAtomValue(repoPathAtom);
   const setWorkingTree = useSetAtom(workingTreeAtom);
@@ -17,35 +57,28 @@ export const useReloadWorkingTree = () => {
     if (!repoPath) {
       return;
     }
-    const [stat, user] = await Promise.all([
-      invokeTauriCommand("get_workingtree_stat", {
-        repoPath
-      }),
-      invokeTauriCommand("get_user_info", { repoPath })
-    ]);
-    const files: Record<WorkingTreeFileKind["type"], WorkingTreeFileEntry[]> = {
-      unmerged: [],
-      unstaged: [],
-      staged: []
-    };
-    for (const file of stat.files) {
-      files[file.kind.type].push(file);
-    }
-    const value: WorkingTreeStat = {
-      id: "--",
-      author: user.name,
-      mailAddress: user.email,
-      summary: "<Working tree>",
-      date: Date.now(),
-      parentIds: stat.parentIds,
-      unmergedFiles: files.unmerged,
-      unstagedFiles: files.unstaged,
-      stagedFiles: files.staged
-    };
+    const value = await fetchWorkingTreeStat(repoPath);
     setWorkingTree({ repoPath, value });
   }, [repoPath, setWorkingTree]);
 };
 
+/**
+ * Reloads the working tree of the specified repository, only if it has been loaded.
+ * (otherwise, it will be loaded when the working tree is selected)
+ */
+export const useReloadSpecifiedWorkingTree = () => {
+  return useAtomCallback(
+    useCallbackWithErrorHandler(async (get: Getter, _set: Setter, repoPath: string) => {
+      const store = get(repositoryStoresAtomFamily(repoPath));
+      if (!store.get(workingTreeAtom)) {
+        return;
+      }
+      const value = await fetchWorkingTreeStat(repoPath);
+      store.set(workingTreeAtom, { repoPath, value });
+    }, [])
+  );
+};
+
 export const useStage = () => {
   const repoPath = useAtomValue(repoPathAtom);
   const reloadWorkingTree = useReloadWorkingTree();
@@ -114,12 +147,61 @@ export const useBeginCommit = () => {
   }, [repoPath, dialog]);
 };
 
+const useConfirmBypassHooks = () => {
+  const confirm = useConfirmDialog();
+  return useCallback(
+    async (error: CommandError) => {
+      const ret = await confirm.showModal({
+        title: `${error.hook} hook failed`,
+        content: (
+          <>
+            <pre className="bg-console px-2 py-1 m-0 mb-2 max-h-96 overflow-auto whitespace-pre-wrap">
+              {error.stderr}
+            </pre>
+            Run again without hooks?
+          </>
+        ),
+        buttons: ["Bypass hooks", "Cancel"],
+        defaultButton: "reject"
+      });
+      return ret === "accepted";
+    },
+    [confirm]
+  );
+};
+
 export const useCommit = () => {
   const repoPath = useAtomValue(repoPathAtom);
   const reloadRepository = useReloadRepository();
+  const loading = useLoading();
   const { showWarning } = useAlert();
+  const { execute, kill, isRunning } = useExecuteCommitInXterm();
+  const xtermDialog = useXtermDialog({ isRunning });
+
+  const confirmBypassHooks = useConfirmBypassHooks();
+
+  const invokeCommit = useCallback(
+    async (options: CommitOptions, onOutput?: (line: string) => void) => {
+      // output of hooks is shown by `onOutput` if specified, instead of the loading indicator
+      if (!onOutput) {
+        loading.show();
+      }
+      try {
+        await withCommandOutput(
+          (id) => invokeTauriCommand("commit", { id, repoPath, options }),
+          onOutput ?? (() => {})
+        );
+      } finally {
+        if (!onOutput) {
+          loading.hide();
+        }
+      }
+    },
+    [repoPath, loading]
+  );
+
   return useCallbackWithErrorHandler(
-    async (options: CommitOptions) => {
+    async (options: CommitOptions, xtermEl?: HTMLDivElement, onOutput?: (line: string) => void) => {
       if (options.commitType === "normal" && !options.message) {
         showWarning("Input commit message");
         return false;
@@ -127,21 +209,75 @@ export const useCommit = () => {
       if (!repoPath) {
         return false;
       }
-      const stat = await invokeTauriCommand("get_workingtree_stat", { repoPath });
-      if (options.commitType === "normal" && stat.files.every((f) => f.kind.type !== "staged")) {
-        showWarning("Nothing to commit");
-        return false;
+      loading.show();
+      let signing: CommitSigning;
+      try {
+        const stat = await invokeTauriCommand("get_workingtree_stat", { repoPath });
+        if (
+          options.commitType !== "amend" &&
+          !options.allowEmpty &&
+          stat.files.every((f) => f.kind.type !== "staged")
+        ) {
+          showWarning("Nothing to commit");
+          return false;
+        }
+        if (stat.files.some((f) => f.statusCode === "U")) {
+          showWarning("One or more files are still unmerged");
+          return false;
+        }
+        signing = await invokeTauriCommand("get_commit_signing", {
+          repoPath,
+          signing: options.signing
+        });
+      } finally {
+        loading.hide();
       }
-      if (stat.files.some((f) => f.statusCode === "U")) {
-        showWarning("One or more files are still unmerged");
-        return false;
+      if (signing === "none") {
+        try {
+          await invokeCommit(options, onOutput);
+        } catch (error) {
+          if (!isCommandError(error) || error.kind !== "hookFailed" || options.noVerify) {
+            throw error;
+          }
+          if (!(await confirmBypassHooks(error))) {
+            return false;
+          }
+          await invokeCommit({ ...options, noVerify: true }, onOutput);
+        }
+        await reloadRepository();
+        return true;
+      }
+      // gpg (pinentry) or ssh-keygen may prompt for the passphrase in the terminal
+      let succeeded = false;
+      const openXterm = async (el: HTMLDivElement) => {
+        const status = await execute(el, { repoPath, options }, { onSucceeded: reloadRepository });
+        succeeded = status === "succeeded";
+        return status;
+      };
+      if (xtermEl) {
+        await openXterm(xtermEl);
+      } else {
+        await xtermDialog.showModal(
+          <XtermDialogBody title="Commit" openXterm={openXterm} killPty={kill} startImmediate>
+            <DialogSection label="Signing">
+              Enter the passphrase of the signing key if asked
+            </DialogSection>
+          </XtermDialogBody>
+        );
       }
-      await invokeTauriCommand("commit", { repoPath, options });
-      await reloadRepository();
-      return true;
+      return succeeded;
     },
-    [repoPath, reloadRepository, showWarning],
-    { loading: true }
+    [
+      repoPath,
+      reloadRepository,
+      loading,
+      showWarning,
+      invokeCommit,
+      confirmBypassHooks,
+      execute,
+      kill,
+      xtermDialog
+    ]
   );
 };
 
@@ -163,3 +299,25 @@ export const useFixup = () => {
     await commit({ commitType: "amend" });
   }, [repoPath, confirm, commit]);
 };
+
+export const useCreateFixupCommit = () => {
+  const repoPath = useAtomValue(repoPathAtom);
+  const confirm = useConfirmDialog();
+  const commit = useCommit();
+  return useCallback(
+    async (target: Commit) => {
+      if (!repoPath) {
+        return;
+      }
+      const ret = await confirm.showModal({
+        title: `Create fixup commit for ${shortHash(target.id)}`,
+        content: `Commit staged changes as "fixup! ${target.summary}" (for rebase --autosquash)`
+      });
+      if (ret !== "accepted") {
+        return;
+      }
+      await commit({ commitType: "fixup", target: target.id });
+    },
+    [repoPath, confirm, commit]
+  );
+};
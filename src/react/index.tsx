@@ -39,6 +39,7 @@ import {
   useOpenRepository,
   useReloadSpecifiedRepository
 } from "./features/repository/hooks/actions/openRepository";
+import { useReloadSpecifiedWorkingTree } from "./features/repository/hooks/actions/workingtree";
 import { lazy } from "./shared/components/hoc/lazy";
 import { Cmd, CommandGroup } from "./shared/components/ui/CommandGroup";
 import {
@@ -191,16 +192,22 @@ const App = ({
   const config = useConfigValue();
   const [, openRepository] = useWithRef(useOpenRepository());
   const reloadRepository = useReloadSpecifiedRepository();
+  const reloadWorkingTree = useReloadSpecifiedWorkingTree();
   const theme = useMemo(() => createMuiTheme(config.fontSize), [config.fontSize]);
   const [, reportError] = useWithRef(useAlert().reportError);
   const [initializing, setInitializing] = useState(true);
   useEffect(() => {
-    void listen<string>("request_reload", (e) => {
-      void reloadRepository(e.payload);
+    void listen<ReloadRequest>("request_reload", (e) => {
+      const { repoPath, refs, index, workingTree } = e.payload;
+      if (refs) {
+        void reloadRepository(repoPath);
+      } else if (index || workingTree) {
+        void reloadWorkingTree(repoPath);
+      }
     }).then((unlisten) => {
       window.addEventListener("unload", unlisten);
     });
-  }, [reloadRepository]);
+  }, [reloadRepository, reloadWorkingTree]);
   useEffect(() => {
     (async () => {
       if (startupRepository) {
@@ -203,3 +203,19 @@ const executeCustomCommand = (
   });
 
 export const useExecuteCustomCommandInXterm = () => useExecuteInXterm(executeCustomCommand, false);
+
+const executeCommit = (
+  id: PtyId,
+  rows: number,
+  cols: number,
+  options: { repoPath: string; options: CommitOptions }
+) =>
+  invokeTauriCommand("commit_with_pty", {
+    id,
+    rows,
+    cols,
+    repoPath: options.repoPath,
+    options: options.options
+  });
+
+export const useExecuteCommitInXterm = () => useExecuteInXterm(executeCommit, true);
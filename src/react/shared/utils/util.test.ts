@@ -77,6 +77,15 @@ describe("serializeError", () => {
     const result = serializeError("something went wrong");
     expect(result.message).toBe("something went wrong");
   });
+  it("serializes command error with hint", () => {
+    const result = serializeError({
+      kind: "exitCodeNonZero",
+      message: "GitError(commit,rc=128) fatal: Unable to create '.git/index.lock': File exists.",
+      hint: "indexLocked"
+    });
+    expect(result.name).toBeUndefined();
+    expect(result.message).toContain("remove .git/index.lock");
+  });
   it("serializes unknown value", () => {
     const result = serializeError(42);
     expect(result.message).toBe("42");
@@ -1,3 +1,5 @@
+import type { CommandError } from "@backend/CommandError";
+import type { ErrorHint } from "@backend/ErrorHint";
 import { type DebouncedFunc, throttle } from "lodash";
 
 export const assertNever = (_: never): never => {
@@ -25,8 +27,26 @@ export const getExtension = (pathOrFileName: string): string => {
   return 0 <= p ? fileName.slice(p) : "";
 };
 
+const errorHintMessages: Record<ErrorHint, string> = {
+  notARepository: "The folder is not a git repository.",
+  indexLocked:
+    "Another git process seems to be running. If not, remove .git/index.lock and try again.",
+  mergeConflict: "Resolve the conflicts first.",
+  authenticationRequired: "Authentication is required. Configure credentials for the remote.",
+  signingFailed:
+    "Failed to sign the commit. Check the signing key (user.signingKey), or commit without signing."
+};
+
+export const isCommandError = (error: unknown): error is CommandError =>
+  typeof error === "object" && error !== null && "kind" in error && "message" in error;
+
 export const serializeError = (error: unknown): ErrorLike => {
-  if (typeof error === "object" && error !== null) {
+  if (isCommandError(error)) {
+    return {
+      message: error.hint ? `${error.message}\n${errorHintMessages[error.hint]}` : error.message,
+      stack: undefined
+    };
+  } else if (typeof error === "object" && error !== null) {
     return {
       name: "name" in error ? (error.name as string) : "Unknown",
       message: "message" in error ? (error.message as string) : JSON.stringify(error),